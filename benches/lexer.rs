@@ -0,0 +1,171 @@
+use std::time::{Duration, Instant};
+
+use blaze::scripting::lexer::Lexer;
+use regex::Regex;
+
+// A model file with many tables, enums and functions, repeated until it's large enough.
+const MODEL: &str = r#"
+/// Products the shop sells.
+table products {
+    name: str < 64,
+    price: float >= 0 = 0,
+    category: &categories?;
+};
+enum Level: uint { Low, Medium, High };
+event product_update (&products.price, "change") {
+    &products.name = "updated";
+};
+function get_total_price(products: &products[]): float {
+    mut total: float = 0;
+    product of products {
+        if product.price >= 10 && product.price != 20 { total += product.price } else { total -= 1 };
+    };
+    total /* nested /* block */ comment */
+};
+"#;
+
+// The patterns of the lexer that came before the scanner, in the order it tried them.
+// It compiled all of them again at every position, which is why it's only run on small files.
+const BASELINE_PATTERNS: [&str; 65] = [
+    r"///([^/\n][^\n]*)?(?m:$)",
+    r"//[^\n]*",
+    r"package\b",
+    r"import\b",
+    r"manage\b",
+    r"attach\b",
+    r"inspect\b",
+    r"if\b",
+    r"elif\b",
+    r"else\b",
+    r"while\b",
+    r"for\b",
+    r"of\b",
+    r"continue\b",
+    r"break\b",
+    r"return\b",
+    r"\+\+",
+    r"\-\-",
+    r"\+=",
+    r"-=",
+    r"\*=",
+    r"\/=",
+    r"\+",
+    r"-",
+    r"\*",
+    r"\/",
+    r"==",
+    r"\!=",
+    r">=",
+    r"<=",
+    r">",
+    r"<",
+    r"\^",
+    r"&&",
+    r"\|\|",
+    r"\!",
+    r"&",
+    r"=",
+    r"mut\b",
+    r"fin\b",
+    r"function\b",
+    r"enum\b",
+    r"table\b",
+    r"event\b",
+    r"\(",
+    r"\)",
+    r"\{",
+    r"\}",
+    r"\[",
+    r"\]",
+    r#"".*?[^\\]"|"""#,
+    r"\d+(\.\d+)?",
+    r#"\s"#,
+    r"\.",
+    r",",
+    r":",
+    r"\?",
+    r"true\b",
+    r"false\b",
+    r"null\b",
+    r"[a-zA-Z_]\w*",
+    r"\n",
+    r"\t",
+    r"\r",
+    r";",
+];
+
+fn analyze_with_baseline(code: &str) {
+    let mut position = 0;
+    while position < code.len() {
+        let rest = &code[position..];
+        let length = match rest.starts_with("/*") {
+            true => get_block_comment_length(rest),
+            false => BASELINE_PATTERNS
+                .iter()
+                .find_map(|pattern| {
+                    let regex = Regex::new(&format!("^(?:{})", pattern)).unwrap();
+                    regex.find(rest).map(|found| found.len())
+                })
+                .unwrap(),
+        };
+        position += length;
+    }
+}
+
+fn get_block_comment_length(code: &str) -> usize {
+    let mut depth = 0;
+    let mut length = 0;
+    loop {
+        let rest = &code[length..];
+        if rest.starts_with("/*") {
+            depth += 1;
+            length += 2;
+        } else if rest.starts_with("*/") {
+            depth -= 1;
+            length += 2;
+            if depth == 0 {
+                return length;
+            }
+        } else {
+            length += rest.chars().next().unwrap().len_utf8();
+        }
+    }
+}
+
+fn report(lexer_name: &str, size: &str, code_length: usize, elapsed: Duration) {
+    println!(
+        "{:>8} {:>5}: {:>8.2?} ({:.1} KB/s)",
+        lexer_name,
+        size,
+        elapsed,
+        code_length as f64 / 1024.0 / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    for size in [4, 16] {
+        let code = MODEL.repeat(size * 1024 / MODEL.len() + 1);
+        let size = format!("{} KB", size);
+        let started_at = Instant::now();
+        analyze_with_baseline(&code);
+        report("baseline", &size, code.len(), started_at.elapsed());
+
+        let code_length = code.len();
+        let started_at = Instant::now();
+        Lexer::new(code).analyze().unwrap();
+        report("scanner", &size, code_length, started_at.elapsed());
+    }
+    for size in [1, 4, 16] {
+        let megabytes = size * 1024 * 1024;
+        let code = MODEL.repeat(megabytes / MODEL.len() + 1);
+        let code_length = code.len();
+        let started_at = Instant::now();
+        Lexer::new(code).analyze().unwrap();
+        report(
+            "scanner",
+            &format!("{} MB", size),
+            code_length,
+            started_at.elapsed(),
+        );
+    }
+}
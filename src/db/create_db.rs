@@ -32,12 +32,12 @@ fn create_db_folders(db_path_buf: &Path) -> Result<()> {
 fn create_manage_file(path_to_db_buf: &Path) -> Result<bool> {
     let mut managing_file_path_buf = path_to_db_buf.to_path_buf();
     let manage_file_content = br#"manage (
-    tmax_connections = 3,
+    max_connections = 3,
     work_dir = "/",
     backups_dir = "backups/"
 );
 
-attach "/data/main;"#;
+attach "/data/main";"#;
 
     managing_file_path_buf.push("manage.blz");
     if let Err(e) = fs::metadata(managing_file_path_buf.to_str().unwrap()) {
@@ -0,0 +1,162 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::scripting::ast::body::BodyNode;
+use crate::scripting::ast::expression::ExpressionNode;
+use crate::scripting::ast::manage::ManageNode;
+use crate::scripting::ast::number::NumberNode;
+use crate::scripting::ast::string::StringNode;
+use crate::scripting::lexer::Lexer;
+use crate::scripting::parser::Parser;
+
+// The folder a new datablaze keeps its model in.
+const DEFAULT_PACKAGES_DIR: &str = "model";
+const DEFAULT_BACKUPS_DIR: &str = "backups";
+
+// Settings of a datablaze declared by the `manage (...)` block of manage.blz.
+// The settings that are not declared stay empty, so the defaults can be applied by the caller.
+#[derive(Debug, Default, PartialEq)]
+pub struct ManageConfig {
+    pub packages: Option<String>,
+    pub max_connections: Option<usize>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub work_dir: Option<String>,
+    pub backups_dir: Option<String>,
+}
+
+impl ManageConfig {
+    pub fn load(path: &str) -> io::Result<Self> {
+        let code = fs::read_to_string(path)?;
+        let mut code_lexer = Lexer::new(code);
+        code_lexer.get_context().set_code_source(path.to_string());
+        let tokens = code_lexer.analyze()?;
+        let mut code_parser = Parser::new(tokens);
+        code_parser.get_context().set_code_source(path.to_string());
+        let body = code_parser.parse()?;
+        Self::from_body(&body)
+    }
+
+    // The paths of manage.blz are relative to the work directory, and the work directory
+    // is relative to manage.blz itself, so `work_dir = "/"` is the folder of manage.blz.
+    pub fn get_work_dir(&self, manager_file: &Path) -> PathBuf {
+        let directory = manager_file.parent().unwrap_or(Path::new(""));
+        match &self.work_dir {
+            Some(work_dir) => directory.join(work_dir.trim_start_matches('/')),
+            None => directory.to_path_buf(),
+        }
+    }
+
+    pub fn get_backups_dir(&self, manager_file: &Path) -> PathBuf {
+        let backups_dir = self.backups_dir.as_deref().unwrap_or(DEFAULT_BACKUPS_DIR);
+        self.get_work_dir(manager_file)
+            .join(backups_dir.trim_start_matches('/'))
+    }
+
+    // Without the setting, packages are in the model folder if the datablaze has one,
+    // as a datablaze doesn't need any packages.
+    pub fn get_packages_dir(&self, manager_file: &Path) -> Option<PathBuf> {
+        let directory = self.get_work_dir(manager_file);
+        match &self.packages {
+            Some(packages) => Some(directory.join(packages)),
+            None => Some(directory.join(DEFAULT_PACKAGES_DIR)).filter(|path| path.is_dir()),
+        }
+    }
+
+    pub fn from_body(body: &BodyNode) -> io::Result<Self> {
+        let mut manage_nodes = body
+            .nodes
+            .iter()
+            .filter_map(|node| node.downcast_ref::<ManageNode>());
+        let manage_node = manage_nodes
+            .next()
+            .ok_or_else(|| configuration_error("no manage block is declared"))?;
+        if manage_nodes.next().is_some() {
+            return Err(configuration_error("only one manage block can be declared"));
+        }
+        Self::from_node(manage_node)
+    }
+
+    pub fn from_node(manage_node: &ManageNode) -> io::Result<Self> {
+        let mut config = ManageConfig::default();
+        let mut declared_names: Vec<&str> = vec![];
+        for setting in manage_node.get_settings() {
+            // Datablazes created before the setting was renamed declare 'tmax_connections'.
+            let name = match setting.get_name() {
+                "tmax_connections" => "max_connections",
+                name => name,
+            };
+            if setting.is_positional() {
+                return Err(configuration_error(
+                    "settings must be named, e.g. 'port = 6980'",
+                ));
+            }
+            if declared_names.contains(&name) {
+                return Err(configuration_error(format!(
+                    "'{}' is declared more than once",
+                    name
+                )));
+            }
+            declared_names.push(name);
+
+            let value = setting.get_value().unwrap();
+            match name {
+                "packages" => config.packages = Some(expect_string(name, value)?),
+                "host" => config.host = Some(expect_string(name, value)?),
+                "work_dir" => config.work_dir = Some(expect_string(name, value)?),
+                "backups_dir" => config.backups_dir = Some(expect_string(name, value)?),
+                "max_connections" => {
+                    let max_connections = expect_integer(name, value)?;
+                    if max_connections == 0 {
+                        return Err(configuration_error("'max_connections' must be positive"));
+                    }
+                    config.max_connections = Some(max_connections as usize);
+                }
+                // Ports are often written as strings, so both forms are accepted.
+                "port" => {
+                    let port = match value.downcast_ref::<StringNode>() {
+                        Some(string) => string.get_value().parse::<u64>().map_err(|_| {
+                            configuration_error(format!(
+                                "'port' must be a number, found \"{}\"",
+                                string.get_value()
+                            ))
+                        })?,
+                        None => expect_integer(name, value)?,
+                    };
+                    let port = u16::try_from(port)
+                        .ok()
+                        .filter(|port| *port != 0)
+                        .ok_or_else(|| {
+                            configuration_error(format!("'port' {} is out of range", port))
+                        })?;
+                    config.port = Some(port);
+                }
+                _ => return Err(configuration_error(format!("unknown setting '{}'", name))),
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn configuration_error(message: impl AsRef<str>) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Configuration Error: {}", message.as_ref()),
+    )
+}
+
+fn expect_string(name: &str, value: &dyn ExpressionNode) -> io::Result<String> {
+    value
+        .downcast_ref::<StringNode>()
+        .map(|string| string.get_value().to_string())
+        .ok_or_else(|| configuration_error(format!("'{}' must be a string", name)))
+}
+
+fn expect_integer(name: &str, value: &dyn ExpressionNode) -> io::Result<u64> {
+    value
+        .downcast_ref::<NumberNode>()
+        .and_then(|number| number.get_value().to_i128())
+        .and_then(|number| u64::try_from(number).ok())
+        .ok_or_else(|| configuration_error(format!("'{}' must be a non-negative integer", name)))
+}
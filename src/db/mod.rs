@@ -1 +1,2 @@
 pub mod create_db;
+pub mod manage;
@@ -0,0 +1,26 @@
+use super::expression::ExpressionNode;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayNode {
+    items: Vec<Box<dyn ExpressionNode>>,
+}
+
+impl ExpressionNode for ArrayNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(ArrayNode)
+    }
+}
+
+impl ArrayNode {
+    pub fn new(items: Vec<Box<dyn ExpressionNode>>) -> Self {
+        ArrayNode { items }
+    }
+
+    pub fn get_items(&self) -> &Vec<Box<dyn ExpressionNode>> {
+        &self.items
+    }
+
+    pub fn get_items_mut(&mut self) -> &mut Vec<Box<dyn ExpressionNode>> {
+        &mut self.items
+    }
+}
@@ -0,0 +1,22 @@
+use super::expression::ExpressionNode;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachNode {
+    path: String,
+}
+
+impl ExpressionNode for AttachNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(AttachNode)
+    }
+}
+
+impl AttachNode {
+    pub fn new(path: String) -> Self {
+        AttachNode { path }
+    }
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+}
@@ -1,11 +1,14 @@
+use crate::scripting::span::{NodeSpan, Span};
 use crate::scripting::tokens::TokenType;
 
 use super::expression::ExpressionNode;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct BinaryOperatorNode {
-    _operator: TokenType,
-    _left_operand: Box<dyn ExpressionNode>,
-    _right_operand: Box<dyn ExpressionNode>,
+    operator: TokenType,
+    left_operand: Box<dyn ExpressionNode>,
+    right_operand: Box<dyn ExpressionNode>,
+    span: NodeSpan,
 }
 
 impl BinaryOperatorNode {
@@ -15,11 +18,41 @@ impl BinaryOperatorNode {
         right_operand: Box<dyn ExpressionNode>,
     ) -> Self {
         BinaryOperatorNode {
-            _operator: operator,
-            _left_operand: left_operand,
-            _right_operand: right_operand,
+            operator,
+            left_operand,
+            right_operand,
+            span: NodeSpan::default(),
         }
     }
+
+    pub fn get_operator(&self) -> &TokenType {
+        &self.operator
+    }
+
+    pub fn get_left_operand(&self) -> &dyn ExpressionNode {
+        self.left_operand.as_ref()
+    }
+
+    pub fn get_left_operand_mut(&mut self) -> &mut Box<dyn ExpressionNode> {
+        &mut self.left_operand
+    }
+
+    pub fn get_right_operand(&self) -> &dyn ExpressionNode {
+        self.right_operand.as_ref()
+    }
+
+    pub fn get_right_operand_mut(&mut self) -> &mut Box<dyn ExpressionNode> {
+        &mut self.right_operand
+    }
+
+    // From the start of the left operand to the end of the right one.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span.0
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = NodeSpan(Some(span));
+    }
 }
 
 impl ExpressionNode for BinaryOperatorNode {
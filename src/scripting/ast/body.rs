@@ -1,22 +1,62 @@
 use super::expression::ExpressionNode;
+use crate::scripting::span::Span;
 
+#[derive(Debug, Clone)]
 pub struct BodyNode {
     pub nodes: Vec<Box<dyn ExpressionNode>>,
+    // Where each of the nodes is written, when the body comes from the parser.
+    spans: Vec<Option<Span>>,
+    // From '{' to '}', for bodies written in brackets.
+    span: Option<Span>,
 }
 
 impl BodyNode {
     pub fn new() -> Self {
-        BodyNode { nodes: vec![] }
+        BodyNode {
+            nodes: vec![],
+            spans: vec![],
+            span: None,
+        }
     }
 
     pub fn add_node(&mut self, node: Box<dyn ExpressionNode>) {
         self.nodes.push(node);
+        self.spans.push(None);
+    }
+
+    pub fn add_spanned_node(&mut self, node: Box<dyn ExpressionNode>, span: Span) {
+        self.nodes.push(node);
+        self.spans.push(Some(span));
+    }
+
+    pub fn append(&mut self, other: BodyNode) {
+        self.nodes.extend(other.nodes);
+        self.spans.extend(other.spans);
+    }
+
+    pub fn get_node_span(&self, index: usize) -> Option<Span> {
+        self.spans.get(index).copied().flatten()
+    }
+
+    pub fn get_span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = Some(span);
+    }
+}
+
+// Spans are left out, so the same code written in different places gives equal bodies.
+impl PartialEq for BodyNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.nodes == other.nodes
     }
 }
 
 impl ExpressionNode for BodyNode {
     fn get_type(&self) -> &'static str {
-        stringify!(ExpressionNode)
+        stringify!(BodyNode)
     }
 }
 
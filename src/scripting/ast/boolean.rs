@@ -4,21 +4,23 @@ use crate::scripting::tokens::TokenType;
 
 use super::expression::ExpressionNode;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct BooleanNode {
-    _state: bool,
+    state: bool,
 }
 
 impl BooleanNode {
     pub fn new(token_type: TokenType) -> Result<Self, io::Error> {
         match token_type {
-            TokenType::True => Ok(BooleanNode { _state: true }),
-            TokenType::False => Ok(BooleanNode { _state: false }),
-            _ => Err(io::Error::new(
-                io::ErrorKind::Other,
-                "Invalid value for a boolean node",
-            )),
+            TokenType::True => Ok(BooleanNode { state: true }),
+            TokenType::False => Ok(BooleanNode { state: false }),
+            _ => Err(io::Error::other("Invalid value for a boolean node")),
         }
     }
+
+    pub fn get_state(&self) -> bool {
+        self.state
+    }
 }
 
 impl ExpressionNode for BooleanNode {
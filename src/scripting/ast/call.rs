@@ -1,15 +1,43 @@
 use super::{expression::ExpressionNode, parameter::Parameters};
+use crate::scripting::span::{NodeSpan, Span};
 
+// The built-in types that can be called to cast a value, like `int(x)`.
+pub const BUILTIN_DATATYPES: [&str; 9] = [
+    "str", "int", "uint", "float", "decimal", "bool", "datetime", "uuid", "geo",
+];
+
+// Position calls get an item with `items[0]`, where the index is a positional argument,
+// or a slice with `items[1:3]`, where the bounds are the `start` and `stop` arguments.
+// Type calls cast values, like `int(x)`, or build rows, like `accounts(name = "x")`.
+// The start and stop of a slice, which are None when left out.
+pub type SliceBounds<'a> = (
+    Option<&'a dyn ExpressionNode>,
+    Option<&'a dyn ExpressionNode>,
+);
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum CallType {
     Function,
     Position,
     Type,
 }
 
+impl CallType {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            CallType::Function => "function",
+            CallType::Position => "position",
+            CallType::Type => "type",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct CallNode {
-    _callee: Box<dyn ExpressionNode>,
-    _arguments: Parameters,
-    _call_type: CallType,
+    callee: Box<dyn ExpressionNode>,
+    arguments: Parameters,
+    call_type: CallType,
+    span: NodeSpan,
 }
 
 impl ExpressionNode for CallNode {
@@ -21,9 +49,54 @@ impl ExpressionNode for CallNode {
 impl CallNode {
     pub fn new(callee: Box<dyn ExpressionNode>, arguments: Parameters) -> Self {
         CallNode {
-            _callee: callee,
-            _arguments: arguments,
-            _call_type: CallType::Function,
+            callee,
+            arguments,
+            call_type: CallType::Function,
+            span: NodeSpan::default(),
+        }
+    }
+
+    pub fn get_callee(&self) -> &dyn ExpressionNode {
+        self.callee.as_ref()
+    }
+
+    pub fn get_callee_mut(&mut self) -> &mut Box<dyn ExpressionNode> {
+        &mut self.callee
+    }
+
+    pub fn get_arguments(&self) -> &Parameters {
+        &self.arguments
+    }
+
+    pub fn get_arguments_mut(&mut self) -> &mut Parameters {
+        &mut self.arguments
+    }
+
+    pub fn get_call_type(&self) -> &CallType {
+        &self.call_type
+    }
+
+    pub fn set_call_type(&mut self, call_type: CallType) {
+        self.call_type = call_type;
+    }
+
+    // From the start of the callee to the closing ')' or ']'.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span.0
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = NodeSpan(Some(span));
+    }
+
+    // Only slices like `items[1:3]` have bounds.
+    pub fn get_slice_bounds(&self) -> Option<SliceBounds<'_>> {
+        match (
+            self.call_type == CallType::Position,
+            self.arguments.as_slice(),
+        ) {
+            (true, [start, stop]) => Some((start.get_value(), stop.get_value())),
+            _ => None,
         }
     }
 }
@@ -2,9 +2,10 @@ use super::expression::ExpressionNode;
 
 pub type Conditions = Vec<(Box<dyn ExpressionNode>, Box<dyn ExpressionNode>)>;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct ConditionalTreeNode {
-    _conditions: Conditions,
-    _default: Option<Box<dyn ExpressionNode>>,
+    conditions: Conditions,
+    default: Option<Box<dyn ExpressionNode>>,
 }
 
 impl ExpressionNode for ConditionalTreeNode {
@@ -16,8 +17,24 @@ impl ExpressionNode for ConditionalTreeNode {
 impl ConditionalTreeNode {
     pub fn new(conditions: Conditions, default: Option<Box<dyn ExpressionNode>>) -> Self {
         ConditionalTreeNode {
-            _conditions: conditions,
-            _default: default,
+            conditions,
+            default,
         }
     }
+
+    pub fn get_conditions(&self) -> &Conditions {
+        &self.conditions
+    }
+
+    pub fn get_conditions_mut(&mut self) -> &mut Conditions {
+        &mut self.conditions
+    }
+
+    pub fn get_default(&self) -> Option<&dyn ExpressionNode> {
+        self.default.as_deref()
+    }
+
+    pub fn get_default_mut(&mut self) -> Option<&mut Box<dyn ExpressionNode>> {
+        self.default.as_mut()
+    }
 }
@@ -0,0 +1,29 @@
+use super::expression::ExpressionNode;
+
+// The keys and the values of a document in the order they are written.
+pub type DocumentFields = Vec<(String, Box<dyn ExpressionNode>)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentNode {
+    fields: DocumentFields,
+}
+
+impl ExpressionNode for DocumentNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(DocumentNode)
+    }
+}
+
+impl DocumentNode {
+    pub fn new(fields: DocumentFields) -> Self {
+        DocumentNode { fields }
+    }
+
+    pub fn get_fields(&self) -> &DocumentFields {
+        &self.fields
+    }
+
+    pub fn get_fields_mut(&mut self) -> &mut DocumentFields {
+        &mut self.fields
+    }
+}
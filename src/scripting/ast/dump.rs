@@ -0,0 +1,536 @@
+use super::array::ArrayNode;
+use super::attach::AttachNode;
+use super::binary_operator::BinaryOperatorNode;
+use super::body::BodyNode;
+use super::boolean::BooleanNode;
+use super::call::CallNode;
+use super::conditional_tree::ConditionalTreeNode;
+use super::document::DocumentNode;
+use super::enum_declaration::EnumDeclarationNode;
+use super::event_declaration::EventDeclarationNode;
+use super::expression::ExpressionNode;
+use super::for_loop::ForLoopNode;
+use super::function_declaration::FunctionDeclarationNode;
+use super::functional_return::FunctionalReturnNode;
+use super::identifier::IdentifierNode;
+use super::import::ImportNode;
+use super::inspect::InspectNode;
+use super::loop_control::{LoopControlNode, LoopControlType};
+use super::manage::ManageNode;
+use super::member::MemberNode;
+use super::null::NullNode;
+use super::number::NumberNode;
+use super::package::PackageNode;
+use super::parameter::{Parameter, Parameters};
+use super::parenthesized::ParenthesizedNode;
+use super::string::StringNode;
+use super::table_declaration::{Column, TableDeclarationNode};
+use super::unary_operator::UnaryOperatorNode;
+use super::variable_declaration::VariableDeclaration;
+use super::visitor::Visitor;
+use super::while_loop::WhileLoopNode;
+use crate::scripting::span::Span;
+use crate::scripting::tokens::{TokenSide, TokenType};
+
+// S-expressions that don't fit into this width are written one field per line.
+const MAX_SEXPR_WIDTH: usize = 80;
+
+// The AST as plain data, so it can be written as JSON or as an S-expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tree {
+    Node(&'static str, Vec<(&'static str, Tree)>),
+    List(Vec<Tree>),
+    String(String),
+    Number(f64),
+    Boolean(bool),
+    Span(Span),
+    Null,
+}
+
+impl Tree {
+    pub fn from_body(body: &BodyNode) -> Self {
+        let mut builder = TreeBuilder { trees: vec![] };
+        builder.visit_body(body);
+        builder.trees.pop().unwrap()
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json, 0);
+        json
+    }
+
+    pub fn to_sexpr(&self) -> String {
+        let mut sexpr = String::new();
+        self.write_sexpr(&mut sexpr, 0);
+        sexpr
+    }
+
+    fn write_json(&self, json: &mut String, indent: usize) {
+        let inner_indent = "  ".repeat(indent + 1);
+        match self {
+            Tree::Node(kind, fields) => {
+                json.push_str(&format!("{{\n{}\"type\": {}", inner_indent, quote(kind)));
+                for (name, value) in fields {
+                    json.push_str(&format!(",\n{}\"{}\": ", inner_indent, name));
+                    value.write_json(json, indent + 1);
+                }
+                json.push_str(&format!("\n{}}}", "  ".repeat(indent)));
+            }
+            Tree::List(items) if items.is_empty() => json.push_str("[]"),
+            Tree::List(items) => {
+                json.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        json.push(',');
+                    }
+                    json.push('\n');
+                    json.push_str(&inner_indent);
+                    item.write_json(json, indent + 1);
+                }
+                json.push_str(&format!("\n{}]", "  ".repeat(indent)));
+            }
+            Tree::Span(span) => json.push_str(&format!(
+                "{{\"start\": {}, \"stop\": {}, \"line\": {}, \"column\": {}}}",
+                span.start, span.stop, span.line, span.column
+            )),
+            Tree::Null => json.push_str("null"),
+            leaf => json.push_str(&leaf.to_flat_sexpr()),
+        }
+    }
+
+    fn write_sexpr(&self, sexpr: &mut String, indent: usize) {
+        let flat_sexpr = self.to_flat_sexpr();
+        if indent * 2 + flat_sexpr.len() <= MAX_SEXPR_WIDTH {
+            sexpr.push_str(&flat_sexpr);
+            return;
+        }
+        let inner_indent = "  ".repeat(indent + 1);
+        match self {
+            Tree::Node(kind, fields) => {
+                sexpr.push('(');
+                sexpr.push_str(kind);
+                for (name, value) in fields {
+                    sexpr.push_str(&format!("\n{}:{} ", inner_indent, name));
+                    value.write_sexpr(sexpr, indent + 1);
+                }
+                sexpr.push(')');
+            }
+            Tree::List(items) => {
+                sexpr.push('(');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        sexpr.push('\n');
+                        sexpr.push_str(&inner_indent);
+                    }
+                    item.write_sexpr(sexpr, indent + 1);
+                }
+                sexpr.push(')');
+            }
+            _ => sexpr.push_str(&flat_sexpr),
+        }
+    }
+
+    fn to_flat_sexpr(&self) -> String {
+        match self {
+            Tree::Node(kind, fields) => {
+                let mut sexpr = format!("({}", kind);
+                for (name, value) in fields {
+                    sexpr.push_str(&format!(" :{} {}", name, value.to_flat_sexpr()));
+                }
+                sexpr.push(')');
+                sexpr
+            }
+            Tree::List(items) => {
+                let items: Vec<String> = items.iter().map(Tree::to_flat_sexpr).collect();
+                format!("({})", items.join(" "))
+            }
+            Tree::String(value) => quote(value),
+            Tree::Number(value) => value.to_string(),
+            Tree::Boolean(value) => value.to_string(),
+            Tree::Span(span) => format!(
+                "(span {} {} {} {})",
+                span.start, span.stop, span.line, span.column
+            ),
+            Tree::Null => "nil".to_string(),
+        }
+    }
+}
+
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for char in value.chars() {
+        match char {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            char if char.is_control() => quoted.push_str(&format!("\\u{:04x}", char as u32)),
+            _ => quoted.push(char),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn string(value: &str) -> Tree {
+    Tree::String(value.to_string())
+}
+
+fn optional_string(value: Option<&str>) -> Tree {
+    value.map_or(Tree::Null, string)
+}
+
+// The span goes first. A statement replaces the span of its node, which is the same
+// or narrower, so the span isn't written twice.
+fn set_span(fields: &mut Vec<(&'static str, Tree)>, span: Option<Span>) {
+    let Some(span) = span else {
+        return;
+    };
+    match fields.first_mut() {
+        Some(("span", tree)) => *tree = Tree::Span(span),
+        _ => fields.insert(0, ("span", Tree::Span(span))),
+    }
+}
+
+fn operator(token_type: &TokenType) -> Tree {
+    string(token_type.get_text().unwrap_or_default())
+}
+
+// Every visit pushes the tree of the visited node, which its parent then pops.
+struct TreeBuilder {
+    trees: Vec<Tree>,
+}
+
+impl TreeBuilder {
+    fn build(&mut self, node: &dyn ExpressionNode) -> Tree {
+        self.visit_expression(node);
+        self.trees.pop().unwrap()
+    }
+
+    fn build_optional(&mut self, node: Option<&dyn ExpressionNode>) -> Tree {
+        node.map_or(Tree::Null, |node| self.build(node))
+    }
+
+    fn build_body(&mut self, node: &BodyNode) -> Tree {
+        self.visit_body(node);
+        self.trees.pop().unwrap()
+    }
+
+    fn build_parameters(&mut self, parameters: &Parameters) -> Tree {
+        Tree::List(
+            parameters
+                .iter()
+                .map(|parameter| {
+                    self.visit_parameter(parameter);
+                    self.trees.pop().unwrap()
+                })
+                .collect(),
+        )
+    }
+
+    fn push(&mut self, kind: &'static str, fields: Vec<(&'static str, Tree)>) {
+        self.trees.push(Tree::Node(kind, fields));
+    }
+}
+
+impl Visitor for TreeBuilder {
+    fn visit_array(&mut self, node: &ArrayNode) {
+        let items = node
+            .get_items()
+            .iter()
+            .map(|item| self.build(item.as_ref()))
+            .collect();
+        self.push(node.get_type(), vec![("items", Tree::List(items))]);
+    }
+
+    fn visit_attach(&mut self, node: &AttachNode) {
+        self.push(node.get_type(), vec![("path", string(node.get_path()))]);
+    }
+
+    fn visit_binary_operator(&mut self, node: &BinaryOperatorNode) {
+        let mut fields = vec![
+            ("operator", operator(node.get_operator())),
+            ("left", self.build(node.get_left_operand())),
+            ("right", self.build(node.get_right_operand())),
+        ];
+        set_span(&mut fields, node.get_span());
+        self.push(node.get_type(), fields);
+    }
+
+    // Statements carry the spans they are written at.
+    fn visit_body(&mut self, node: &BodyNode) {
+        let mut statements = vec![];
+        for (index, statement) in node.nodes.iter().enumerate() {
+            let mut tree = self.build(statement.as_ref());
+            if let Tree::Node(_, fields) = &mut tree {
+                set_span(fields, node.get_node_span(index));
+            }
+            statements.push(tree);
+        }
+        let mut fields = vec![("statements", Tree::List(statements))];
+        set_span(&mut fields, node.get_span());
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_boolean(&mut self, node: &BooleanNode) {
+        self.push(
+            node.get_type(),
+            vec![("value", Tree::Boolean(node.get_state()))],
+        );
+    }
+
+    fn visit_call(&mut self, node: &CallNode) {
+        let mut fields = vec![
+            ("call_type", string(node.get_call_type().get_name())),
+            ("callee", self.build(node.get_callee())),
+            ("arguments", self.build_parameters(node.get_arguments())),
+        ];
+        set_span(&mut fields, node.get_span());
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_conditional_tree(&mut self, node: &ConditionalTreeNode) {
+        let branches = node
+            .get_conditions()
+            .iter()
+            .map(|(condition, value)| {
+                Tree::Node(
+                    "Branch",
+                    vec![
+                        ("condition", self.build(condition.as_ref())),
+                        ("value", self.build(value.as_ref())),
+                    ],
+                )
+            })
+            .collect();
+        let fields = vec![
+            ("branches", Tree::List(branches)),
+            ("default", self.build_optional(node.get_default())),
+        ];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_document(&mut self, node: &DocumentNode) {
+        let fields = node
+            .get_fields()
+            .iter()
+            .map(|(key, value)| {
+                Tree::Node(
+                    "Field",
+                    vec![("key", string(key)), ("value", self.build(value.as_ref()))],
+                )
+            })
+            .collect();
+        self.push(node.get_type(), vec![("fields", Tree::List(fields))]);
+    }
+
+    fn visit_enum_declaration(&mut self, node: &EnumDeclarationNode) {
+        let variants = node.get_variants().iter().map(|variant| string(variant));
+        let fields = vec![
+            ("name", string(node.get_name())),
+            ("datatype", optional_string(node.get_datatype())),
+            ("variants", Tree::List(variants.collect())),
+            ("doc", optional_string(node.get_doc())),
+        ];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_event_declaration(&mut self, node: &EventDeclarationNode) {
+        let fields = vec![
+            ("name", string(node.get_name())),
+            ("table", string(node.get_table())),
+            ("column", optional_string(node.get_column())),
+            ("change_kind", string(node.get_change_kind().get_name())),
+            ("body", self.build_body(node.get_body())),
+        ];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_for_loop(&mut self, node: &ForLoopNode) {
+        let fields = vec![
+            ("item", string(node.get_item())),
+            ("collection", self.build(node.get_collection())),
+            ("body", self.build_body(node.get_body())),
+        ];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationNode) {
+        let generics = node.get_generics().iter().map(|generic| string(generic));
+        let body = node
+            .get_body()
+            .map_or(Tree::Null, |body| self.build_body(body));
+        let fields = vec![
+            ("name", string(node.get_name())),
+            ("generics", Tree::List(generics.collect())),
+            ("arguments", self.build_parameters(node.get_arguments())),
+            ("datatype", optional_string(node.get_datatype())),
+            ("body", body),
+            ("doc", optional_string(node.get_doc())),
+        ];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_functional_return(&mut self, node: &FunctionalReturnNode) {
+        let fields = vec![("value", self.build_optional(node.get_value()))];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_identifier(&mut self, node: &IdentifierNode) {
+        let mut fields = vec![("name", string(node.get_name()))];
+        set_span(&mut fields, node.get_span());
+        if !node.get_types().is_empty() {
+            let types = node.get_types().iter().map(|datatype| {
+                self.visit_identifier(datatype);
+                self.trees.pop().unwrap()
+            });
+            fields.push(("types", Tree::List(types.collect())));
+        }
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_import(&mut self, node: &ImportNode) {
+        let imports = node.get_imports().iter().map(|import| {
+            Tree::Node(
+                "Import",
+                vec![
+                    ("package", string(import.get_package())),
+                    ("symbol", string(import.get_symbol())),
+                ],
+            )
+        });
+        self.push(
+            node.get_type(),
+            vec![("imports", Tree::List(imports.collect()))],
+        );
+    }
+
+    fn visit_inspect(&mut self, node: &InspectNode) {
+        let targets = node.get_targets().iter().map(|target| string(target));
+        self.push(
+            node.get_type(),
+            vec![("targets", Tree::List(targets.collect()))],
+        );
+    }
+
+    fn visit_loop_control(&mut self, node: &LoopControlNode) {
+        let control = match node.get_control_type() {
+            LoopControlType::Continue => "continue",
+            LoopControlType::Break => "break",
+        };
+        self.push(node.get_type(), vec![("control", string(control))]);
+    }
+
+    fn visit_manage(&mut self, node: &ManageNode) {
+        let fields = vec![("settings", self.build_parameters(node.get_settings()))];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_member(&mut self, node: &MemberNode) {
+        let fields = vec![
+            ("parent", self.build(node.get_parent())),
+            ("child", self.build(node.get_child())),
+        ];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_null(&mut self, node: &NullNode) {
+        self.push(node.get_type(), vec![]);
+    }
+
+    fn visit_number(&mut self, node: &NumberNode) {
+        let mut fields = vec![
+            ("kind", string(node.get_value().get_type_name())),
+            ("value", Tree::Number(node.get_value().to_f64())),
+        ];
+        set_span(&mut fields, node.get_span());
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_package(&mut self, node: &PackageNode) {
+        self.push(node.get_type(), vec![("name", string(node.get_name()))]);
+    }
+
+    fn visit_parenthesized(&mut self, node: &ParenthesizedNode) {
+        let mut fields = vec![("expression", self.build(node.get_expression()))];
+        set_span(&mut fields, node.get_span());
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_string(&mut self, node: &StringNode) {
+        self.push(node.get_type(), vec![("value", string(node.get_value()))]);
+    }
+
+    fn visit_table_declaration(&mut self, node: &TableDeclarationNode) {
+        let columns = node.get_columns().iter().map(|column| {
+            self.visit_column(column);
+            self.trees.pop().unwrap()
+        });
+        let fields = vec![
+            ("name", string(node.get_name())),
+            ("primary_key", optional_string(node.get_primary_key())),
+            ("columns", Tree::List(columns.collect())),
+            ("doc", optional_string(node.get_doc())),
+        ];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_unary_operator(&mut self, node: &UnaryOperatorNode) {
+        let side = match node.get_side() {
+            TokenSide::Left => "left",
+            TokenSide::Right => "right",
+        };
+        let fields = vec![
+            ("operator", operator(node.get_operator())),
+            ("side", string(side)),
+            ("operand", self.build(node.get_operand())),
+        ];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_variable_declaration(&mut self, node: &VariableDeclaration) {
+        let fields = vec![
+            ("name", string(node.get_name())),
+            ("mutable", Tree::Boolean(node.is_mutable())),
+            ("datatype", optional_string(node.get_datatype())),
+            ("value", self.build_optional(node.get_value())),
+        ];
+        self.push(node.get_type(), fields);
+    }
+
+    fn visit_while_loop(&mut self, node: &WhileLoopNode) {
+        let fields = vec![
+            ("condition", self.build(node.get_condition())),
+            ("body", self.build_body(node.get_body())),
+        ];
+        self.push(node.get_type(), fields);
+    }
+
+    // Positional arguments have no names.
+    fn visit_parameter(&mut self, node: &Parameter) {
+        let name = Some(node.get_name()).filter(|name| !name.is_empty());
+        let fields = vec![
+            ("name", optional_string(name)),
+            ("datatype", optional_string(node.get_datatype())),
+            ("value", self.build_optional(node.get_value())),
+        ];
+        self.push("Parameter", fields);
+    }
+
+    fn visit_column(&mut self, node: &Column) {
+        let constraints = node
+            .get_constraints()
+            .iter()
+            .map(|constraint| string(&constraint.to_string()));
+        let fields = vec![
+            ("name", string(node.get_name())),
+            ("column_type", string(&node.get_column_type().to_string())),
+            ("constraints", Tree::List(constraints.collect())),
+            ("default", self.build_optional(node.get_default())),
+        ];
+        self.push("Column", fields);
+    }
+}
@@ -0,0 +1,46 @@
+use super::expression::ExpressionNode;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumDeclarationNode {
+    name: String,
+    datatype: Option<String>,
+    variants: Vec<String>,
+    doc: Option<String>,
+}
+
+impl ExpressionNode for EnumDeclarationNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(EnumDeclarationNode)
+    }
+}
+
+impl EnumDeclarationNode {
+    pub fn new(name: String, datatype: Option<String>, variants: Vec<String>) -> Self {
+        EnumDeclarationNode {
+            name,
+            datatype,
+            variants,
+            doc: None,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_datatype(&self) -> Option<&str> {
+        self.datatype.as_deref()
+    }
+
+    pub fn get_variants(&self) -> &Vec<String> {
+        &self.variants
+    }
+
+    pub fn get_doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    pub fn set_doc(&mut self, doc: Option<String>) {
+        self.doc = doc;
+    }
+}
@@ -0,0 +1,93 @@
+use super::body::BodyNode;
+use super::expression::ExpressionNode;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    Insert,
+    Update,
+    Delete,
+    Change, // any of the above
+}
+
+impl ChangeKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "insert" => Some(ChangeKind::Insert),
+            "update" => Some(ChangeKind::Update),
+            "delete" => Some(ChangeKind::Delete),
+            "change" => Some(ChangeKind::Change),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            ChangeKind::Insert => "insert",
+            ChangeKind::Update => "update",
+            ChangeKind::Delete => "delete",
+            ChangeKind::Change => "change",
+        }
+    }
+
+    pub fn includes(&self, kind: &ChangeKind) -> bool {
+        *self == ChangeKind::Change || self == kind
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventDeclarationNode {
+    name: String,
+    table: String,
+    column: Option<String>,
+    change_kind: ChangeKind,
+    body: BodyNode,
+}
+
+impl ExpressionNode for EventDeclarationNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(EventDeclarationNode)
+    }
+}
+
+impl EventDeclarationNode {
+    pub fn new(
+        name: String,
+        table: String,
+        column: Option<String>,
+        change_kind: ChangeKind,
+        body: BodyNode,
+    ) -> Self {
+        EventDeclarationNode {
+            name,
+            table,
+            column,
+            change_kind,
+            body,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_table(&self) -> &str {
+        &self.table
+    }
+
+    // An event without a column fires on changes of any column of the table.
+    pub fn get_column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    pub fn get_change_kind(&self) -> &ChangeKind {
+        &self.change_kind
+    }
+
+    pub fn get_body(&self) -> &BodyNode {
+        &self.body
+    }
+
+    pub fn get_body_mut(&mut self) -> &mut BodyNode {
+        &mut self.body
+    }
+}
@@ -1,3 +1,53 @@
-pub trait ExpressionNode {
+use std::any::Any;
+use std::fmt::Debug;
+
+pub trait ExpressionNode: Any + Debug + NodeBox {
     fn get_type(&self) -> &'static str;
 }
+
+// Cloning and comparing boxed nodes needs their concrete types,
+// so it's implemented once for every node deriving Clone and PartialEq.
+pub trait NodeBox {
+    fn clone_box(&self) -> Box<dyn ExpressionNode>;
+    fn equals(&self, other: &dyn ExpressionNode) -> bool;
+}
+
+impl<T: ExpressionNode + Clone + PartialEq> NodeBox for T {
+    fn clone_box(&self) -> Box<dyn ExpressionNode> {
+        Box::new(self.clone())
+    }
+
+    fn equals(&self, other: &dyn ExpressionNode) -> bool {
+        other.downcast_ref::<T>().is_some_and(|other| self == other)
+    }
+}
+
+impl dyn ExpressionNode {
+    pub fn downcast_ref<T: ExpressionNode>(&self) -> Option<&T> {
+        (self as &dyn Any).downcast_ref::<T>()
+    }
+
+    pub fn downcast_mut<T: ExpressionNode>(&mut self) -> Option<&mut T> {
+        (self as &mut dyn Any).downcast_mut::<T>()
+    }
+}
+
+impl Clone for Box<dyn ExpressionNode> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+impl PartialEq for Box<dyn ExpressionNode> {
+    fn eq(&self, other: &Self) -> bool {
+        self.equals(other.as_ref())
+    }
+}
+
+// Derived comparisons of boxed fields are only compiled with this one,
+// see https://github.com/rust-lang/rust/issues/31740.
+impl PartialEq<&Self> for Box<dyn ExpressionNode> {
+    fn eq(&self, other: &&Self) -> bool {
+        self.equals(other.as_ref())
+    }
+}
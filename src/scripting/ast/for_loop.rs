@@ -0,0 +1,44 @@
+use super::{body::BodyNode, expression::ExpressionNode};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForLoopNode {
+    item: String,
+    collection: Box<dyn ExpressionNode>,
+    body: BodyNode,
+}
+
+impl ExpressionNode for ForLoopNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(ForLoopNode)
+    }
+}
+
+impl ForLoopNode {
+    pub fn new(item: String, collection: Box<dyn ExpressionNode>, body: BodyNode) -> Self {
+        ForLoopNode {
+            item,
+            collection,
+            body,
+        }
+    }
+
+    pub fn get_item(&self) -> &str {
+        &self.item
+    }
+
+    pub fn get_collection(&self) -> &dyn ExpressionNode {
+        self.collection.as_ref()
+    }
+
+    pub fn get_collection_mut(&mut self) -> &mut Box<dyn ExpressionNode> {
+        &mut self.collection
+    }
+
+    pub fn get_body(&self) -> &BodyNode {
+        &self.body
+    }
+
+    pub fn get_body_mut(&mut self) -> &mut BodyNode {
+        &mut self.body
+    }
+}
@@ -1,13 +1,17 @@
 use super::body::BodyNode;
 use super::expression::ExpressionNode;
 use super::parameter::Parameters;
+use crate::scripting::span::{NodeSpan, Span};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct FunctionDeclarationNode {
-    _name: String,
-    _datatype: Option<String>,
-    _generics: Vec<String>,
-    _arguments: Parameters,
-    _body: Option<BodyNode>,
+    name: String,
+    datatype: Option<String>,
+    generics: Vec<String>,
+    arguments: Parameters,
+    body: Option<BodyNode>,
+    doc: Option<String>,
+    span: NodeSpan,
 }
 
 impl FunctionDeclarationNode {
@@ -18,13 +22,66 @@ impl FunctionDeclarationNode {
         body: Option<BodyNode>,
     ) -> Self {
         FunctionDeclarationNode {
-            _name: name,
-            _datatype: datatype,
-            _generics: vec![],
-            _arguments: arguments,
-            _body: body,
+            name,
+            datatype,
+            generics: vec![],
+            arguments,
+            body,
+            doc: None,
+            span: NodeSpan::default(),
         }
     }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_datatype(&self) -> Option<&str> {
+        self.datatype.as_deref()
+    }
+
+    // The type parameters, like `T` in `function first<T>(items: T[]): T`.
+    pub fn get_generics(&self) -> &[String] {
+        &self.generics
+    }
+
+    pub fn set_generics(&mut self, generics: Vec<String>) {
+        self.generics = generics;
+    }
+
+    pub fn get_arguments(&self) -> &Parameters {
+        &self.arguments
+    }
+
+    pub fn get_arguments_mut(&mut self) -> &mut Parameters {
+        &mut self.arguments
+    }
+
+    pub fn get_body(&self) -> Option<&BodyNode> {
+        self.body.as_ref()
+    }
+
+    pub fn get_body_mut(&mut self) -> Option<&mut BodyNode> {
+        self.body.as_mut()
+    }
+
+    // The `///` comments written right above the declaration.
+    pub fn get_doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    pub fn set_doc(&mut self, doc: Option<String>) {
+        self.doc = doc;
+    }
+
+    // The name after 'function', which stands for the whole declaration in diagnostics.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span.0
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = NodeSpan(Some(span));
+    }
 }
 
 impl ExpressionNode for FunctionDeclarationNode {
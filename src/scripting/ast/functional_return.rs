@@ -1,7 +1,10 @@
 use super::expression::ExpressionNode;
+use crate::scripting::span::{NodeSpan, Span};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct FunctionalReturnNode {
-    _value: Option<Box<dyn ExpressionNode>>
+    value: Option<Box<dyn ExpressionNode>>,
+    span: NodeSpan,
 }
 
 impl ExpressionNode for FunctionalReturnNode {
@@ -13,7 +16,25 @@ impl ExpressionNode for FunctionalReturnNode {
 impl FunctionalReturnNode {
     pub fn new(value: Option<Box<dyn ExpressionNode>>) -> Self {
         FunctionalReturnNode {
-            _value: value
+            value,
+            span: NodeSpan::default(),
         }
     }
+
+    pub fn get_value(&self) -> Option<&dyn ExpressionNode> {
+        self.value.as_deref()
+    }
+
+    pub fn get_value_mut(&mut self) -> Option<&mut Box<dyn ExpressionNode>> {
+        self.value.as_mut()
+    }
+
+    // Where the 'return' keyword is written.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span.0
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = NodeSpan(Some(span));
+    }
 }
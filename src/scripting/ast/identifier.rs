@@ -1,8 +1,11 @@
 use super::expression::ExpressionNode;
+use crate::scripting::span::{NodeSpan, Span};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct IdentifierNode {
-    _name: String,
-    _types: Vec<IdentifierNode>,
+    name: String,
+    types: Vec<IdentifierNode>,
+    span: NodeSpan,
 }
 
 impl ExpressionNode for IdentifierNode {
@@ -14,8 +17,31 @@ impl ExpressionNode for IdentifierNode {
 impl IdentifierNode {
     pub fn new(name: String) -> Self {
         IdentifierNode {
-            _name: name,
-            _types: vec![],
+            name,
+            types: vec![],
+            span: NodeSpan::default(),
         }
     }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    // The type arguments of a generic call, like `int` in `first<int>(numbers)`.
+    pub fn get_types(&self) -> &[IdentifierNode] {
+        &self.types
+    }
+
+    pub fn set_types(&mut self, types: Vec<IdentifierNode>) {
+        self.types = types;
+    }
+
+    // Only the name, without the type arguments that may follow it.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span.0
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = NodeSpan(Some(span));
+    }
 }
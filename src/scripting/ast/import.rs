@@ -0,0 +1,49 @@
+use super::expression::ExpressionNode;
+
+// The symbol that imports every declaration of a package, as in `import users:all`.
+pub const WILDCARD_SYMBOL: &str = "all";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    package: String,
+    symbol: String,
+}
+
+impl Import {
+    pub fn new(package: String, symbol: String) -> Self {
+        Import { package, symbol }
+    }
+
+    pub fn get_package(&self) -> &str {
+        &self.package
+    }
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn is_wildcard(&self) -> bool {
+        self.symbol == WILDCARD_SYMBOL
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportNode {
+    imports: Vec<Import>,
+}
+
+impl ExpressionNode for ImportNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(ImportNode)
+    }
+}
+
+impl ImportNode {
+    pub fn new(imports: Vec<Import>) -> Self {
+        ImportNode { imports }
+    }
+
+    pub fn get_imports(&self) -> &Vec<Import> {
+        &self.imports
+    }
+}
@@ -0,0 +1,37 @@
+use super::expression::ExpressionNode;
+use crate::scripting::span::{NodeSpan, Span};
+
+// `inspect all;` of older manage.blz files. It's still read, but it has no effect.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectNode {
+    targets: Vec<String>,
+    span: NodeSpan,
+}
+
+impl ExpressionNode for InspectNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(InspectNode)
+    }
+}
+
+impl InspectNode {
+    pub fn new(targets: Vec<String>) -> Self {
+        InspectNode {
+            targets,
+            span: NodeSpan::default(),
+        }
+    }
+
+    pub fn get_targets(&self) -> &[String] {
+        &self.targets
+    }
+
+    // Where the 'inspect' keyword is written.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span.0
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = NodeSpan(Some(span));
+    }
+}
@@ -1,12 +1,16 @@
 use super::expression::ExpressionNode;
+use crate::scripting::span::{NodeSpan, Span};
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum LoopControlType {
     Continue,
-    Break
+    Break,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct LoopControlNode {
-    _control_type: LoopControlType
+    control_type: LoopControlType,
+    span: NodeSpan,
 }
 
 impl ExpressionNode for LoopControlNode {
@@ -18,7 +22,21 @@ impl ExpressionNode for LoopControlNode {
 impl LoopControlNode {
     pub fn new(control_type: LoopControlType) -> Self {
         LoopControlNode {
-            _control_type: control_type
+            control_type,
+            span: NodeSpan::default(),
         }
     }
+
+    pub fn get_control_type(&self) -> &LoopControlType {
+        &self.control_type
+    }
+
+    // Where the 'break' or 'continue' keyword is written.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span.0
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = NodeSpan(Some(span));
+    }
 }
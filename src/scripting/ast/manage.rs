@@ -0,0 +1,27 @@
+use super::expression::ExpressionNode;
+use super::parameter::Parameters;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManageNode {
+    settings: Parameters,
+}
+
+impl ExpressionNode for ManageNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(ManageNode)
+    }
+}
+
+impl ManageNode {
+    pub fn new(settings: Parameters) -> Self {
+        ManageNode { settings }
+    }
+
+    pub fn get_settings(&self) -> &Parameters {
+        &self.settings
+    }
+
+    pub fn get_settings_mut(&mut self) -> &mut Parameters {
+        &mut self.settings
+    }
+}
@@ -1,8 +1,9 @@
 use super::expression::ExpressionNode;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct MemberNode {
-    _parent: Box<dyn ExpressionNode>,
-    _child: Box<dyn ExpressionNode>,
+    parent: Box<dyn ExpressionNode>,
+    child: Box<dyn ExpressionNode>,
 }
 
 impl ExpressionNode for MemberNode {
@@ -13,9 +14,22 @@ impl ExpressionNode for MemberNode {
 
 impl MemberNode {
     pub fn new(parent: Box<dyn ExpressionNode>, child: Box<dyn ExpressionNode>) -> Self {
-        MemberNode {
-            _parent: parent,
-            _child: child,
-        }
+        MemberNode { parent, child }
+    }
+
+    pub fn get_parent(&self) -> &dyn ExpressionNode {
+        self.parent.as_ref()
+    }
+
+    pub fn get_parent_mut(&mut self) -> &mut Box<dyn ExpressionNode> {
+        &mut self.parent
+    }
+
+    pub fn get_child(&self) -> &dyn ExpressionNode {
+        self.child.as_ref()
+    }
+
+    pub fn get_child_mut(&mut self) -> &mut Box<dyn ExpressionNode> {
+        &mut self.child
     }
 }
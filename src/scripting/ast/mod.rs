@@ -1,18 +1,32 @@
+pub mod array;
+pub mod attach;
 pub mod binary_operator;
 pub mod body;
 pub mod boolean;
 pub mod call;
 pub mod conditional_tree;
+pub mod document;
+pub mod dump;
+pub mod enum_declaration;
+pub mod event_declaration;
 pub mod expression;
+pub mod for_loop;
 pub mod function_declaration;
-pub mod identifier;
-pub mod member;
 pub mod functional_return;
-pub mod while_loop;
+pub mod identifier;
+pub mod import;
+pub mod inspect;
 pub mod loop_control;
+pub mod manage;
+pub mod member;
 pub mod null;
 pub mod number;
+pub mod package;
 pub mod parameter;
+pub mod parenthesized;
 pub mod string;
+pub mod table_declaration;
 pub mod unary_operator;
 pub mod variable_declaration;
+pub mod visitor;
+pub mod while_loop;
@@ -1,5 +1,6 @@
 use super::expression::ExpressionNode;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct NullNode;
 
 impl ExpressionNode for NullNode {
@@ -1,12 +1,32 @@
 use super::expression::ExpressionNode;
+use crate::scripting::number::Number;
+use crate::scripting::span::{NodeSpan, Span};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct NumberNode {
-    _value: f64,
+    value: Number,
+    span: NodeSpan,
 }
 
 impl NumberNode {
-    pub fn new(value: f64) -> Self {
-        NumberNode { _value: value }
+    pub fn new(value: Number) -> Self {
+        NumberNode {
+            value,
+            span: NodeSpan::default(),
+        }
+    }
+
+    pub fn get_value(&self) -> Number {
+        self.value
+    }
+
+    // Includes the sign when it's folded into the literal, e.g. `-1`.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span.0
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = NodeSpan(Some(span));
     }
 }
 
@@ -0,0 +1,22 @@
+use super::expression::ExpressionNode;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageNode {
+    name: String,
+}
+
+impl ExpressionNode for PackageNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(PackageNode)
+    }
+}
+
+impl PackageNode {
+    pub fn new(name: String) -> Self {
+        PackageNode { name }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+}
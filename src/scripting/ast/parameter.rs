@@ -2,11 +2,12 @@ use strum_macros::Display;
 
 use super::expression::ExpressionNode;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Parameter {
-    _name: String,
-    _datatype: Option<String>,
-    _value: Option<Box<dyn ExpressionNode>>, // Value field can either store a transmitted value
-                                             // or contain a default value for functions, plans, and tables.
+    name: String,
+    datatype: Option<String>,
+    value: Option<Box<dyn ExpressionNode>>, // Value field can either store a transmitted value
+                                            // or contain a default value for functions, plans, and tables.
 }
 
 impl Parameter {
@@ -16,27 +17,48 @@ impl Parameter {
         value: Option<Box<dyn ExpressionNode>>,
     ) -> Self {
         Parameter {
-            _name: name,
-            _datatype: datatype,
-            _value: value,
+            name,
+            datatype,
+            value,
         }
     }
 
     pub fn new_functional(name: String, datatype: String) -> Self {
         Parameter {
-            _name: name,
-            _datatype: Some(datatype),
-            _value: None,
+            name,
+            datatype: Some(datatype),
+            value: None,
         }
     }
 
     pub fn new_calling(name: Option<String>, value: Box<dyn ExpressionNode>) -> Self {
         Parameter {
-            _name: name.unwrap_or_default(),
-            _datatype: None,
-            _value: Some(value),
+            name: name.unwrap_or_default(),
+            datatype: None,
+            value: Some(value),
         }
     }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_datatype(&self) -> Option<&str> {
+        self.datatype.as_deref()
+    }
+
+    pub fn get_value(&self) -> Option<&dyn ExpressionNode> {
+        self.value.as_deref()
+    }
+
+    pub fn get_value_mut(&mut self) -> Option<&mut Box<dyn ExpressionNode>> {
+        self.value.as_mut()
+    }
+
+    // Call arguments without a name are positional ones.
+    pub fn is_positional(&self) -> bool {
+        self.name.is_empty()
+    }
 }
 
 pub type Parameters = Vec<Parameter>;
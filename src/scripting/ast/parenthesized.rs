@@ -0,0 +1,40 @@
+use super::expression::ExpressionNode;
+use crate::scripting::span::{NodeSpan, Span};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParenthesizedNode {
+    expression: Box<dyn ExpressionNode>,
+    span: NodeSpan,
+}
+
+impl ExpressionNode for ParenthesizedNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(ParenthesizedNode)
+    }
+}
+
+impl ParenthesizedNode {
+    pub fn new(expression: Box<dyn ExpressionNode>) -> Self {
+        ParenthesizedNode {
+            expression,
+            span: NodeSpan::default(),
+        }
+    }
+
+    pub fn get_expression(&self) -> &dyn ExpressionNode {
+        self.expression.as_ref()
+    }
+
+    pub fn get_expression_mut(&mut self) -> &mut Box<dyn ExpressionNode> {
+        &mut self.expression
+    }
+
+    // From the opening parenthesis to the closing one.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span.0
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = NodeSpan(Some(span));
+    }
+}
@@ -1,21 +1,31 @@
 use crate::scripting::ast::expression::ExpressionNode;
 
-enum StringType {
+#[derive(Debug, Clone, PartialEq)]
+pub enum StringType {
     Basic,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct StringNode {
-    _value: String,
-    _string_type: StringType,
+    value: String,
+    string_type: StringType,
 }
 
 impl StringNode {
     pub fn new(value: String) -> Self {
         StringNode {
-            _value: value,
-            _string_type: StringType::Basic,
+            value,
+            string_type: StringType::Basic,
         }
     }
+
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn get_string_type(&self) -> &StringType {
+        &self.string_type
+    }
 }
 
 impl ExpressionNode for StringNode {
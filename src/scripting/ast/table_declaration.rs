@@ -0,0 +1,156 @@
+use std::fmt;
+
+use crate::scripting::number::Number;
+use crate::scripting::tokens::TokenType;
+
+use super::expression::ExpressionNode;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReferenceModifier {
+    Single,   // &T
+    Optional, // &T?
+    Unique,   // &T! - no other row can refer to the same target
+    Many,     // &T[]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnType {
+    Datatype(String),
+    Reference(String, ReferenceModifier),
+}
+
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnType::Datatype(datatype) => write!(f, "{}", datatype),
+            ColumnType::Reference(table, modifier) => {
+                let suffix = match modifier {
+                    ReferenceModifier::Single => "",
+                    ReferenceModifier::Optional => "?",
+                    ReferenceModifier::Unique => "!",
+                    ReferenceModifier::Many => "[]",
+                };
+                write!(f, "&{}{}", table, suffix)
+            }
+        }
+    }
+}
+
+// Bounds restrict numbers by their value and strings by their length,
+// while an exact constraint like `str 2` fixes the length.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constraint {
+    Bound(TokenType, Number),
+    Exact(Number),
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::Bound(operator, bound) => {
+                let operator = operator.get_text().unwrap_or_default();
+                write!(f, "{} {}", operator, bound.to_literal())
+            }
+            Constraint::Exact(length) => write!(f, "{}", length.to_literal()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    name: String,
+    column_type: ColumnType,
+    constraints: Vec<Constraint>,
+    default: Option<Box<dyn ExpressionNode>>,
+}
+
+impl Column {
+    pub fn new(
+        name: String,
+        column_type: ColumnType,
+        constraints: Vec<Constraint>,
+        default: Option<Box<dyn ExpressionNode>>,
+    ) -> Self {
+        Column {
+            name,
+            column_type,
+            constraints,
+            default,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_column_type(&self) -> &ColumnType {
+        &self.column_type
+    }
+
+    pub fn get_constraints(&self) -> &Vec<Constraint> {
+        &self.constraints
+    }
+
+    pub fn get_default(&self) -> Option<&dyn ExpressionNode> {
+        self.default.as_deref()
+    }
+
+    pub fn get_default_mut(&mut self) -> Option<&mut Box<dyn ExpressionNode>> {
+        self.default.as_mut()
+    }
+}
+
+pub type Columns = Vec<Column>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDeclarationNode {
+    name: String,
+    primary_key: Option<String>,
+    columns: Columns,
+    doc: Option<String>,
+}
+
+impl ExpressionNode for TableDeclarationNode {
+    fn get_type(&self) -> &'static str {
+        stringify!(TableDeclarationNode)
+    }
+}
+
+impl TableDeclarationNode {
+    pub fn new(name: String, primary_key: Option<String>, columns: Columns) -> Self {
+        TableDeclarationNode {
+            name,
+            primary_key,
+            columns,
+            doc: None,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_primary_key(&self) -> Option<&str> {
+        self.primary_key.as_deref()
+    }
+
+    pub fn get_columns(&self) -> &Columns {
+        &self.columns
+    }
+
+    pub fn get_columns_mut(&mut self) -> &mut Columns {
+        &mut self.columns
+    }
+
+    pub fn get_column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.get_name() == name)
+    }
+
+    pub fn get_doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    pub fn set_doc(&mut self, doc: Option<String>) {
+        self.doc = doc;
+    }
+}
@@ -2,10 +2,11 @@ use crate::scripting::tokens::{TokenSide, TokenType};
 
 use super::expression::ExpressionNode;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct UnaryOperatorNode {
-    _operator: TokenType,
-    _operand: Box<dyn ExpressionNode>,
-    _side: TokenSide,
+    operator: TokenType,
+    operand: Box<dyn ExpressionNode>,
+    side: TokenSide,
 }
 
 impl ExpressionNode for UnaryOperatorNode {
@@ -17,9 +18,25 @@ impl ExpressionNode for UnaryOperatorNode {
 impl UnaryOperatorNode {
     pub fn new(operator: TokenType, operand: Box<dyn ExpressionNode>, side: TokenSide) -> Self {
         UnaryOperatorNode {
-            _operator: operator,
-            _operand: operand,
-            _side: side,
+            operator,
+            operand,
+            side,
         }
     }
+
+    pub fn get_operator(&self) -> &TokenType {
+        &self.operator
+    }
+
+    pub fn get_operand(&self) -> &dyn ExpressionNode {
+        self.operand.as_ref()
+    }
+
+    pub fn get_operand_mut(&mut self) -> &mut Box<dyn ExpressionNode> {
+        &mut self.operand
+    }
+
+    pub fn get_side(&self) -> &TokenSide {
+        &self.side
+    }
 }
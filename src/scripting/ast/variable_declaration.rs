@@ -1,23 +1,59 @@
 use super::expression::ExpressionNode;
+use crate::scripting::span::{NodeSpan, Span};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct VariableDeclaration {
-    _name: String,
-    _datatype: Option<String>,
-    _value: Option<Box<dyn ExpressionNode>>,
+    name: String,
+    is_mutable: bool,
+    datatype: Option<String>,
+    value: Option<Box<dyn ExpressionNode>>,
+    span: NodeSpan,
 }
 
 impl VariableDeclaration {
     pub fn new(
         name: String,
+        is_mutable: bool,
         datatype: Option<String>,
         value: Option<Box<dyn ExpressionNode>>,
     ) -> Self {
         VariableDeclaration {
-            _name: name,
-            _datatype: datatype,
-            _value: value,
+            name,
+            is_mutable,
+            datatype,
+            value,
+            span: NodeSpan::default(),
         }
     }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_mutable(&self) -> bool {
+        self.is_mutable
+    }
+
+    pub fn get_datatype(&self) -> Option<&str> {
+        self.datatype.as_deref()
+    }
+
+    pub fn get_value(&self) -> Option<&dyn ExpressionNode> {
+        self.value.as_deref()
+    }
+
+    pub fn get_value_mut(&mut self) -> Option<&mut Box<dyn ExpressionNode>> {
+        self.value.as_mut()
+    }
+
+    // The declared name, e.g. `x` in `mut x = 1`.
+    pub fn get_span(&self) -> Option<Span> {
+        self.span.0
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = NodeSpan(Some(span));
+    }
 }
 
 impl ExpressionNode for VariableDeclaration {
@@ -0,0 +1,619 @@
+use super::array::ArrayNode;
+use super::attach::AttachNode;
+use super::binary_operator::BinaryOperatorNode;
+use super::body::BodyNode;
+use super::boolean::BooleanNode;
+use super::call::CallNode;
+use super::conditional_tree::ConditionalTreeNode;
+use super::document::DocumentNode;
+use super::enum_declaration::EnumDeclarationNode;
+use super::event_declaration::EventDeclarationNode;
+use super::expression::ExpressionNode;
+use super::for_loop::ForLoopNode;
+use super::function_declaration::FunctionDeclarationNode;
+use super::functional_return::FunctionalReturnNode;
+use super::identifier::IdentifierNode;
+use super::import::ImportNode;
+use super::inspect::InspectNode;
+use super::loop_control::LoopControlNode;
+use super::manage::ManageNode;
+use super::member::MemberNode;
+use super::null::NullNode;
+use super::number::NumberNode;
+use super::package::PackageNode;
+use super::parameter::Parameter;
+use super::parenthesized::ParenthesizedNode;
+use super::string::StringNode;
+use super::table_declaration::{Column, TableDeclarationNode};
+use super::unary_operator::UnaryOperatorNode;
+use super::variable_declaration::VariableDeclaration;
+use super::while_loop::WhileLoopNode;
+
+// A pass over the AST, like linting or type checking, overrides the methods
+// of the nodes it's interested in and calls the walk function of a node to visit its children.
+pub trait Visitor {
+    fn visit_expression(&mut self, node: &dyn ExpressionNode) {
+        walk_expression(self, node);
+    }
+
+    fn visit_array(&mut self, node: &ArrayNode) {
+        walk_array(self, node);
+    }
+
+    fn visit_attach(&mut self, _node: &AttachNode) {}
+
+    fn visit_binary_operator(&mut self, node: &BinaryOperatorNode) {
+        walk_binary_operator(self, node);
+    }
+
+    fn visit_body(&mut self, node: &BodyNode) {
+        walk_body(self, node);
+    }
+
+    fn visit_boolean(&mut self, _node: &BooleanNode) {}
+
+    fn visit_call(&mut self, node: &CallNode) {
+        walk_call(self, node);
+    }
+
+    fn visit_conditional_tree(&mut self, node: &ConditionalTreeNode) {
+        walk_conditional_tree(self, node);
+    }
+
+    fn visit_document(&mut self, node: &DocumentNode) {
+        walk_document(self, node);
+    }
+
+    fn visit_enum_declaration(&mut self, _node: &EnumDeclarationNode) {}
+
+    fn visit_event_declaration(&mut self, node: &EventDeclarationNode) {
+        walk_event_declaration(self, node);
+    }
+
+    fn visit_for_loop(&mut self, node: &ForLoopNode) {
+        walk_for_loop(self, node);
+    }
+
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationNode) {
+        walk_function_declaration(self, node);
+    }
+
+    fn visit_functional_return(&mut self, node: &FunctionalReturnNode) {
+        walk_functional_return(self, node);
+    }
+
+    fn visit_identifier(&mut self, _node: &IdentifierNode) {}
+
+    fn visit_import(&mut self, _node: &ImportNode) {}
+
+    fn visit_inspect(&mut self, _node: &InspectNode) {}
+
+    fn visit_loop_control(&mut self, _node: &LoopControlNode) {}
+
+    fn visit_manage(&mut self, node: &ManageNode) {
+        walk_manage(self, node);
+    }
+
+    fn visit_member(&mut self, node: &MemberNode) {
+        walk_member(self, node);
+    }
+
+    fn visit_null(&mut self, _node: &NullNode) {}
+
+    fn visit_number(&mut self, _node: &NumberNode) {}
+
+    fn visit_package(&mut self, _node: &PackageNode) {}
+
+    fn visit_parenthesized(&mut self, node: &ParenthesizedNode) {
+        walk_parenthesized(self, node);
+    }
+
+    fn visit_string(&mut self, _node: &StringNode) {}
+
+    fn visit_table_declaration(&mut self, node: &TableDeclarationNode) {
+        walk_table_declaration(self, node);
+    }
+
+    fn visit_unary_operator(&mut self, node: &UnaryOperatorNode) {
+        walk_unary_operator(self, node);
+    }
+
+    fn visit_variable_declaration(&mut self, node: &VariableDeclaration) {
+        walk_variable_declaration(self, node);
+    }
+
+    fn visit_while_loop(&mut self, node: &WhileLoopNode) {
+        walk_while_loop(self, node);
+    }
+
+    fn visit_parameter(&mut self, node: &Parameter) {
+        walk_parameter(self, node);
+    }
+
+    fn visit_column(&mut self, node: &Column) {
+        walk_column(self, node);
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, node: &dyn ExpressionNode) {
+    if let Some(node) = node.downcast_ref::<ArrayNode>() {
+        visitor.visit_array(node);
+    } else if let Some(node) = node.downcast_ref::<AttachNode>() {
+        visitor.visit_attach(node);
+    } else if let Some(node) = node.downcast_ref::<BinaryOperatorNode>() {
+        visitor.visit_binary_operator(node);
+    } else if let Some(node) = node.downcast_ref::<BodyNode>() {
+        visitor.visit_body(node);
+    } else if let Some(node) = node.downcast_ref::<BooleanNode>() {
+        visitor.visit_boolean(node);
+    } else if let Some(node) = node.downcast_ref::<CallNode>() {
+        visitor.visit_call(node);
+    } else if let Some(node) = node.downcast_ref::<ConditionalTreeNode>() {
+        visitor.visit_conditional_tree(node);
+    } else if let Some(node) = node.downcast_ref::<DocumentNode>() {
+        visitor.visit_document(node);
+    } else if let Some(node) = node.downcast_ref::<EnumDeclarationNode>() {
+        visitor.visit_enum_declaration(node);
+    } else if let Some(node) = node.downcast_ref::<EventDeclarationNode>() {
+        visitor.visit_event_declaration(node);
+    } else if let Some(node) = node.downcast_ref::<ForLoopNode>() {
+        visitor.visit_for_loop(node);
+    } else if let Some(node) = node.downcast_ref::<FunctionDeclarationNode>() {
+        visitor.visit_function_declaration(node);
+    } else if let Some(node) = node.downcast_ref::<FunctionalReturnNode>() {
+        visitor.visit_functional_return(node);
+    } else if let Some(node) = node.downcast_ref::<IdentifierNode>() {
+        visitor.visit_identifier(node);
+    } else if let Some(node) = node.downcast_ref::<ImportNode>() {
+        visitor.visit_import(node);
+    } else if let Some(node) = node.downcast_ref::<InspectNode>() {
+        visitor.visit_inspect(node);
+    } else if let Some(node) = node.downcast_ref::<LoopControlNode>() {
+        visitor.visit_loop_control(node);
+    } else if let Some(node) = node.downcast_ref::<ManageNode>() {
+        visitor.visit_manage(node);
+    } else if let Some(node) = node.downcast_ref::<MemberNode>() {
+        visitor.visit_member(node);
+    } else if let Some(node) = node.downcast_ref::<NullNode>() {
+        visitor.visit_null(node);
+    } else if let Some(node) = node.downcast_ref::<NumberNode>() {
+        visitor.visit_number(node);
+    } else if let Some(node) = node.downcast_ref::<PackageNode>() {
+        visitor.visit_package(node);
+    } else if let Some(node) = node.downcast_ref::<ParenthesizedNode>() {
+        visitor.visit_parenthesized(node);
+    } else if let Some(node) = node.downcast_ref::<StringNode>() {
+        visitor.visit_string(node);
+    } else if let Some(node) = node.downcast_ref::<TableDeclarationNode>() {
+        visitor.visit_table_declaration(node);
+    } else if let Some(node) = node.downcast_ref::<UnaryOperatorNode>() {
+        visitor.visit_unary_operator(node);
+    } else if let Some(node) = node.downcast_ref::<VariableDeclaration>() {
+        visitor.visit_variable_declaration(node);
+    } else if let Some(node) = node.downcast_ref::<WhileLoopNode>() {
+        visitor.visit_while_loop(node);
+    }
+}
+
+pub fn walk_array<V: Visitor + ?Sized>(visitor: &mut V, node: &ArrayNode) {
+    for item in node.get_items() {
+        visitor.visit_expression(item.as_ref());
+    }
+}
+
+pub fn walk_binary_operator<V: Visitor + ?Sized>(visitor: &mut V, node: &BinaryOperatorNode) {
+    visitor.visit_expression(node.get_left_operand());
+    visitor.visit_expression(node.get_right_operand());
+}
+
+pub fn walk_body<V: Visitor + ?Sized>(visitor: &mut V, node: &BodyNode) {
+    for node in &node.nodes {
+        visitor.visit_expression(node.as_ref());
+    }
+}
+
+pub fn walk_call<V: Visitor + ?Sized>(visitor: &mut V, node: &CallNode) {
+    visitor.visit_expression(node.get_callee());
+    for argument in node.get_arguments() {
+        visitor.visit_parameter(argument);
+    }
+}
+
+pub fn walk_conditional_tree<V: Visitor + ?Sized>(visitor: &mut V, node: &ConditionalTreeNode) {
+    for (condition, value) in node.get_conditions() {
+        visitor.visit_expression(condition.as_ref());
+        visitor.visit_expression(value.as_ref());
+    }
+    if let Some(default) = node.get_default() {
+        visitor.visit_expression(default);
+    }
+}
+
+pub fn walk_document<V: Visitor + ?Sized>(visitor: &mut V, node: &DocumentNode) {
+    for (_, value) in node.get_fields() {
+        visitor.visit_expression(value.as_ref());
+    }
+}
+
+pub fn walk_event_declaration<V: Visitor + ?Sized>(visitor: &mut V, node: &EventDeclarationNode) {
+    visitor.visit_body(node.get_body());
+}
+
+pub fn walk_for_loop<V: Visitor + ?Sized>(visitor: &mut V, node: &ForLoopNode) {
+    visitor.visit_expression(node.get_collection());
+    visitor.visit_body(node.get_body());
+}
+
+pub fn walk_function_declaration<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    node: &FunctionDeclarationNode,
+) {
+    for argument in node.get_arguments() {
+        visitor.visit_parameter(argument);
+    }
+    if let Some(body) = node.get_body() {
+        visitor.visit_body(body);
+    }
+}
+
+pub fn walk_functional_return<V: Visitor + ?Sized>(visitor: &mut V, node: &FunctionalReturnNode) {
+    if let Some(value) = node.get_value() {
+        visitor.visit_expression(value);
+    }
+}
+
+pub fn walk_manage<V: Visitor + ?Sized>(visitor: &mut V, node: &ManageNode) {
+    for setting in node.get_settings() {
+        visitor.visit_parameter(setting);
+    }
+}
+
+pub fn walk_member<V: Visitor + ?Sized>(visitor: &mut V, node: &MemberNode) {
+    visitor.visit_expression(node.get_parent());
+    visitor.visit_expression(node.get_child());
+}
+
+pub fn walk_parenthesized<V: Visitor + ?Sized>(visitor: &mut V, node: &ParenthesizedNode) {
+    visitor.visit_expression(node.get_expression());
+}
+
+pub fn walk_table_declaration<V: Visitor + ?Sized>(visitor: &mut V, node: &TableDeclarationNode) {
+    for column in node.get_columns() {
+        visitor.visit_column(column);
+    }
+}
+
+pub fn walk_unary_operator<V: Visitor + ?Sized>(visitor: &mut V, node: &UnaryOperatorNode) {
+    visitor.visit_expression(node.get_operand());
+}
+
+pub fn walk_variable_declaration<V: Visitor + ?Sized>(visitor: &mut V, node: &VariableDeclaration) {
+    if let Some(value) = node.get_value() {
+        visitor.visit_expression(value);
+    }
+}
+
+pub fn walk_while_loop<V: Visitor + ?Sized>(visitor: &mut V, node: &WhileLoopNode) {
+    visitor.visit_expression(node.get_condition());
+    visitor.visit_body(node.get_body());
+}
+
+pub fn walk_parameter<V: Visitor + ?Sized>(visitor: &mut V, node: &Parameter) {
+    if let Some(value) = node.get_value() {
+        visitor.visit_expression(value);
+    }
+}
+
+pub fn walk_column<V: Visitor + ?Sized>(visitor: &mut V, node: &Column) {
+    if let Some(default) = node.get_default() {
+        visitor.visit_expression(default);
+    }
+}
+
+// Like Visitor, but the nodes can be changed or replaced, e.g. by constant folding.
+pub trait VisitorMut {
+    fn visit_expression_mut(&mut self, node: &mut Box<dyn ExpressionNode>) {
+        walk_expression_mut(self, node);
+    }
+
+    fn visit_array_mut(&mut self, node: &mut ArrayNode) {
+        walk_array_mut(self, node);
+    }
+
+    fn visit_attach_mut(&mut self, _node: &mut AttachNode) {}
+
+    fn visit_binary_operator_mut(&mut self, node: &mut BinaryOperatorNode) {
+        walk_binary_operator_mut(self, node);
+    }
+
+    fn visit_body_mut(&mut self, node: &mut BodyNode) {
+        walk_body_mut(self, node);
+    }
+
+    fn visit_boolean_mut(&mut self, _node: &mut BooleanNode) {}
+
+    fn visit_call_mut(&mut self, node: &mut CallNode) {
+        walk_call_mut(self, node);
+    }
+
+    fn visit_conditional_tree_mut(&mut self, node: &mut ConditionalTreeNode) {
+        walk_conditional_tree_mut(self, node);
+    }
+
+    fn visit_document_mut(&mut self, node: &mut DocumentNode) {
+        walk_document_mut(self, node);
+    }
+
+    fn visit_enum_declaration_mut(&mut self, _node: &mut EnumDeclarationNode) {}
+
+    fn visit_event_declaration_mut(&mut self, node: &mut EventDeclarationNode) {
+        walk_event_declaration_mut(self, node);
+    }
+
+    fn visit_for_loop_mut(&mut self, node: &mut ForLoopNode) {
+        walk_for_loop_mut(self, node);
+    }
+
+    fn visit_function_declaration_mut(&mut self, node: &mut FunctionDeclarationNode) {
+        walk_function_declaration_mut(self, node);
+    }
+
+    fn visit_functional_return_mut(&mut self, node: &mut FunctionalReturnNode) {
+        walk_functional_return_mut(self, node);
+    }
+
+    fn visit_identifier_mut(&mut self, _node: &mut IdentifierNode) {}
+
+    fn visit_import_mut(&mut self, _node: &mut ImportNode) {}
+
+    fn visit_inspect_mut(&mut self, _node: &mut InspectNode) {}
+
+    fn visit_loop_control_mut(&mut self, _node: &mut LoopControlNode) {}
+
+    fn visit_manage_mut(&mut self, node: &mut ManageNode) {
+        walk_manage_mut(self, node);
+    }
+
+    fn visit_member_mut(&mut self, node: &mut MemberNode) {
+        walk_member_mut(self, node);
+    }
+
+    fn visit_null_mut(&mut self, _node: &mut NullNode) {}
+
+    fn visit_number_mut(&mut self, _node: &mut NumberNode) {}
+
+    fn visit_package_mut(&mut self, _node: &mut PackageNode) {}
+
+    fn visit_parenthesized_mut(&mut self, node: &mut ParenthesizedNode) {
+        walk_parenthesized_mut(self, node);
+    }
+
+    fn visit_string_mut(&mut self, _node: &mut StringNode) {}
+
+    fn visit_table_declaration_mut(&mut self, node: &mut TableDeclarationNode) {
+        walk_table_declaration_mut(self, node);
+    }
+
+    fn visit_unary_operator_mut(&mut self, node: &mut UnaryOperatorNode) {
+        walk_unary_operator_mut(self, node);
+    }
+
+    fn visit_variable_declaration_mut(&mut self, node: &mut VariableDeclaration) {
+        walk_variable_declaration_mut(self, node);
+    }
+
+    fn visit_while_loop_mut(&mut self, node: &mut WhileLoopNode) {
+        walk_while_loop_mut(self, node);
+    }
+
+    fn visit_parameter_mut(&mut self, node: &mut Parameter) {
+        walk_parameter_mut(self, node);
+    }
+
+    fn visit_column_mut(&mut self, node: &mut Column) {
+        walk_column_mut(self, node);
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut Box<dyn ExpressionNode>,
+) {
+    let node = node.as_mut();
+    if let Some(node) = node.downcast_mut::<ArrayNode>() {
+        visitor.visit_array_mut(node);
+    } else if let Some(node) = node.downcast_mut::<AttachNode>() {
+        visitor.visit_attach_mut(node);
+    } else if let Some(node) = node.downcast_mut::<BinaryOperatorNode>() {
+        visitor.visit_binary_operator_mut(node);
+    } else if let Some(node) = node.downcast_mut::<BodyNode>() {
+        visitor.visit_body_mut(node);
+    } else if let Some(node) = node.downcast_mut::<BooleanNode>() {
+        visitor.visit_boolean_mut(node);
+    } else if let Some(node) = node.downcast_mut::<CallNode>() {
+        visitor.visit_call_mut(node);
+    } else if let Some(node) = node.downcast_mut::<ConditionalTreeNode>() {
+        visitor.visit_conditional_tree_mut(node);
+    } else if let Some(node) = node.downcast_mut::<DocumentNode>() {
+        visitor.visit_document_mut(node);
+    } else if let Some(node) = node.downcast_mut::<EnumDeclarationNode>() {
+        visitor.visit_enum_declaration_mut(node);
+    } else if let Some(node) = node.downcast_mut::<EventDeclarationNode>() {
+        visitor.visit_event_declaration_mut(node);
+    } else if let Some(node) = node.downcast_mut::<ForLoopNode>() {
+        visitor.visit_for_loop_mut(node);
+    } else if let Some(node) = node.downcast_mut::<FunctionDeclarationNode>() {
+        visitor.visit_function_declaration_mut(node);
+    } else if let Some(node) = node.downcast_mut::<FunctionalReturnNode>() {
+        visitor.visit_functional_return_mut(node);
+    } else if let Some(node) = node.downcast_mut::<IdentifierNode>() {
+        visitor.visit_identifier_mut(node);
+    } else if let Some(node) = node.downcast_mut::<ImportNode>() {
+        visitor.visit_import_mut(node);
+    } else if let Some(node) = node.downcast_mut::<InspectNode>() {
+        visitor.visit_inspect_mut(node);
+    } else if let Some(node) = node.downcast_mut::<LoopControlNode>() {
+        visitor.visit_loop_control_mut(node);
+    } else if let Some(node) = node.downcast_mut::<ManageNode>() {
+        visitor.visit_manage_mut(node);
+    } else if let Some(node) = node.downcast_mut::<MemberNode>() {
+        visitor.visit_member_mut(node);
+    } else if let Some(node) = node.downcast_mut::<NullNode>() {
+        visitor.visit_null_mut(node);
+    } else if let Some(node) = node.downcast_mut::<NumberNode>() {
+        visitor.visit_number_mut(node);
+    } else if let Some(node) = node.downcast_mut::<PackageNode>() {
+        visitor.visit_package_mut(node);
+    } else if let Some(node) = node.downcast_mut::<ParenthesizedNode>() {
+        visitor.visit_parenthesized_mut(node);
+    } else if let Some(node) = node.downcast_mut::<StringNode>() {
+        visitor.visit_string_mut(node);
+    } else if let Some(node) = node.downcast_mut::<TableDeclarationNode>() {
+        visitor.visit_table_declaration_mut(node);
+    } else if let Some(node) = node.downcast_mut::<UnaryOperatorNode>() {
+        visitor.visit_unary_operator_mut(node);
+    } else if let Some(node) = node.downcast_mut::<VariableDeclaration>() {
+        visitor.visit_variable_declaration_mut(node);
+    } else if let Some(node) = node.downcast_mut::<WhileLoopNode>() {
+        visitor.visit_while_loop_mut(node);
+    }
+}
+
+pub fn walk_array_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ArrayNode) {
+    for item in node.get_items_mut() {
+        visitor.visit_expression_mut(item);
+    }
+}
+
+pub fn walk_binary_operator_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut BinaryOperatorNode,
+) {
+    visitor.visit_expression_mut(node.get_left_operand_mut());
+    visitor.visit_expression_mut(node.get_right_operand_mut());
+}
+
+pub fn walk_body_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut BodyNode) {
+    for node in &mut node.nodes {
+        visitor.visit_expression_mut(node);
+    }
+}
+
+pub fn walk_call_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut CallNode) {
+    visitor.visit_expression_mut(node.get_callee_mut());
+    for argument in node.get_arguments_mut() {
+        visitor.visit_parameter_mut(argument);
+    }
+}
+
+pub fn walk_conditional_tree_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ConditionalTreeNode,
+) {
+    for (condition, value) in node.get_conditions_mut() {
+        visitor.visit_expression_mut(condition);
+        visitor.visit_expression_mut(value);
+    }
+    if let Some(default) = node.get_default_mut() {
+        visitor.visit_expression_mut(default);
+    }
+}
+
+pub fn walk_document_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut DocumentNode) {
+    for (_, value) in node.get_fields_mut() {
+        visitor.visit_expression_mut(value);
+    }
+}
+
+pub fn walk_event_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut EventDeclarationNode,
+) {
+    visitor.visit_body_mut(node.get_body_mut());
+}
+
+pub fn walk_for_loop_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ForLoopNode) {
+    visitor.visit_expression_mut(node.get_collection_mut());
+    visitor.visit_body_mut(node.get_body_mut());
+}
+
+pub fn walk_function_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut FunctionDeclarationNode,
+) {
+    for argument in node.get_arguments_mut() {
+        visitor.visit_parameter_mut(argument);
+    }
+    if let Some(body) = node.get_body_mut() {
+        visitor.visit_body_mut(body);
+    }
+}
+
+pub fn walk_functional_return_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut FunctionalReturnNode,
+) {
+    if let Some(value) = node.get_value_mut() {
+        visitor.visit_expression_mut(value);
+    }
+}
+
+pub fn walk_manage_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ManageNode) {
+    for setting in node.get_settings_mut() {
+        visitor.visit_parameter_mut(setting);
+    }
+}
+
+pub fn walk_member_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut MemberNode) {
+    visitor.visit_expression_mut(node.get_parent_mut());
+    visitor.visit_expression_mut(node.get_child_mut());
+}
+
+pub fn walk_parenthesized_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ParenthesizedNode,
+) {
+    visitor.visit_expression_mut(node.get_expression_mut());
+}
+
+pub fn walk_table_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut TableDeclarationNode,
+) {
+    for column in node.get_columns_mut() {
+        visitor.visit_column_mut(column);
+    }
+}
+
+pub fn walk_unary_operator_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut UnaryOperatorNode,
+) {
+    visitor.visit_expression_mut(node.get_operand_mut());
+}
+
+pub fn walk_variable_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut VariableDeclaration,
+) {
+    if let Some(value) = node.get_value_mut() {
+        visitor.visit_expression_mut(value);
+    }
+}
+
+pub fn walk_while_loop_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut WhileLoopNode) {
+    visitor.visit_expression_mut(node.get_condition_mut());
+    visitor.visit_body_mut(node.get_body_mut());
+}
+
+pub fn walk_parameter_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Parameter) {
+    if let Some(value) = node.get_value_mut() {
+        visitor.visit_expression_mut(value);
+    }
+}
+
+pub fn walk_column_mut<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Column) {
+    if let Some(default) = node.get_default_mut() {
+        visitor.visit_expression_mut(default);
+    }
+}
@@ -1,8 +1,9 @@
 use super::{body::BodyNode, expression::ExpressionNode};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct WhileLoopNode {
-    _condition: Box<dyn ExpressionNode>,
-    _body: BodyNode
+    condition: Box<dyn ExpressionNode>,
+    body: BodyNode,
 }
 
 impl ExpressionNode for WhileLoopNode {
@@ -13,9 +14,22 @@ impl ExpressionNode for WhileLoopNode {
 
 impl WhileLoopNode {
     pub fn new(condition: Box<dyn ExpressionNode>, body: BodyNode) -> Self {
-        WhileLoopNode {
-            _condition: condition,
-            _body: body
-        }
+        WhileLoopNode { condition, body }
+    }
+
+    pub fn get_condition(&self) -> &dyn ExpressionNode {
+        self.condition.as_ref()
+    }
+
+    pub fn get_condition_mut(&mut self) -> &mut Box<dyn ExpressionNode> {
+        &mut self.condition
+    }
+
+    pub fn get_body(&self) -> &BodyNode {
+        &self.body
+    }
+
+    pub fn get_body_mut(&mut self) -> &mut BodyNode {
+        &mut self.body
     }
 }
@@ -1,7 +1,9 @@
 pub struct Context {
     pub code_source: String,
-    pub position: u64,
-    pub line: u64,
+    // The byte offset in the code, only used by the lexer.
+    pub position: usize,
+    pub line: usize,
+    pub column: usize,
 }
 
 impl Context {
@@ -9,7 +11,8 @@ impl Context {
         Context {
             code_source,
             position: 0,
-            line: 0,
+            line: 1,
+            column: 1,
         }
     }
 
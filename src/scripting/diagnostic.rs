@@ -0,0 +1,400 @@
+use colored::{Color, Colorize};
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::ops::Deref;
+
+use super::source_map::SourceMap;
+use super::span::Span;
+use super::tokens::TokenType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    fn get_color(&self) -> Color {
+        match self {
+            Severity::Error => Color::BrightRed,
+            Severity::Warning => Color::BrightYellow,
+            Severity::Note => Color::BrightBlue,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+// Every kind of problem has its own code that never changes, so tooling
+// can tell problems apart without depending on the wording of messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticCode {
+    // Lexical problems
+    UnrecognizedCharacter,
+    UnterminatedString,
+    UnterminatedComment,
+    AlphanumericInNumber,
+    CharacterNearString,
+    NumberOutOfRange,
+    InvalidDigit,
+    // Syntax problems
+    UnexpectedToken,
+    UnexpectedEnd,
+    DuplicateVariant,
+    UnknownChangeKind,
+    RepeatedUnaryOperator,
+    MissingArgumentType,
+    PositionalAfterKeyword,
+    MissingMember,
+    DuplicateField,
+    NestingTooDeep,
+    // Formatting problems
+    MisplacedComment,
+    // Type problems
+    TypeMismatch,
+    UnknownType,
+    InvalidArguments,
+    // Name problems
+    UndefinedName,
+    DuplicateDeclaration,
+    ShadowedName,
+    StrayLoopControl,
+    ImmutableAssignment,
+    DeprecatedStatement,
+    InvalidAssignment,
+}
+
+impl DiagnosticCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticCode::UnrecognizedCharacter => "L0001",
+            DiagnosticCode::UnterminatedString => "L0002",
+            DiagnosticCode::UnterminatedComment => "L0003",
+            DiagnosticCode::AlphanumericInNumber => "L0004",
+            DiagnosticCode::CharacterNearString => "L0005",
+            DiagnosticCode::NumberOutOfRange => "L0006",
+            DiagnosticCode::InvalidDigit => "L0007",
+            DiagnosticCode::UnexpectedToken => "P0001",
+            DiagnosticCode::UnexpectedEnd => "P0002",
+            DiagnosticCode::DuplicateVariant => "P0003",
+            DiagnosticCode::UnknownChangeKind => "P0004",
+            DiagnosticCode::RepeatedUnaryOperator => "P0006",
+            DiagnosticCode::MissingArgumentType => "P0007",
+            DiagnosticCode::PositionalAfterKeyword => "P0008",
+            DiagnosticCode::MissingMember => "P0009",
+            DiagnosticCode::DuplicateField => "P0010",
+            DiagnosticCode::NestingTooDeep => "P0011",
+            DiagnosticCode::MisplacedComment => "F0001",
+            DiagnosticCode::TypeMismatch => "T0001",
+            DiagnosticCode::UnknownType => "T0002",
+            DiagnosticCode::InvalidArguments => "T0003",
+            DiagnosticCode::UndefinedName => "R0001",
+            DiagnosticCode::DuplicateDeclaration => "R0002",
+            DiagnosticCode::ShadowedName => "R0003",
+            DiagnosticCode::StrayLoopControl => "R0004",
+            DiagnosticCode::ImmutableAssignment => "R0005",
+            DiagnosticCode::DeprecatedStatement => "R0006",
+            DiagnosticCode::InvalidAssignment => "R0007",
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// A secondary place related to the problem, e.g. the previous declaration of a name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: DiagnosticCode,
+    pub message: String,
+    pub code_source: String,
+    pub span: Span,
+    // Written under the primary span, e.g. "expected ';' here".
+    pub span_label: Option<String>,
+    pub labels: Vec<Label>,
+    // The tokens that would have been accepted instead of the found one.
+    pub expected_tokens: Vec<TokenType>,
+}
+
+impl Diagnostic {
+    pub fn error(
+        code: DiagnosticCode,
+        message: impl Into<String>,
+        code_source: &str,
+        span: Span,
+    ) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            code_source: code_source.to_string(),
+            span,
+            span_label: None,
+            labels: vec![],
+            expected_tokens: vec![],
+        }
+    }
+
+    pub fn warning(
+        code: DiagnosticCode,
+        message: impl Into<String>,
+        code_source: &str,
+        span: Span,
+    ) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(code, message, code_source, span)
+        }
+    }
+
+    pub fn with_span_label(mut self, message: impl Into<String>) -> Self {
+        self.span_label = Some(message.into());
+        self
+    }
+
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_expected_tokens(mut self, expected_tokens: Vec<TokenType>) -> Self {
+        self.expected_tokens = expected_tokens;
+        self
+    }
+
+    // The colored form of the diagnostic for terminals.
+    pub fn render(&self, sources: &SourceMap) -> String {
+        self.format(sources, true)
+    }
+
+    pub fn render_plain(&self, sources: &SourceMap) -> String {
+        self.format(sources, false)
+    }
+
+    // Shows the lines the spans are on and underlines the spans, like rustc does.
+    // Without the code only the locations are shown.
+    fn format(&self, sources: &SourceMap, colored: bool) -> String {
+        let severity_color = self.severity.get_color();
+        let header = format!("{}[{}]", self.severity, self.code);
+        let mut annotations = vec![(
+            self.span,
+            self.span_label.as_deref().unwrap_or_default(),
+            true,
+        )];
+        annotations.extend(
+            self.labels
+                .iter()
+                .map(|label| (label.span, label.message.as_str(), false)),
+        );
+        let gutter_width = annotations
+            .iter()
+            .map(|(span, _, _)| span.line.to_string().len())
+            .max()
+            .unwrap();
+        let gutter = |line_number: &str| {
+            paint(
+                &format!("{:>width$} |", line_number, width = gutter_width),
+                Color::BrightBlue,
+                colored,
+            )
+        };
+
+        let mut lines = vec![
+            format!(
+                "{}: {}",
+                paint(&header, severity_color, colored),
+                self.message
+            ),
+            format!(
+                "{}{} {}",
+                " ".repeat(gutter_width),
+                paint("-->", Color::BrightBlue, colored),
+                self.get_location(&self.span)
+            ),
+        ];
+        let Some(code) = sources.get(&self.code_source) else {
+            for label in &self.labels {
+                lines.push(format!(
+                    "{} = note: {}, at {}",
+                    " ".repeat(gutter_width),
+                    label.message,
+                    self.get_location(&label.span)
+                ));
+            }
+            return lines.join("\n");
+        };
+
+        lines.push(gutter(""));
+        let mut line_numbers: Vec<usize> =
+            annotations.iter().map(|(span, _, _)| span.line).collect();
+        line_numbers.sort();
+        line_numbers.dedup();
+        for (index, line_number) in line_numbers.iter().enumerate() {
+            if index > 0 && line_number - line_numbers[index - 1] > 1 {
+                lines.push(paint("...", Color::BrightBlue, colored));
+            }
+            let Some(source_line) = code.lines().nth(line_number - 1) else {
+                continue;
+            };
+            lines.push(format!(
+                "{} {}",
+                gutter(&line_number.to_string()),
+                source_line
+            ));
+            for (span, message, is_primary) in &annotations {
+                if span.line != *line_number {
+                    continue;
+                }
+                let (marker, color) = match is_primary {
+                    true => ("^", severity_color),
+                    false => ("-", Color::BrightBlue),
+                };
+                // Tabs are kept, so the underline stays under the span.
+                let indent: String = source_line
+                    .chars()
+                    .take(span.column - 1)
+                    .map(|char| if char == '\t' { '\t' } else { ' ' })
+                    .collect();
+                let width = code
+                    .get(span.start..span.stop)
+                    .map_or(0, |text| {
+                        text.chars().take_while(|char| *char != '\n').count()
+                    })
+                    .max(1);
+                let underline = format!("{} {}", marker.repeat(width), message);
+                lines.push(format!(
+                    "{} {}{}",
+                    gutter(""),
+                    indent,
+                    paint(underline.trim_end(), color, colored)
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn get_location(&self, span: &Span) -> String {
+        format!("{}:{}:{}", self.code_source, span.line, span.column)
+    }
+}
+
+fn paint(text: &str, color: Color, colored: bool) -> String {
+    match colored {
+        true => text.color(color).bold().to_string(),
+        false => text.to_string(),
+    }
+}
+
+// Without the code at hand, only the message and the locations are written.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render_plain(&SourceMap::new()))
+    }
+}
+
+impl Error for Diagnostic {}
+
+impl From<Diagnostic> for io::Error {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Diagnostics::from(diagnostic).into()
+    }
+}
+
+impl From<Box<Diagnostic>> for io::Error {
+    fn from(diagnostic: Box<Diagnostic>) -> Self {
+        Diagnostics::from(*diagnostic).into()
+    }
+}
+
+// All the problems found in one piece of code, in the order they were found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics(pub Vec<Diagnostic>);
+
+impl Diagnostics {
+    // Diagnostics turned into io::Error by `?` can still be matched on.
+    pub fn from_io_error(error: &io::Error) -> Option<&Diagnostics> {
+        error.get_ref()?.downcast_ref::<Diagnostics>()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    pub fn render(&self, sources: &SourceMap) -> String {
+        self.iter()
+            .map(|diagnostic| diagnostic.render(sources))
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
+
+    pub fn render_plain(&self, sources: &SourceMap) -> String {
+        self.iter()
+            .map(|diagnostic| diagnostic.render_plain(sources))
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
+}
+
+impl Deref for Diagnostics {
+    type Target = [Diagnostic];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Diagnostic> for Diagnostics {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Diagnostics(vec![diagnostic])
+    }
+}
+
+impl From<Box<Diagnostic>> for Diagnostics {
+    fn from(diagnostic: Box<Diagnostic>) -> Self {
+        Diagnostics(vec![*diagnostic])
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, diagnostic) in self.iter().enumerate() {
+            if index > 0 {
+                write!(f, "\n\n")?;
+            }
+            write!(f, "{}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Diagnostics {}
+
+impl From<Diagnostics> for io::Error {
+    fn from(diagnostics: Diagnostics) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, diagnostics)
+    }
+}
@@ -1,13 +1,1450 @@
-pub struct Executor {}
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::rc::{Rc, Weak};
 
-impl Executor {
+use super::ast::array::ArrayNode;
+use super::ast::attach::AttachNode;
+use super::ast::binary_operator::BinaryOperatorNode;
+use super::ast::body::BodyNode;
+use super::ast::boolean::BooleanNode;
+use super::ast::call::{CallNode, CallType, BUILTIN_DATATYPES};
+use super::ast::conditional_tree::ConditionalTreeNode;
+use super::ast::document::DocumentNode;
+use super::ast::enum_declaration::EnumDeclarationNode;
+use super::ast::event_declaration::{ChangeKind, EventDeclarationNode};
+use super::ast::expression::ExpressionNode;
+use super::ast::for_loop::ForLoopNode;
+use super::ast::function_declaration::FunctionDeclarationNode;
+use super::ast::functional_return::FunctionalReturnNode;
+use super::ast::identifier::IdentifierNode;
+use super::ast::import::ImportNode;
+use super::ast::inspect::InspectNode;
+use super::ast::loop_control::{LoopControlNode, LoopControlType};
+use super::ast::manage::ManageNode;
+use super::ast::member::MemberNode;
+use super::ast::null::NullNode;
+use super::ast::number::NumberNode;
+use super::ast::package::PackageNode;
+use super::ast::parenthesized::ParenthesizedNode;
+use super::ast::string::StringNode;
+use super::ast::table_declaration::{Column, ColumnType, Constraint, TableDeclarationNode};
+use super::ast::unary_operator::UnaryOperatorNode;
+use super::ast::variable_declaration::VariableDeclaration;
+use super::ast::while_loop::WhileLoopNode;
+use super::number::{Decimal, Number};
+use super::tokens::TokenType;
+use super::value::{Fields, Value};
+
+// Scripts are evaluated recursively, so a runaway recursion in Blaze code
+// has to be stopped before it overflows the stack of the whole process.
+const MAX_CALL_DEPTH: usize = 128;
+
+type ScopeRef<'a> = Rc<RefCell<Scope<'a>>>;
+
+struct Binding<'a> {
+    value: Value,
+    is_mutable: bool,
+    // The declared type, which the assigned values are converted to.
+    datatype: Option<&'a str>,
+}
+
+#[derive(Clone)]
+struct Function<'a> {
+    declaration: &'a FunctionDeclarationNode,
+    // Functions can only be reached through the scope they are declared in,
+    // so a weak reference is enough and doesn't create reference cycles.
+    closure: Weak<RefCell<Scope<'a>>>,
+}
+
+#[derive(Default)]
+struct Scope<'a> {
+    bindings: HashMap<String, Binding<'a>>,
+    functions: HashMap<String, Function<'a>>,
+    enums: HashMap<String, &'a EnumDeclarationNode>,
+    parent: Option<ScopeRef<'a>>,
+}
+
+impl<'a> Scope<'a> {
+    fn new_child(parent: ScopeRef<'a>) -> ScopeRef<'a> {
+        Rc::new(RefCell::new(Scope {
+            parent: Some(parent),
+            ..Default::default()
+        }))
+    }
+}
+
+#[derive(Clone)]
+struct Event<'a> {
+    declaration: &'a EventDeclarationNode,
+    closure: ScopeRef<'a>,
+}
+
+// A row being written while the events of its table are handled. Handlers
+// change the row through `&table.column`, and the row is stored after all of them.
+// Deleted rows are pending as well, though the changes made to them are dropped.
+struct PendingWrite {
+    table: String,
+    row: Fields,
+}
+
+// Everything that stops the evaluation of the current node: errors as well as
+// the control flow statements that have to unwind up to a loop or a function.
+enum Interruption {
+    Error(io::Error),
+    Return(Value),
+    Break,
+    Continue,
+}
+
+impl From<io::Error> for Interruption {
+    fn from(error: io::Error) -> Self {
+        Interruption::Error(error)
+    }
+}
+
+type Evaluation = Result<Value, Interruption>;
+
+pub struct Executor<'a> {
+    scope: ScopeRef<'a>,
+    tables: HashMap<String, &'a TableDeclarationNode>,
+    rows: HashMap<String, Vec<Fields>>,
+    events: Vec<Event<'a>>,
+    pending_writes: Vec<PendingWrite>,
+    call_depth: usize,
+}
+
+impl<'a> Executor<'a> {
     pub fn new() -> Self {
-        Executor {}
+        Executor {
+            scope: Rc::new(RefCell::new(Scope::default())),
+            tables: HashMap::new(),
+            rows: HashMap::new(),
+            events: vec![],
+            pending_writes: vec![],
+            call_depth: 0,
+        }
+    }
+
+    // Evaluates the nodes right in the global scope, so declarations stay
+    // available for the following calls. The value of the last node is returned.
+    pub fn execute(&mut self, body: &'a BodyNode) -> io::Result<Value> {
+        let evaluation = self.evaluate_nodes(&body.nodes);
+        complete(evaluation)
+    }
+
+    // Builds a row of the table out of the given fields: the missing columns
+    // get their default values and every value is checked against its column.
+    pub fn create_row(&mut self, table_name: &str, mut fields: Fields) -> io::Result<Value> {
+        let table = self.get_table(table_name)?;
+        if let Some((name, _)) = fields
+            .iter()
+            .find(|(name, _)| table.get_column(name).is_none())
+        {
+            return Err(runtime_error(format!(
+                "table '{}' has no column '{}'",
+                table_name, name
+            )));
+        }
+
+        let mut row = Fields::new();
+        for column in table.get_columns() {
+            let position = fields
+                .iter()
+                .position(|(name, _)| name == column.get_name());
+            let value = match (position, column.get_default()) {
+                (Some(index), _) => fields.remove(index).1,
+                (None, Some(default_node)) => {
+                    let evaluation = self.evaluate(default_node);
+                    complete(evaluation)?
+                }
+                (None, None) => Value::Null,
+            };
+            self.check_column_value(table, column, &value)?;
+            row.push((column.get_name().to_string(), value));
+        }
+        if let Some((name, _)) = fields.first() {
+            return Err(runtime_error(format!(
+                "column '{}' is given more than once",
+                name
+            )));
+        }
+        Ok(Value::Document(row))
+    }
+
+    pub fn insert_row(&mut self, table_name: &str, fields: Fields) -> io::Result<Value> {
+        let Value::Document(row) = self.create_row(table_name, fields)? else {
+            unreachable!()
+        };
+        let row = self.write_row(table_name, ChangeKind::Insert, None, Some(row))?;
+        self.rows
+            .entry(table_name.to_string())
+            .or_default()
+            .push(row.clone());
+        Ok(Value::Document(row))
+    }
+
+    pub fn update_row(
+        &mut self,
+        table_name: &str,
+        index: usize,
+        fields: Fields,
+    ) -> io::Result<Value> {
+        let table = self.get_table(table_name)?;
+        let old_row = self.get_stored_row(table_name, index)?;
+        let mut row = old_row.clone();
+        for (name, value) in fields {
+            self.set_column_value(table, &mut row, &name, value)?;
+        }
+        let row = self.write_row(table_name, ChangeKind::Update, Some(&old_row), Some(row))?;
+        self.rows.get_mut(table_name).unwrap()[index] = row.clone();
+        Ok(Value::Document(row))
+    }
+
+    pub fn delete_row(&mut self, table_name: &str, index: usize) -> io::Result<Value> {
+        let old_row = self.get_stored_row(table_name, index)?;
+        self.write_row(table_name, ChangeKind::Delete, Some(&old_row), None)?;
+        self.rows.get_mut(table_name).unwrap().remove(index);
+        Ok(Value::Document(old_row))
+    }
+
+    pub fn get_rows(&self, table_name: &str) -> &[Fields] {
+        self.rows
+            .get(table_name)
+            .map_or(&[], |rows| rows.as_slice())
+    }
+
+    pub fn get_variable(&self, name: &str) -> Option<Value> {
+        let scope = self.find_scope(|scope| scope.bindings.contains_key(name))?;
+        let value = scope.borrow().bindings[name].value.clone();
+        Some(value)
+    }
+
+    fn get_table(&self, table_name: &str) -> io::Result<&'a TableDeclarationNode> {
+        self.tables
+            .get(table_name)
+            .copied()
+            .ok_or_else(|| runtime_error(format!("table '{}' is not declared", table_name)))
+    }
+
+    fn get_stored_row(&self, table_name: &str, index: usize) -> io::Result<Fields> {
+        self.get_table(table_name)?;
+        self.get_rows(table_name)
+            .get(index)
+            .cloned()
+            .ok_or_else(|| runtime_error(format!("table '{}' has no row {}", table_name, index)))
+    }
+
+    // Runs the handlers of the events the write fires and returns the row they leave.
+    // The events are chosen before any handler runs, so the changes made by handlers
+    // don't fire other events. If a handler fails, the whole write fails.
+    fn write_row(
+        &mut self,
+        table_name: &str,
+        change_kind: ChangeKind,
+        old_row: Option<&Fields>,
+        row: Option<Fields>,
+    ) -> io::Result<Fields> {
+        let fired_events: Vec<Event<'a>> = self
+            .events
+            .iter()
+            .filter(|event| {
+                let declaration = event.declaration;
+                declaration.get_table() == table_name
+                    && declaration.get_change_kind().includes(&change_kind)
+                    && declaration.get_column().is_none_or(|column| {
+                        let get_value =
+                            |row: Option<&Fields>| row.and_then(|row| get_row_value(row, column));
+                        get_value(old_row) != get_value(row.as_ref())
+                    })
+            })
+            .cloned()
+            .collect();
+
+        let old_value = old_row.map_or(Value::Null, |row| Value::Document(row.clone()));
+        let is_deleted = row.is_none();
+        self.pending_writes.push(PendingWrite {
+            table: table_name.to_string(),
+            row: row.or_else(|| old_row.cloned()).unwrap_or_default(),
+        });
+        let mut result = Ok(());
+        for event in fired_events {
+            let new_value = match is_deleted {
+                true => Value::Null,
+                false => Value::Document(self.pending_writes.last().unwrap().row.clone()),
+            };
+            result = self.handle_event(&event, old_value.clone(), new_value);
+            if result.is_err() {
+                break;
+            }
+        }
+        let pending_write = self.pending_writes.pop().unwrap();
+        result?;
+        Ok(pending_write.row)
+    }
+
+    fn handle_event(
+        &mut self,
+        event: &Event<'a>,
+        old_value: Value,
+        new_value: Value,
+    ) -> io::Result<()> {
+        let declaration = event.declaration;
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(runtime_error(format!(
+                "maximum call depth of {} exceeded in event '{}'",
+                MAX_CALL_DEPTH,
+                declaration.get_name()
+            )));
+        }
+        let event_scope = Scope::new_child(event.closure.clone());
+        let evaluation = self.in_scope(event_scope, |this| {
+            this.define_variable("old", old_value, false, None)?;
+            this.define_variable("new", new_value, false, None)?;
+            this.call_depth += 1;
+            let result = this.evaluate_nodes(&declaration.get_body().nodes);
+            this.call_depth -= 1;
+            result
+        });
+        complete(evaluation)?;
+        Ok(())
+    }
+
+    fn find_pending_write(&mut self, table_name: &str) -> io::Result<&mut Fields> {
+        self.pending_writes
+            .iter_mut()
+            .rev()
+            .find(|pending_write| pending_write.table == table_name)
+            .map(|pending_write| &mut pending_write.row)
+            .ok_or_else(|| {
+                runtime_error(format!(
+                    "no row of table '{}' is being written here",
+                    table_name
+                ))
+            })
+    }
+
+    fn evaluate_nodes(&mut self, nodes: &'a [Box<dyn ExpressionNode>]) -> Evaluation {
+        let mut last_value = Value::Null;
+        for node in nodes {
+            last_value = self.evaluate(node.as_ref())?;
+        }
+        Ok(last_value)
+    }
+
+    fn evaluate(&mut self, node: &'a dyn ExpressionNode) -> Evaluation {
+        if let Some(number) = node.downcast_ref::<NumberNode>() {
+            return Ok(Value::Number(number.get_value()));
+        }
+        if let Some(string) = node.downcast_ref::<StringNode>() {
+            return Ok(Value::String(string.get_value().to_string()));
+        }
+        if let Some(boolean) = node.downcast_ref::<BooleanNode>() {
+            return Ok(Value::Boolean(boolean.get_state()));
+        }
+        if node.downcast_ref::<NullNode>().is_some() {
+            return Ok(Value::Null);
+        }
+        if let Some(identifier) = node.downcast_ref::<IdentifierNode>() {
+            let name = identifier.get_name();
+            return self
+                .get_variable(name)
+                .ok_or_else(|| runtime_error(format!("'{}' is not defined", name)).into());
+        }
+        if let Some(parenthesized) = node.downcast_ref::<ParenthesizedNode>() {
+            return self.evaluate(parenthesized.get_expression());
+        }
+        if let Some(array) = node.downcast_ref::<ArrayNode>() {
+            let mut items = vec![];
+            for item in array.get_items() {
+                items.push(self.evaluate(item.as_ref())?);
+            }
+            return Ok(Value::Array(items));
+        }
+        if let Some(document) = node.downcast_ref::<DocumentNode>() {
+            let mut fields = Fields::new();
+            for (key, value) in document.get_fields() {
+                fields.push((key.clone(), self.evaluate(value.as_ref())?));
+            }
+            return Ok(Value::Document(fields));
+        }
+        if let Some(body) = node.downcast_ref::<BodyNode>() {
+            let scope = Scope::new_child(self.scope.clone());
+            return self.in_scope(scope, |this| this.evaluate_nodes(&body.nodes));
+        }
+        if let Some(declaration) = node.downcast_ref::<VariableDeclaration>() {
+            return self.declare_variable(declaration);
+        }
+        if let Some(declaration) = node.downcast_ref::<FunctionDeclarationNode>() {
+            return self.declare_function(declaration);
+        }
+        if let Some(operator) = node.downcast_ref::<BinaryOperatorNode>() {
+            return self.evaluate_binary_operator(operator);
+        }
+        if let Some(operator) = node.downcast_ref::<UnaryOperatorNode>() {
+            return self.evaluate_unary_operator(operator);
+        }
+        if let Some(tree) = node.downcast_ref::<ConditionalTreeNode>() {
+            return self.evaluate_conditional_tree(tree);
+        }
+        if let Some(while_loop) = node.downcast_ref::<WhileLoopNode>() {
+            return self.evaluate_while_loop(while_loop);
+        }
+        if let Some(for_loop) = node.downcast_ref::<ForLoopNode>() {
+            return self.evaluate_for_loop(for_loop);
+        }
+        if let Some(call) = node.downcast_ref::<CallNode>() {
+            return match call.get_call_type() {
+                CallType::Function => self.call_function(call),
+                CallType::Position => {
+                    let value = self.evaluate(call.get_callee())?;
+                    self.get_position(value, call)
+                }
+                CallType::Type => self.call_type(call),
+            };
+        }
+        if let Some(functional_return) = node.downcast_ref::<FunctionalReturnNode>() {
+            let value = match functional_return.get_value() {
+                Some(value_node) => self.evaluate(value_node)?,
+                None => Value::Null,
+            };
+            return Err(Interruption::Return(value));
+        }
+        if let Some(loop_control) = node.downcast_ref::<LoopControlNode>() {
+            return Err(match loop_control.get_control_type() {
+                LoopControlType::Break => Interruption::Break,
+                LoopControlType::Continue => Interruption::Continue,
+            });
+        }
+        if let Some(member) = node.downcast_ref::<MemberNode>() {
+            return self.evaluate_member(member);
+        }
+        if let Some(declaration) = node.downcast_ref::<EnumDeclarationNode>() {
+            return self.declare_enum(declaration);
+        }
+        if let Some(declaration) = node.downcast_ref::<TableDeclarationNode>() {
+            return self.declare_table(declaration);
+        }
+        if let Some(declaration) = node.downcast_ref::<EventDeclarationNode>() {
+            return self.declare_event(declaration);
+        }
+        // Packages, imports and attachments are resolved by the package loader beforehand,
+        // and the settings of manage blocks are read by ManageConfig. Inspections do nothing.
+        if node.downcast_ref::<PackageNode>().is_some()
+            || node.downcast_ref::<ImportNode>().is_some()
+            || node.downcast_ref::<AttachNode>().is_some()
+            || node.downcast_ref::<ManageNode>().is_some()
+            || node.downcast_ref::<InspectNode>().is_some()
+        {
+            return Ok(Value::Null);
+        }
+        Err(runtime_error(format!("{} cannot be evaluated", node.get_type())).into())
+    }
+
+    fn in_scope(
+        &mut self,
+        scope: ScopeRef<'a>,
+        evaluation: impl FnOnce(&mut Self) -> Evaluation,
+    ) -> Evaluation {
+        let previous_scope = std::mem::replace(&mut self.scope, scope);
+        let result = evaluation(self);
+        self.scope = previous_scope;
+        result
+    }
+
+    fn find_scope(&self, contains: impl Fn(&Scope<'a>) -> bool) -> Option<ScopeRef<'a>> {
+        let mut scope = Some(self.scope.clone());
+        while let Some(current_scope) = scope {
+            if contains(&current_scope.borrow()) {
+                return Some(current_scope);
+            }
+            scope = current_scope.borrow().parent.clone();
+        }
+        None
+    }
+
+    fn define_variable(
+        &mut self,
+        name: &str,
+        value: Value,
+        is_mutable: bool,
+        datatype: Option<&'a str>,
+    ) -> io::Result<()> {
+        if self.scope.borrow().bindings.contains_key(name) {
+            return Err(runtime_error(format!(
+                "'{}' is already declared in this scope",
+                name
+            )));
+        }
+        let value = self.bind_value(name, datatype, value)?;
+        let binding = Binding {
+            value,
+            is_mutable,
+            datatype,
+        };
+        self.scope
+            .borrow_mut()
+            .bindings
+            .insert(name.to_string(), binding);
+        Ok(())
+    }
+
+    fn assign_variable(&mut self, name: &str, value: Value) -> io::Result<()> {
+        let scope = self
+            .find_scope(|scope| scope.bindings.contains_key(name))
+            .ok_or_else(|| runtime_error(format!("'{}' is not defined", name)))?;
+        let (is_mutable, datatype) = {
+            let binding = &scope.borrow().bindings[name];
+            (binding.is_mutable, binding.datatype)
+        };
+        if !is_mutable {
+            return Err(runtime_error(format!(
+                "cannot assign twice to immutable variable '{}'",
+                name
+            )));
+        }
+        let value = self.bind_value(name, datatype, value)?;
+        scope.borrow_mut().bindings.get_mut(name).unwrap().value = value;
+        Ok(())
+    }
+
+    // Values are stored as the declared type, so `fin x: float = 1` holds a float. Like in the
+    // type checker only integers are converted, and values of enums have to be their variants.
+    // Tables, arrays and type parameters aren't checked.
+    fn bind_value(&self, name: &str, datatype: Option<&str>, value: Value) -> io::Result<Value> {
+        let Some(datatype) = datatype else {
+            return Ok(value);
+        };
+        let bound_value = match (datatype, &value) {
+            (_, Value::Null) => Some(value.clone()),
+            ("float", Value::Number(Number::Float(_)))
+            | ("decimal", Value::Number(Number::Decimal(_)))
+            | ("str" | "datetime" | "uuid", Value::String(_))
+            | ("bool", Value::Boolean(_)) => Some(value.clone()),
+            (
+                "int" | "uint" | "float" | "decimal",
+                Value::Number(number @ (Number::Int(_) | Number::Uint(_))),
+            ) => number.convert(datatype).map(Value::Number),
+            ("str" | "int" | "uint" | "float" | "decimal" | "bool" | "datetime" | "uuid", _) => {
+                None
+            }
+            _ => match self.find_enum(datatype) {
+                Some(declaration) => {
+                    Some(value.clone()).filter(|value| is_variant(declaration, value))
+                }
+                None => Some(value.clone()),
+            },
+        };
+        bound_value.ok_or_else(|| {
+            runtime_error(format!(
+                "{} cannot be stored in '{}', which is declared as '{}'",
+                value, name, datatype
+            ))
+        })
+    }
+
+    fn declare_variable(&mut self, declaration: &'a VariableDeclaration) -> Evaluation {
+        let value = match declaration.get_value() {
+            Some(value_node) => self.evaluate(value_node)?,
+            None => Value::Null,
+        };
+        self.define_variable(
+            declaration.get_name(),
+            value,
+            declaration.is_mutable(),
+            declaration.get_datatype(),
+        )?;
+        Ok(Value::Null)
+    }
+
+    fn declare_function(&mut self, declaration: &'a FunctionDeclarationNode) -> Evaluation {
+        let name = declaration.get_name();
+        let mut scope = self.scope.borrow_mut();
+        if scope.functions.contains_key(name) {
+            return Err(runtime_error(format!(
+                "function '{}' is already declared in this scope",
+                name
+            ))
+            .into());
+        }
+        let function = Function {
+            declaration,
+            closure: Rc::downgrade(&self.scope),
+        };
+        scope.functions.insert(name.to_string(), function);
+        Ok(Value::Null)
+    }
+
+    fn find_enum(&self, name: &str) -> Option<&'a EnumDeclarationNode> {
+        let scope = self.find_scope(|scope| scope.enums.contains_key(name))?;
+        let declaration = scope.borrow().enums[name];
+        Some(declaration)
+    }
+
+    fn declare_enum(&mut self, declaration: &'a EnumDeclarationNode) -> Evaluation {
+        let name = declaration.get_name();
+        if let Some(datatype) = declaration.get_datatype() {
+            if !["str", "int", "uint"].contains(&datatype) {
+                return Err(runtime_error(format!(
+                    "enum '{}' cannot be backed by '{}', only by 'str', 'int' or 'uint'",
+                    name, datatype
+                ))
+                .into());
+            }
+        }
+        let mut scope = self.scope.borrow_mut();
+        if scope.enums.contains_key(name) {
+            return Err(runtime_error(format!(
+                "enum '{}' is already declared in this scope",
+                name
+            ))
+            .into());
+        }
+        scope.enums.insert(name.to_string(), declaration);
+        Ok(Value::Null)
+    }
+
+    fn declare_table(&mut self, declaration: &'a TableDeclarationNode) -> Evaluation {
+        let name = declaration.get_name();
+        if self.tables.contains_key(name) {
+            return Err(runtime_error(format!("table '{}' is already declared", name)).into());
+        }
+        self.tables.insert(name.to_string(), declaration);
+        Ok(Value::Null)
+    }
+
+    fn declare_event(&mut self, declaration: &'a EventDeclarationNode) -> Evaluation {
+        let name = declaration.get_name();
+        if self
+            .events
+            .iter()
+            .any(|event| event.declaration.get_name() == name)
+        {
+            return Err(runtime_error(format!("event '{}' is already declared", name)).into());
+        }
+        let table = self.get_table(declaration.get_table())?;
+        if let Some(column) = declaration.get_column() {
+            if table.get_column(column).is_none() {
+                return Err(runtime_error(format!(
+                    "table '{}' has no column '{}'",
+                    table.get_name(),
+                    column
+                ))
+                .into());
+            }
+        }
+        self.events.push(Event {
+            declaration,
+            closure: self.scope.clone(),
+        });
+        Ok(Value::Null)
+    }
+
+    fn check_column_value(
+        &self,
+        table: &TableDeclarationNode,
+        column: &Column,
+        value: &Value,
+    ) -> io::Result<()> {
+        for constraint in column.get_constraints() {
+            if !satisfies(value, constraint)? {
+                return Err(runtime_error(format!(
+                    "{} does not satisfy '{}' in column '{}.{}'",
+                    value,
+                    constraint,
+                    table.get_name(),
+                    column.get_name()
+                )));
+            }
+        }
+        let ColumnType::Datatype(datatype) = column.get_column_type() else {
+            return Ok(());
+        };
+        let Some(declaration) = self.find_enum(datatype) else {
+            return Ok(());
+        };
+        if *value != Value::Null && !is_variant(declaration, value) {
+            return Err(runtime_error(format!(
+                "{} is not a variant of '{}' in column '{}.{}'",
+                value,
+                datatype,
+                table.get_name(),
+                column.get_name()
+            )));
+        }
+        Ok(())
+    }
+
+    fn set_column_value(
+        &self,
+        table: &TableDeclarationNode,
+        row: &mut Fields,
+        column_name: &str,
+        value: Value,
+    ) -> io::Result<()> {
+        let column = table.get_column(column_name).ok_or_else(|| {
+            runtime_error(format!(
+                "table '{}' has no column '{}'",
+                table.get_name(),
+                column_name
+            ))
+        })?;
+        self.check_column_value(table, column, &value)?;
+        let field = row
+            .iter_mut()
+            .find(|(name, _)| name == column_name)
+            .unwrap();
+        field.1 = value;
+        Ok(())
+    }
+
+    fn evaluate_member(&mut self, member: &'a MemberNode) -> Evaluation {
+        let parent = member.get_parent();
+        if let Some(identifier) = parent.downcast_ref::<IdentifierNode>() {
+            let name = identifier.get_name();
+            // Variables shadow the enums declared with the same name.
+            if let (None, Some(declaration)) = (self.get_variable(name), self.find_enum(name)) {
+                let (variant_node, rest) = split_member(member.get_child());
+                let variant = get_member_name(variant_node)?;
+                let index = declaration
+                    .get_variants()
+                    .iter()
+                    .position(|declared_variant| declared_variant == variant)
+                    .ok_or_else(|| {
+                        runtime_error(format!("enum '{}' has no variant '{}'", name, variant))
+                    })?;
+                let value = get_enum_value(declaration, index);
+                return match rest {
+                    Some(rest) => self.access_member(value, rest),
+                    None => Ok(value),
+                };
+            }
+        }
+        let value = self.evaluate(parent)?;
+        self.access_member(value, member.get_child())
+    }
+
+    // Members can be followed by positions, like `cart.products[0]`.
+    fn access_member(&mut self, value: Value, node: &'a dyn ExpressionNode) -> Evaluation {
+        let (member_node, rest) = split_member(node);
+        let position_call = member_node
+            .downcast_ref::<CallNode>()
+            .filter(|call| *call.get_call_type() == CallType::Position);
+        let member_value = match position_call {
+            Some(call) => {
+                let value = self.access_member(value, call.get_callee())?;
+                self.get_position(value, call)?
+            }
+            None => {
+                let name = get_member_name(member_node)?;
+                value.get_field(name).cloned().ok_or_else(|| {
+                    runtime_error(format!(
+                        "'{}' has no member '{}'",
+                        value.get_type_name(),
+                        name
+                    ))
+                })?
+            }
+        };
+        match rest {
+            Some(rest) => self.access_member(member_value, rest),
+            None => Ok(member_value),
+        }
+    }
+
+    fn get_position(&mut self, value: Value, call: &'a CallNode) -> Evaluation {
+        if let Some((start, stop)) = call.get_slice_bounds() {
+            let start = match start {
+                Some(start_node) => Some(self.evaluate(start_node)?),
+                None => None,
+            };
+            let stop = match stop {
+                Some(stop_node) => Some(self.evaluate(stop_node)?),
+                None => None,
+            };
+            return Ok(get_slice(value, start, stop)?);
+        }
+        let index_node = call.get_arguments()[0].get_value().unwrap();
+        let index = self.evaluate(index_node)?;
+        Ok(get_item(value, index)?)
+    }
+
+    // Built-in types cast their only argument, while tables build a row out of
+    // the arguments, matched to the columns by position or by name.
+    fn call_type(&mut self, call: &'a CallNode) -> Evaluation {
+        let name = get_member_name(call.get_callee())?;
+        let mut values = vec![];
+        for argument in call.get_arguments() {
+            let value = match argument.get_value() {
+                Some(value_node) => self.evaluate(value_node)?,
+                None => Value::Null,
+            };
+            values.push((argument.get_name(), value));
+        }
+        if BUILTIN_DATATYPES.contains(&name) {
+            return match values.as_slice() {
+                [("", value)] => Ok(cast(name, value.clone())?),
+                _ => Err(runtime_error(format!(
+                    "'{}' takes 1 positional argument but {} were given",
+                    name,
+                    values.len()
+                ))
+                .into()),
+            };
+        }
+        let table = self.get_table(name)?;
+        let mut fields = Fields::new();
+        for (index, (keyword, value)) in values.into_iter().enumerate() {
+            let column_name = match keyword {
+                "" => table
+                    .get_columns()
+                    .get(index)
+                    .map(|column| column.get_name()),
+                _ => Some(keyword),
+            };
+            let column_name = column_name.ok_or_else(|| {
+                runtime_error(format!(
+                    "table '{}' has {} columns but {} values were given",
+                    name,
+                    table.get_columns().len(),
+                    call.get_arguments().len()
+                ))
+            })?;
+            fields.push((column_name.to_string(), value));
+        }
+        Ok(self.create_row(name, fields)?)
+    }
+
+    // Items are put into a copy of what holds them, which is assigned in turn,
+    // so `a[0][1] = 5` changes `a`.
+    fn assign(&mut self, target: &'a dyn ExpressionNode, value: Value) -> Result<(), Interruption> {
+        let item_call = target.downcast_ref::<CallNode>().filter(|call| {
+            *call.get_call_type() == CallType::Position && call.get_slice_bounds().is_none()
+        });
+        let Some(call) = item_call else {
+            let name = get_assignable_name(target)?;
+            return Ok(self.assign_variable(name, value)?);
+        };
+        let holder = self.evaluate(call.get_callee())?;
+        let index = self.evaluate(call.get_arguments()[0].get_value().unwrap())?;
+        let holder = set_item(holder, index, value)?;
+        self.assign(call.get_callee(), holder)
+    }
+
+    fn evaluate_binary_operator(&mut self, operator_node: &'a BinaryOperatorNode) -> Evaluation {
+        let operator = operator_node.get_operator();
+        match operator {
+            x if x.is_assignment() => {
+                let target = operator_node.get_left_operand();
+                let value = match x.get_compound_operator() {
+                    Some(compound_operator) => {
+                        let left_value = self.evaluate(target)?;
+                        let right_value = self.evaluate(operator_node.get_right_operand())?;
+                        let is_integer = is_integer(&left_value) && is_integer(&right_value);
+                        let value =
+                            apply_operator(&compound_operator, left_value.clone(), right_value)?;
+                        // Integers divided in place stay integers, so `x /= 2` keeps x an int.
+                        match value {
+                            Value::Number(number) if is_integer => {
+                                let type_name = left_value.get_type_name();
+                                number
+                                    .convert(type_name)
+                                    .map(Value::Number)
+                                    .ok_or_else(|| out_of_range(&compound_operator))?
+                            }
+                            _ => value,
+                        }
+                    }
+                    None => self.evaluate(operator_node.get_right_operand())?,
+                };
+                if let Some((table_name, column)) =
+                    get_column_reference(operator_node.get_left_operand())?
+                {
+                    let table = self.get_table(table_name)?;
+                    let mut row = std::mem::take(self.find_pending_write(table_name)?);
+                    let result = self.set_column_value(table, &mut row, column, value.clone());
+                    *self.find_pending_write(table_name)? = row;
+                    result?;
+                    return Ok(value);
+                }
+                self.assign(target, value.clone())?;
+                return Ok(value);
+            }
+            TokenType::And | TokenType::Or => {
+                let left_state = self.evaluate_condition(operator_node.get_left_operand())?;
+                // The right operand is only evaluated when it can change the result.
+                if left_state == (*operator == TokenType::Or) {
+                    return Ok(Value::Boolean(left_state));
+                }
+                let right_state = self.evaluate_condition(operator_node.get_right_operand())?;
+                return Ok(Value::Boolean(right_state));
+            }
+            _ => {}
+        }
+
+        let left_value = self.evaluate(operator_node.get_left_operand())?;
+        let right_value = self.evaluate(operator_node.get_right_operand())?;
+        Ok(apply_operator(operator, left_value, right_value)?)
+    }
+
+    fn evaluate_unary_operator(&mut self, operator_node: &'a UnaryOperatorNode) -> Evaluation {
+        let operator = operator_node.get_operator();
+        match operator {
+            TokenType::Negotion => {
+                let state = self.evaluate_condition(operator_node.get_operand())?;
+                Ok(Value::Boolean(!state))
+            }
+            TokenType::Subtraction => match self.evaluate(operator_node.get_operand())? {
+                Value::Number(number) => Ok(Value::Number(
+                    number.checked_neg().ok_or_else(|| out_of_range(operator))?,
+                )),
+                value => Err(runtime_error(format!(
+                    "'{}' cannot be applied to '{}'",
+                    get_operator_text(operator),
+                    value.get_type_name()
+                ))
+                .into()),
+            },
+            TokenType::Increment | TokenType::Decrement => {
+                let value = match self.evaluate(operator_node.get_operand())? {
+                    Value::Number(number) => {
+                        let step = match operator {
+                            TokenType::Increment => number.checked_add(Number::Int(1)),
+                            _ => number.checked_sub(Number::Int(1)),
+                        };
+                        Value::Number(step.ok_or_else(|| out_of_range(operator))?)
+                    }
+                    value => {
+                        return Err(runtime_error(format!(
+                            "'{}' cannot be applied to '{}'",
+                            get_operator_text(operator),
+                            value.get_type_name()
+                        ))
+                        .into())
+                    }
+                };
+                self.assign(operator_node.get_operand(), value.clone())?;
+                Ok(value)
+            }
+            TokenType::Link => {
+                // `&table` queries all the rows of the table.
+                if let Some(identifier) =
+                    operator_node.get_operand().downcast_ref::<IdentifierNode>()
+                {
+                    let table_name = identifier.get_name();
+                    self.get_table(table_name)?;
+                    let rows = self
+                        .get_rows(table_name)
+                        .iter()
+                        .cloned()
+                        .map(Value::Document);
+                    return Ok(Value::Array(rows.collect()));
+                }
+                let Some((table_name, column)) = get_column_reference(operator_node)? else {
+                    unreachable!()
+                };
+                let row = self.find_pending_write(table_name)?;
+                get_row_value(row, column).ok_or_else(|| {
+                    runtime_error(format!("table '{}' has no column '{}'", table_name, column))
+                        .into()
+                })
+            }
+            _ => Err(runtime_error(format!(
+                "'{}' operator is not supported yet",
+                get_operator_text(operator)
+            ))
+            .into()),
+        }
+    }
+
+    fn evaluate_condition(&mut self, node: &'a dyn ExpressionNode) -> Result<bool, Interruption> {
+        match self.evaluate(node)? {
+            Value::Boolean(state) => Ok(state),
+            value => Err(runtime_error(format!(
+                "condition must be 'bool', found '{}'",
+                value.get_type_name()
+            ))
+            .into()),
+        }
+    }
+
+    fn evaluate_conditional_tree(&mut self, tree: &'a ConditionalTreeNode) -> Evaluation {
+        for (condition, consequence) in tree.get_conditions() {
+            if self.evaluate_condition(condition.as_ref())? {
+                return self.evaluate(consequence.as_ref());
+            }
+        }
+        match tree.get_default() {
+            Some(default) => self.evaluate(default),
+            None => Ok(Value::Null),
+        }
+    }
+
+    fn evaluate_while_loop(&mut self, while_loop: &'a WhileLoopNode) -> Evaluation {
+        while self.evaluate_condition(while_loop.get_condition())? {
+            match self.evaluate(while_loop.get_body()) {
+                Ok(_) | Err(Interruption::Continue) => continue,
+                Err(Interruption::Break) => break,
+                Err(interruption) => return Err(interruption),
+            }
+        }
+        Ok(Value::Null)
+    }
+
+    fn evaluate_for_loop(&mut self, for_loop: &'a ForLoopNode) -> Evaluation {
+        let items = match self.evaluate(for_loop.get_collection())? {
+            Value::Array(items) => items,
+            value => {
+                return Err(runtime_error(format!(
+                    "'{}' cannot be iterated over",
+                    value.get_type_name()
+                ))
+                .into())
+            }
+        };
+        for item in items {
+            let item_scope = Scope::new_child(self.scope.clone());
+            let evaluation = self.in_scope(item_scope, |this| {
+                this.define_variable(for_loop.get_item(), item, false, None)?;
+                this.evaluate(for_loop.get_body())
+            });
+            match evaluation {
+                Ok(_) | Err(Interruption::Continue) => continue,
+                Err(Interruption::Break) => break,
+                Err(interruption) => return Err(interruption),
+            }
+        }
+        Ok(Value::Null)
+    }
+
+    fn call_function(&mut self, call: &'a CallNode) -> Evaluation {
+        let name = call
+            .get_callee()
+            .downcast_ref::<IdentifierNode>()
+            .ok_or_else(|| {
+                runtime_error(format!("{} is not callable", call.get_callee().get_type()))
+            })?
+            .get_name();
+        let function = self
+            .find_scope(|scope| scope.functions.contains_key(name))
+            .map(|scope| scope.borrow().functions[name].clone())
+            .ok_or_else(|| runtime_error(format!("function '{}' is not defined", name)))?;
+        let declaration = function.declaration;
+        let body = declaration
+            .get_body()
+            .ok_or_else(|| runtime_error(format!("function '{}' has no body to execute", name)))?;
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(runtime_error(format!(
+                "maximum call depth of {} exceeded in '{}'",
+                MAX_CALL_DEPTH, name
+            ))
+            .into());
+        }
+
+        let mut positional_values = vec![];
+        let mut keyword_values = vec![];
+        for argument in call.get_arguments() {
+            let value = match argument.get_value() {
+                Some(value_node) => self.evaluate(value_node)?,
+                None => Value::Null,
+            };
+            if argument.is_positional() {
+                positional_values.push(value);
+            } else {
+                keyword_values.push((argument.get_name(), value));
+            }
+        }
+        let parameters = declaration.get_arguments();
+        if positional_values.len() > parameters.len() {
+            return Err(runtime_error(format!(
+                "'{}' takes {} arguments but {} were given",
+                name,
+                parameters.len(),
+                positional_values.len()
+            ))
+            .into());
+        }
+
+        let closure = function
+            .closure
+            .upgrade()
+            .ok_or_else(|| runtime_error(format!("function '{}' is out of scope", name)))?;
+        let function_scope = Scope::new_child(closure);
+        let mut positional_values = positional_values.into_iter();
+        let result = self.in_scope(function_scope, |this| {
+            for parameter in parameters {
+                let parameter_name = parameter.get_name();
+                let keyword_index = keyword_values
+                    .iter()
+                    .position(|(keyword, _)| *keyword == parameter_name);
+                let value = match (positional_values.next(), keyword_index) {
+                    (Some(_), Some(_)) => {
+                        return Err(runtime_error(format!(
+                            "'{}' got multiple values for argument '{}'",
+                            name, parameter_name
+                        ))
+                        .into())
+                    }
+                    (Some(value), None) => value,
+                    (None, Some(index)) => keyword_values.remove(index).1,
+                    (None, None) => match parameter.get_value() {
+                        Some(default_node) => this.evaluate(default_node)?,
+                        None => {
+                            return Err(runtime_error(format!(
+                                "'{}' is missing argument '{}'",
+                                name, parameter_name
+                            ))
+                            .into())
+                        }
+                    },
+                };
+                this.define_variable(parameter_name, value, false, parameter.get_datatype())?;
+            }
+            if let Some((keyword, _)) = keyword_values.first() {
+                return Err(runtime_error(format!(
+                    "'{}' got an unexpected argument '{}'",
+                    name, keyword
+                ))
+                .into());
+            }
+
+            this.call_depth += 1;
+            let result = this.evaluate_nodes(&body.nodes);
+            this.call_depth -= 1;
+            result
+        });
+
+        match result {
+            Ok(value) | Err(Interruption::Return(value)) => Ok(value),
+            Err(Interruption::Break) | Err(Interruption::Continue) => Err(runtime_error(format!(
+                "loop control escapes the body of function '{}'",
+                name
+            ))
+            .into()),
+            Err(error) => Err(error),
+        }
     }
 }
 
-impl Default for Executor {
+impl Default for Executor<'_> {
     fn default() -> Self {
         Self::new()
     }
 }
+
+fn complete(evaluation: Evaluation) -> io::Result<Value> {
+    match evaluation {
+        Ok(value) | Err(Interruption::Return(value)) => Ok(value),
+        Err(Interruption::Error(error)) => Err(error),
+        Err(Interruption::Break) => Err(runtime_error("'break' outside of a loop")),
+        Err(Interruption::Continue) => Err(runtime_error("'continue' outside of a loop")),
+    }
+}
+
+fn runtime_error(message: impl AsRef<str>) -> io::Error {
+    io::Error::other(format!("Runtime Error: {}", message.as_ref()))
+}
+
+fn unsupported_operands(operator: &TokenType, left: &Value, right: &Value) -> io::Error {
+    runtime_error(format!(
+        "'{}' is not supported between '{}' and '{}'",
+        get_operator_text(operator),
+        left.get_type_name(),
+        right.get_type_name()
+    ))
+}
+
+fn out_of_range(operator: &TokenType) -> io::Error {
+    runtime_error(format!(
+        "the result of '{}' is out of range",
+        get_operator_text(operator)
+    ))
+}
+
+// Operators are written in messages the way they are in the code, e.g. '^' rather than 'Hat'.
+fn get_operator_text(operator: &TokenType) -> &'static str {
+    operator.get_text().unwrap_or_default()
+}
+
+fn get_assignable_name(node: &dyn ExpressionNode) -> io::Result<&str> {
+    node.downcast_ref::<IdentifierNode>()
+        .map(|identifier| identifier.get_name())
+        .ok_or_else(|| runtime_error(format!("{} cannot be assigned to", node.get_type())))
+}
+
+// Only the columns of the row being written can be referenced by `&table.column` for now.
+fn get_column_reference(node: &dyn ExpressionNode) -> io::Result<Option<(&str, &str)>> {
+    let Some(operator_node) = node
+        .downcast_ref::<UnaryOperatorNode>()
+        .filter(|operator_node| *operator_node.get_operator() == TokenType::Link)
+    else {
+        return Ok(None);
+    };
+    let operand = operator_node.get_operand();
+    let (table_name, column) = operand
+        .downcast_ref::<MemberNode>()
+        .and_then(|member| {
+            let table = member.get_parent().downcast_ref::<IdentifierNode>()?;
+            let column = member.get_child().downcast_ref::<IdentifierNode>()?;
+            Some((table.get_name(), column.get_name()))
+        })
+        .ok_or_else(|| runtime_error("'&' must be followed by 'table.column'"))?;
+    Ok(Some((table_name, column)))
+}
+
+fn get_row_value(row: &Fields, column: &str) -> Option<Value> {
+    row.iter()
+        .find(|(name, _)| name == column)
+        .map(|(_, value)| value.clone())
+}
+
+// Enums backed by numbers are valued by the position of a variant,
+// while the others are valued by its name.
+fn get_enum_value(declaration: &EnumDeclarationNode, index: usize) -> Value {
+    match declaration.get_datatype() {
+        Some("int") => Value::Number(Number::Int(index as i64)),
+        Some("uint") => Value::Number(Number::Uint(index as u64)),
+        _ => Value::String(declaration.get_variants()[index].clone()),
+    }
+}
+
+fn is_integer(value: &Value) -> bool {
+    matches!(value, Value::Number(Number::Int(_) | Number::Uint(_)))
+}
+
+fn is_variant(declaration: &EnumDeclarationNode, value: &Value) -> bool {
+    (0..declaration.get_variants().len()).any(|index| get_enum_value(declaration, index) == *value)
+}
+
+// Numbers are constrained by their values, while strings and arrays are by their lengths.
+fn satisfies(value: &Value, constraint: &Constraint) -> io::Result<bool> {
+    let measure = match value {
+        Value::Number(number) => *number,
+        Value::String(string) => Number::Uint(string.chars().count() as u64),
+        Value::Array(items) => Number::Uint(items.len() as u64),
+        _ => return Ok(true),
+    };
+    match constraint {
+        Constraint::Bound(operator, bound) => {
+            Ok(compare(operator, measure, *bound)? == Value::Boolean(true))
+        }
+        Constraint::Exact(exact) => Ok(measure == *exact),
+    }
+}
+
+// Members are nested to the right, so `a.b.c` is split into `b` and `c`.
+fn split_member(node: &dyn ExpressionNode) -> (&dyn ExpressionNode, Option<&dyn ExpressionNode>) {
+    match node.downcast_ref::<MemberNode>() {
+        Some(member) => (member.get_parent(), Some(member.get_child())),
+        None => (node, None),
+    }
+}
+
+fn get_member_name(node: &dyn ExpressionNode) -> io::Result<&str> {
+    node.downcast_ref::<IdentifierNode>()
+        .map(|identifier| identifier.get_name())
+        .ok_or_else(|| {
+            runtime_error(format!(
+                "{} cannot be accessed as a member",
+                node.get_type()
+            ))
+        })
+}
+
+// Negative positions count from the end, so `items[-1]` is the last item.
+fn get_position_index(index: &Value, length: usize) -> io::Result<i64> {
+    let position = match index {
+        Value::Number(number) => number
+            .to_i128()
+            .and_then(|number| i64::try_from(number).ok()),
+        _ => None,
+    };
+    match position {
+        Some(position) if position < 0 => Ok(position + length as i64),
+        Some(position) => Ok(position),
+        None => Err(runtime_error(format!(
+            "positions must be whole numbers, found {}",
+            index
+        ))),
+    }
+}
+
+// Documents are indexed by the names of their fields.
+fn get_item(value: Value, index: Value) -> io::Result<Value> {
+    let item = match (&value, &index) {
+        (Value::Document(_), Value::String(name)) => value.get_field(name).cloned(),
+        (Value::Array(items), Value::Number(_)) => {
+            let position = get_position_index(&index, items.len())?;
+            usize::try_from(position)
+                .ok()
+                .and_then(|position| items.get(position).cloned())
+        }
+        (Value::String(string), Value::Number(_)) => {
+            let position = get_position_index(&index, string.chars().count())?;
+            usize::try_from(position)
+                .ok()
+                .and_then(|position| string.chars().nth(position))
+                .map(|character| Value::String(character.to_string()))
+        }
+        _ => {
+            return Err(runtime_error(format!(
+                "'{}' cannot be indexed by '{}'",
+                value.get_type_name(),
+                index.get_type_name()
+            )))
+        }
+    };
+    item.ok_or_else(|| runtime_error(format!("{} has no item at {}", value, index)))
+}
+
+// Documents get the fields that they don't have yet, while arrays only have their items replaced.
+fn set_item(value: Value, index: Value, item: Value) -> io::Result<Value> {
+    match (value, &index) {
+        (Value::Document(mut fields), Value::String(name)) => {
+            match fields.iter_mut().find(|(field_name, _)| field_name == name) {
+                Some(field) => field.1 = item,
+                None => fields.push((name.clone(), item)),
+            }
+            Ok(Value::Document(fields))
+        }
+        (Value::Array(mut items), Value::Number(_)) => {
+            let position = get_position_index(&index, items.len())?;
+            match usize::try_from(position) {
+                Ok(position) if position < items.len() => {
+                    items[position] = item;
+                    Ok(Value::Array(items))
+                }
+                _ => Err(runtime_error(format!(
+                    "{} has no item at {}",
+                    Value::Array(items),
+                    index
+                ))),
+            }
+        }
+        (value, _) => Err(runtime_error(format!(
+            "items of '{}' cannot be assigned by '{}'",
+            value.get_type_name(),
+            index.get_type_name()
+        ))),
+    }
+}
+
+// Bounds out of the range are clamped, like slices of the end that don't exist are empty.
+fn get_slice(value: Value, start: Option<Value>, stop: Option<Value>) -> io::Result<Value> {
+    let length = match &value {
+        Value::Array(items) => items.len(),
+        Value::String(string) => string.chars().count(),
+        _ => {
+            return Err(runtime_error(format!(
+                "'{}' cannot be sliced",
+                value.get_type_name()
+            )))
+        }
+    };
+    let get_bound = |bound: Option<Value>, default: usize| -> io::Result<usize> {
+        match bound {
+            None | Some(Value::Null) => Ok(default),
+            Some(bound) => {
+                let position = get_position_index(&bound, length)?;
+                Ok(position.clamp(0, length as i64) as usize)
+            }
+        }
+    };
+    let start = get_bound(start, 0)?;
+    let stop = get_bound(stop, length)?.max(start);
+    Ok(match value {
+        Value::Array(items) => Value::Array(items[start..stop].to_vec()),
+        Value::String(string) => {
+            Value::String(string.chars().skip(start).take(stop - start).collect())
+        }
+        _ => unreachable!(),
+    })
+}
+
+// Strings are read like the literals in the code, so `int("0xFF")` is 255,
+// except for decimals, which are read exactly.
+fn cast(datatype: &str, value: Value) -> io::Result<Value> {
+    let number = match &value {
+        Value::Boolean(state) => Some(Number::Int(i64::from(*state))),
+        Value::Number(number) => Some(*number),
+        Value::String(string) if datatype == "decimal" => {
+            Decimal::parse(string.trim()).map(Number::Decimal)
+        }
+        Value::String(string) => Number::parse_literal(string.trim()).ok(),
+        _ => None,
+    };
+    let cast_value = match (datatype, &value) {
+        (_, Value::Null) => Some(Value::Null),
+        ("str", Value::String(_)) => Some(value.clone()),
+        ("str", _) => Some(Value::String(value.to_string())),
+        ("int" | "uint" | "float" | "decimal", _) => number
+            .and_then(|number| number.convert(datatype))
+            .map(Value::Number),
+        ("bool", Value::Boolean(_)) => Some(value.clone()),
+        ("bool", Value::Number(number)) => Some(Value::Boolean(!number.is_zero())),
+        ("bool", Value::String(string)) => match string.as_str() {
+            "true" => Some(Value::Boolean(true)),
+            "false" => Some(Value::Boolean(false)),
+            _ => None,
+        },
+        ("datetime" | "uuid" | "geo", Value::String(_)) => Some(value.clone()),
+        _ => None,
+    };
+    cast_value.ok_or_else(|| runtime_error(format!("{} cannot be cast to '{}'", value, datatype)))
+}
+
+fn apply_operator(
+    operator: &TokenType,
+    left_value: Value,
+    right_value: Value,
+) -> io::Result<Value> {
+    let value = match (operator, &left_value, &right_value) {
+        (TokenType::EqualSign, _, _) => Value::Boolean(left_value == right_value),
+        (TokenType::NotEqualSign, _, _) => Value::Boolean(left_value != right_value),
+        (TokenType::Addition, Value::String(left), Value::String(right)) => {
+            Value::String(format!("{}{}", left, right))
+        }
+        (_, Value::Number(left), Value::Number(right)) => calculate(operator, *left, *right)?,
+        (_, Value::String(left), Value::String(right)) => match operator {
+            TokenType::Greater => Value::Boolean(left > right),
+            TokenType::Less => Value::Boolean(left < right),
+            TokenType::GreaterOrEqual => Value::Boolean(left >= right),
+            TokenType::LessOrEqual => Value::Boolean(left <= right),
+            _ => return Err(unsupported_operands(operator, &left_value, &right_value)),
+        },
+        _ => return Err(unsupported_operands(operator, &left_value, &right_value)),
+    };
+    Ok(value)
+}
+
+fn calculate(operator: &TokenType, left: Number, right: Number) -> io::Result<Value> {
+    let result = match operator {
+        TokenType::Addition => left.checked_add(right),
+        TokenType::Subtraction => left.checked_sub(right),
+        TokenType::Multiplication => left.checked_mul(right),
+        TokenType::Division if right.is_zero() => return Err(runtime_error("division by zero")),
+        TokenType::Division => left.checked_div(right),
+        TokenType::Hat => left.checked_pow(right),
+        _ => return compare(operator, left, right),
+    };
+    result
+        .map(Value::Number)
+        .ok_or_else(|| out_of_range(operator))
+}
+
+fn compare(operator: &TokenType, left: Number, right: Number) -> io::Result<Value> {
+    let value = match operator {
+        TokenType::Greater => Value::Boolean(left > right),
+        TokenType::Less => Value::Boolean(left < right),
+        TokenType::GreaterOrEqual => Value::Boolean(left >= right),
+        TokenType::LessOrEqual => Value::Boolean(left <= right),
+        _ => {
+            return Err(unsupported_operands(
+                operator,
+                &Value::Number(left),
+                &Value::Number(right),
+            ))
+        }
+    };
+    Ok(value)
+}
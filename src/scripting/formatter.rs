@@ -0,0 +1,693 @@
+use super::ast::array::ArrayNode;
+use super::ast::attach::AttachNode;
+use super::ast::binary_operator::BinaryOperatorNode;
+use super::ast::body::BodyNode;
+use super::ast::boolean::BooleanNode;
+use super::ast::call::{CallNode, CallType};
+use super::ast::conditional_tree::ConditionalTreeNode;
+use super::ast::document::DocumentNode;
+use super::ast::enum_declaration::EnumDeclarationNode;
+use super::ast::event_declaration::EventDeclarationNode;
+use super::ast::expression::ExpressionNode;
+use super::ast::for_loop::ForLoopNode;
+use super::ast::function_declaration::FunctionDeclarationNode;
+use super::ast::functional_return::FunctionalReturnNode;
+use super::ast::identifier::IdentifierNode;
+use super::ast::import::ImportNode;
+use super::ast::inspect::InspectNode;
+use super::ast::loop_control::{LoopControlNode, LoopControlType};
+use super::ast::manage::ManageNode;
+use super::ast::member::MemberNode;
+use super::ast::null::NullNode;
+use super::ast::number::NumberNode;
+use super::ast::package::PackageNode;
+use super::ast::parameter::{Parameter, Parameters};
+use super::ast::parenthesized::ParenthesizedNode;
+use super::ast::string::StringNode;
+use super::ast::table_declaration::{Column, TableDeclarationNode};
+use super::ast::unary_operator::UnaryOperatorNode;
+use super::ast::variable_declaration::VariableDeclaration;
+use super::ast::visitor::Visitor;
+use super::ast::while_loop::WhileLoopNode;
+use super::diagnostic::{Diagnostic, DiagnosticCode, Diagnostics};
+use super::lexer::Lexer;
+use super::parser::Parser;
+use super::tokens::{Token, TokenSide, TokenType};
+
+const INDENT: &str = "    ";
+// Parameter lists that would make a line longer are written one parameter per line.
+const MAX_LINE_WIDTH: usize = 100;
+
+// Reprints the code in the canonical style, keeping the comments written
+// between statements and between the items of declarations.
+pub fn format_code(code: &str, code_source: &str) -> Result<String, Diagnostics> {
+    let mut code_lexer = Lexer::new(code.to_string());
+    code_lexer
+        .get_context()
+        .set_code_source(code_source.to_string());
+    let tokens = code_lexer.analyze_with_comments()?;
+    let (comments, code_tokens): (Vec<Token>, Vec<Token>) =
+        tokens.iter().cloned().partition(is_comment);
+    // Doc comments are kept for the parser, so they still end up in the declarations.
+    let mut code_parser = Parser::new(
+        tokens
+            .into_iter()
+            .filter(|token| !token.is_type(TokenType::Comment))
+            .collect(),
+    );
+    code_parser
+        .get_context()
+        .set_code_source(code_source.to_string());
+    let body = code_parser.parse()?;
+
+    let mut formatter = Formatter::new(code, code_source, code_tokens, comments);
+    formatter.write_statements(&body, code.len());
+    if !formatter.diagnostics.is_empty() {
+        return Err(Diagnostics(formatter.diagnostics));
+    }
+    let mut formatted_code = formatter.output;
+    if !formatted_code.is_empty() {
+        formatted_code.push('\n');
+    }
+    Ok(formatted_code)
+}
+
+// Where the items of a bracketed list, like table columns, are written.
+struct ListSpans {
+    open: usize,
+    items: Vec<(usize, usize)>,
+    close: usize,
+}
+
+struct Formatter<'a> {
+    code: &'a str,
+    code_source: &'a str,
+    line_starts: Vec<usize>,
+    tokens: Vec<Token>,
+    comments: Vec<Token>,
+    next_comment: usize,
+    // Where the statement being written starts, to find the items of its lists.
+    statement_start: usize,
+    output: String,
+    indent: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(code: &'a str, code_source: &'a str, tokens: Vec<Token>, comments: Vec<Token>) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(code.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Formatter {
+            code,
+            code_source,
+            line_starts,
+            tokens,
+            comments,
+            next_comment: 0,
+            statement_start: 0,
+            output: String::new(),
+            indent: 0,
+            diagnostics: vec![],
+        }
+    }
+
+    // Writes the statements of a body one per line, up to the end position
+    // where the comments left inside the body stop.
+    fn write_statements(&mut self, body: &BodyNode, end: usize) {
+        let mut previous_stop = None;
+        for (index, node) in body.nodes.iter().enumerate() {
+            let span = body.get_node_span(index);
+            if let Some(span) = span {
+                self.write_leading_comments(span.start, &mut previous_stop);
+                self.statement_start = span.start;
+            }
+            self.begin_line(
+                span.is_some_and(|span| self.has_blank_line(previous_stop, span.start)),
+            );
+            self.visit_expression(node.as_ref());
+            // Statements closed with a bracket don't need ';' after them.
+            if !self.output.ends_with('}') {
+                self.output.push(';');
+            }
+            if let Some(span) = span {
+                self.skip_misplaced_comments(span.stop);
+                let next_start = body.get_node_span(index + 1).map_or(end, |span| span.start);
+                previous_stop = Some(self.write_trailing_comment(span.stop, next_start));
+            }
+        }
+        self.write_leading_comments(end, &mut previous_stop);
+    }
+
+    // Declarations list their items one per line, each followed by a comma.
+    fn write_list<T>(
+        &mut self,
+        brackets: (TokenType, TokenType),
+        items: &[T],
+        mut write_item: impl FnMut(&mut Self, &T),
+    ) {
+        let list = self.find_list_spans(brackets.0.clone());
+        self.skip_misplaced_comments(list.open);
+        self.output
+            .push_str(brackets.0.get_text().unwrap_or_default());
+        let length = self.output.len();
+        self.indent += 1;
+        let mut previous_stop = None;
+        for (index, item) in items.iter().enumerate() {
+            let span = list.items.get(index).copied();
+            if let Some((start, _)) = span {
+                self.write_leading_comments(start, &mut previous_stop);
+            }
+            self.begin_line(
+                span.is_some_and(|(start, _)| self.has_blank_line(previous_stop, start)),
+            );
+            write_item(self, item);
+            self.output.push(',');
+            if let Some((_, stop)) = span {
+                self.skip_misplaced_comments(stop);
+                let next_start = list.items.get(index + 1).map_or(list.close, |item| item.0);
+                previous_stop = Some(self.write_trailing_comment(stop, next_start));
+            }
+        }
+        self.write_leading_comments(list.close, &mut previous_stop);
+        self.indent -= 1;
+        if self.output.len() > length {
+            self.new_line();
+        }
+        self.output
+            .push_str(brackets.1.get_text().unwrap_or_default());
+    }
+
+    // The items are separated by commas or semicolons outside of nested brackets.
+    fn find_list_spans(&self, open: TokenType) -> ListSpans {
+        let mut list = ListSpans {
+            open: 0,
+            items: vec![],
+            close: 0,
+        };
+        let first_index = self.tokens.iter().position(|token| {
+            token.span.start >= self.statement_start && token.is_type(open.clone())
+        });
+        let Some(first_index) = first_index else {
+            return list;
+        };
+        let mut tokens = self.tokens.iter().skip(first_index);
+        list.open = tokens.next().unwrap().span.start;
+        let mut depth = 0;
+        let mut item: Option<(usize, usize)> = None;
+        for token in tokens {
+            match token.token_type {
+                TokenType::LPar | TokenType::LBracket | TokenType::LSquareBracket => depth += 1,
+                TokenType::RPar | TokenType::RBracket | TokenType::RSquareBracket if depth == 0 => {
+                    list.items.extend(item);
+                    list.close = token.span.start;
+                    break;
+                }
+                TokenType::RPar | TokenType::RBracket | TokenType::RSquareBracket => depth -= 1,
+                TokenType::Comma | TokenType::ExpressionEnd if depth == 0 => {
+                    list.items.extend(item.take());
+                    continue;
+                }
+                _ => {}
+            }
+            let (start, _) = item.unwrap_or((token.span.start, token.span.stop));
+            item = Some((start, token.span.stop));
+        }
+        list
+    }
+
+    fn write_leading_comments(&mut self, position: usize, previous_stop: &mut Option<usize>) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.span.start >= position {
+                break;
+            }
+            let comment = comment.clone();
+            self.begin_line(self.has_blank_line(*previous_stop, comment.span.start));
+            self.output.push_str(comment.value.trim_end());
+            *previous_stop = Some(comment.span.stop);
+            self.next_comment += 1;
+        }
+    }
+
+    // A comment on the line where an item ends stays at the end of the line.
+    // Returns where the item ends, including the comment.
+    fn write_trailing_comment(&mut self, stop: usize, next_start: usize) -> usize {
+        let Some(comment) = self.comments.get(self.next_comment) else {
+            return stop;
+        };
+        if comment.span.start >= next_start
+            || self.get_line(comment.span.start) != self.get_line(stop)
+        {
+            return stop;
+        }
+        let comment = comment.clone();
+        self.output.push(' ');
+        self.output.push_str(comment.value.trim_end());
+        self.next_comment += 1;
+        comment.span.stop
+    }
+
+    // Comments inside expressions have no place of their own in the canonical style.
+    fn skip_misplaced_comments(&mut self, position: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.span.start >= position {
+                break;
+            }
+            self.diagnostics.push(
+                Diagnostic::error(
+                    DiagnosticCode::MisplacedComment,
+                    "comments can only be kept between statements and between declaration items",
+                    self.code_source,
+                    comment.span,
+                )
+                .with_span_label("this comment can't be kept"),
+            );
+            self.next_comment += 1;
+        }
+    }
+
+    fn has_blank_line(&self, previous_stop: Option<usize>, start: usize) -> bool {
+        previous_stop
+            .and_then(|stop| self.code.get(stop..start))
+            .is_some_and(|between| between.matches('\n').count() > 1)
+    }
+
+    fn get_line(&self, position: usize) -> usize {
+        self.line_starts
+            .partition_point(|line_start| *line_start <= position)
+    }
+
+    fn begin_line(&mut self, blank_line: bool) {
+        if !self.output.is_empty() {
+            if blank_line {
+                self.output.push('\n');
+            }
+            self.output.push('\n');
+        }
+        self.output.push_str(&INDENT.repeat(self.indent));
+    }
+
+    fn new_line(&mut self) {
+        self.output.push('\n');
+        self.output.push_str(&INDENT.repeat(self.indent));
+    }
+
+    // Writes into a separate string, to measure the written code before placing it.
+    fn capture(&mut self, write: impl FnOnce(&mut Self)) -> String {
+        let output = std::mem::take(&mut self.output);
+        write(self);
+        std::mem::replace(&mut self.output, output)
+    }
+
+    // The trailing width is for what follows the parameters on the same line.
+    fn write_parameters(&mut self, parameters: &Parameters, trailing_width: usize) {
+        self.indent += 1;
+        let texts: Vec<String> = parameters
+            .iter()
+            .map(|parameter| self.capture(|formatter| formatter.visit_parameter(parameter)))
+            .collect();
+        self.indent -= 1;
+        let flat_texts = texts.join(", ");
+        let line_width = self.output[self.output.rfind('\n').map_or(0, |index| index + 1)..]
+            .chars()
+            .count();
+        if !flat_texts.contains('\n')
+            && line_width + flat_texts.chars().count() + 2 + trailing_width <= MAX_LINE_WIDTH
+        {
+            self.output.push('(');
+            self.output.push_str(&flat_texts);
+            self.output.push(')');
+            return;
+        }
+        self.output.push('(');
+        self.indent += 1;
+        for text in texts {
+            self.new_line();
+            self.output.push_str(&text);
+            self.output.push(',');
+        }
+        self.indent -= 1;
+        self.new_line();
+        self.output.push(')');
+    }
+
+    fn write_datatype(&mut self, datatype: Option<&str>) {
+        if let Some(datatype) = datatype {
+            self.output.push_str(": ");
+            self.output.push_str(datatype);
+        }
+    }
+
+    fn write_assignment(&mut self, value: Option<&dyn ExpressionNode>) {
+        if let Some(value) = value {
+            self.output.push_str(" = ");
+            self.visit_expression(value);
+        }
+    }
+}
+
+impl Visitor for Formatter<'_> {
+    fn visit_attach(&mut self, node: &AttachNode) {
+        self.output.push_str("attach ");
+        self.output.push_str(&quote(node.get_path()));
+    }
+
+    fn visit_binary_operator(&mut self, node: &BinaryOperatorNode) {
+        self.visit_expression(node.get_left_operand());
+        self.output.push(' ');
+        self.output
+            .push_str(node.get_operator().get_text().unwrap_or_default());
+        self.output.push(' ');
+        self.visit_expression(node.get_right_operand());
+    }
+
+    fn visit_array(&mut self, node: &ArrayNode) {
+        self.output.push('[');
+        for (index, item) in node.get_items().iter().enumerate() {
+            if index > 0 {
+                self.output.push_str(", ");
+            }
+            self.visit_expression(item.as_ref());
+        }
+        self.output.push(']');
+    }
+
+    fn visit_body(&mut self, node: &BodyNode) {
+        let span = node.get_span();
+        if let Some(span) = span {
+            self.skip_misplaced_comments(span.start);
+        }
+        self.output.push('{');
+        let length = self.output.len();
+        self.indent += 1;
+        // The closing bracket is the last character of the body.
+        self.write_statements(node, span.map_or(0, |span| span.stop - 1));
+        self.indent -= 1;
+        if self.output.len() > length {
+            self.new_line();
+        }
+        self.output.push('}');
+    }
+
+    fn visit_boolean(&mut self, node: &BooleanNode) {
+        self.output.push_str(match node.get_state() {
+            true => "true",
+            false => "false",
+        });
+    }
+
+    fn visit_call(&mut self, node: &CallNode) {
+        self.visit_expression(node.get_callee());
+        if *node.get_call_type() != CallType::Position {
+            self.write_parameters(node.get_arguments(), 0);
+            return;
+        }
+        self.output.push('[');
+        match node.get_slice_bounds() {
+            Some((start, stop)) => {
+                if let Some(start) = start {
+                    self.visit_expression(start);
+                }
+                self.output.push(':');
+                if let Some(stop) = stop {
+                    self.visit_expression(stop);
+                }
+            }
+            None => {
+                for argument in node.get_arguments() {
+                    self.visit_parameter(argument);
+                }
+            }
+        }
+        self.output.push(']');
+    }
+
+    fn visit_conditional_tree(&mut self, node: &ConditionalTreeNode) {
+        for (index, (condition, value)) in node.get_conditions().iter().enumerate() {
+            self.output
+                .push_str(if index == 0 { "if " } else { " elif " });
+            self.visit_expression(condition.as_ref());
+            self.output.push(' ');
+            self.visit_expression(value.as_ref());
+        }
+        if let Some(default) = node.get_default() {
+            self.output.push_str(" else ");
+            self.visit_expression(default);
+        }
+    }
+
+    fn visit_document(&mut self, node: &DocumentNode) {
+        if node.get_fields().is_empty() {
+            self.output.push_str("{:}");
+            return;
+        }
+        self.output.push_str("{ ");
+        for (index, (key, value)) in node.get_fields().iter().enumerate() {
+            if index > 0 {
+                self.output.push_str(", ");
+            }
+            match is_plain_key(key) {
+                true => self.output.push_str(key),
+                false => self.output.push_str(&quote(key)),
+            }
+            self.output.push_str(": ");
+            self.visit_expression(value.as_ref());
+        }
+        self.output.push_str(" }");
+    }
+
+    fn visit_enum_declaration(&mut self, node: &EnumDeclarationNode) {
+        self.output.push_str("enum ");
+        self.output.push_str(node.get_name());
+        self.write_datatype(node.get_datatype());
+        self.output.push(' ');
+        self.write_list(
+            (TokenType::LBracket, TokenType::RBracket),
+            node.get_variants(),
+            |formatter, variant| formatter.output.push_str(variant),
+        );
+    }
+
+    fn visit_event_declaration(&mut self, node: &EventDeclarationNode) {
+        self.output.push_str("event ");
+        self.output.push_str(node.get_name());
+        self.output.push_str(" (&");
+        self.output.push_str(node.get_table());
+        if let Some(column) = node.get_column() {
+            self.output.push('.');
+            self.output.push_str(column);
+        }
+        self.output.push_str(", ");
+        self.output
+            .push_str(&quote(node.get_change_kind().get_name()));
+        self.output.push_str(") ");
+        self.visit_body(node.get_body());
+    }
+
+    fn visit_for_loop(&mut self, node: &ForLoopNode) {
+        self.output.push_str("for ");
+        self.output.push_str(node.get_item());
+        self.output.push_str(" of ");
+        self.visit_expression(node.get_collection());
+        self.output.push(' ');
+        self.visit_body(node.get_body());
+    }
+
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationNode) {
+        self.output.push_str("function ");
+        self.output.push_str(node.get_name());
+        if !node.get_generics().is_empty() {
+            self.output
+                .push_str(&format!("<{}>", node.get_generics().join(", ")));
+        }
+        // Room for ": type {" after the parameters.
+        let trailing_width = node.get_datatype().map_or(0, |datatype| datatype.len() + 2) + 2;
+        self.write_parameters(node.get_arguments(), trailing_width);
+        self.write_datatype(node.get_datatype());
+        if let Some(body) = node.get_body() {
+            self.output.push(' ');
+            self.visit_body(body);
+        }
+    }
+
+    fn visit_functional_return(&mut self, node: &FunctionalReturnNode) {
+        self.output.push_str("return");
+        if let Some(value) = node.get_value() {
+            self.output.push(' ');
+            self.visit_expression(value);
+        }
+    }
+
+    fn visit_identifier(&mut self, node: &IdentifierNode) {
+        self.output.push_str(node.get_name());
+        if !node.get_types().is_empty() {
+            let types: Vec<&str> = node
+                .get_types()
+                .iter()
+                .map(|datatype| datatype.get_name())
+                .collect();
+            self.output.push_str(&format!("<{}>", types.join(", ")));
+        }
+    }
+
+    fn visit_import(&mut self, node: &ImportNode) {
+        let imports: Vec<String> = node
+            .get_imports()
+            .iter()
+            .map(|import| format!("{}:{}", import.get_package(), import.get_symbol()))
+            .collect();
+        self.output.push_str("import ");
+        self.output.push_str(&imports.join(", "));
+    }
+
+    fn visit_inspect(&mut self, node: &InspectNode) {
+        self.output.push_str("inspect ");
+        self.output.push_str(&node.get_targets().join(", "));
+    }
+
+    fn visit_loop_control(&mut self, node: &LoopControlNode) {
+        self.output.push_str(match node.get_control_type() {
+            LoopControlType::Continue => "continue",
+            LoopControlType::Break => "break",
+        });
+    }
+
+    fn visit_manage(&mut self, node: &ManageNode) {
+        self.output.push_str("manage ");
+        self.write_list(
+            (TokenType::LPar, TokenType::RPar),
+            node.get_settings(),
+            |formatter, setting| formatter.visit_parameter(setting),
+        );
+    }
+
+    fn visit_member(&mut self, node: &MemberNode) {
+        self.visit_expression(node.get_parent());
+        self.output.push('.');
+        self.visit_expression(node.get_child());
+    }
+
+    fn visit_null(&mut self, _node: &NullNode) {
+        self.output.push_str("null");
+    }
+
+    fn visit_number(&mut self, node: &NumberNode) {
+        self.output.push_str(&node.get_value().to_literal());
+    }
+
+    fn visit_package(&mut self, node: &PackageNode) {
+        self.output.push_str("package ");
+        self.output.push_str(node.get_name());
+    }
+
+    fn visit_parenthesized(&mut self, node: &ParenthesizedNode) {
+        self.output.push('(');
+        self.visit_expression(node.get_expression());
+        self.output.push(')');
+    }
+
+    fn visit_string(&mut self, node: &StringNode) {
+        self.output.push_str(&quote(node.get_value()));
+    }
+
+    fn visit_table_declaration(&mut self, node: &TableDeclarationNode) {
+        self.output.push_str("table ");
+        self.output.push_str(node.get_name());
+        self.write_datatype(node.get_primary_key());
+        self.output.push(' ');
+        self.write_list(
+            (TokenType::LBracket, TokenType::RBracket),
+            node.get_columns(),
+            |formatter, column| formatter.visit_column(column),
+        );
+    }
+
+    fn visit_unary_operator(&mut self, node: &UnaryOperatorNode) {
+        let operator = node.get_operator().get_text().unwrap_or_default();
+        if *node.get_side() == TokenSide::Left {
+            self.output.push_str(operator);
+        }
+        let operand_start = self.output.len();
+        self.visit_expression(node.get_operand());
+        // `- -x` would be read back as `--x` without the space.
+        if operator == "-" && self.output[operand_start..].starts_with('-') {
+            self.output.insert(operand_start, ' ');
+        }
+        if *node.get_side() == TokenSide::Right {
+            self.output.push_str(operator);
+        }
+    }
+
+    fn visit_variable_declaration(&mut self, node: &VariableDeclaration) {
+        self.output.push_str(match node.is_mutable() {
+            true => "mut ",
+            false => "fin ",
+        });
+        self.output.push_str(node.get_name());
+        self.write_datatype(node.get_datatype());
+        self.write_assignment(node.get_value());
+    }
+
+    fn visit_while_loop(&mut self, node: &WhileLoopNode) {
+        self.output.push_str("while ");
+        self.visit_expression(node.get_condition());
+        self.output.push(' ');
+        self.visit_body(node.get_body());
+    }
+
+    // Function parameters have types, while arguments of calls may have names.
+    fn visit_parameter(&mut self, node: &Parameter) {
+        if node.get_datatype().is_some() {
+            self.output.push_str(node.get_name());
+            self.write_datatype(node.get_datatype());
+            self.write_assignment(node.get_value());
+            return;
+        }
+        if !node.get_name().is_empty() {
+            self.output.push_str(node.get_name());
+            self.output.push_str(" = ");
+        }
+        if let Some(value) = node.get_value() {
+            self.visit_expression(value);
+        }
+    }
+
+    fn visit_column(&mut self, node: &Column) {
+        self.output.push_str(node.get_name());
+        self.output.push_str(": ");
+        self.output.push_str(&node.get_column_type().to_string());
+        for constraint in node.get_constraints() {
+            self.output.push(' ');
+            self.output.push_str(&constraint.to_string());
+        }
+        self.write_assignment(node.get_default());
+    }
+}
+
+fn is_comment(token: &Token) -> bool {
+    token.is_type(TokenType::Comment) || token.is_type(TokenType::DocComment)
+}
+
+// Keys that are lexed as a single name, and not as keywords, are written without quotes.
+fn is_plain_key(key: &str) -> bool {
+    let tokens = Lexer::new(key.to_string()).analyze().unwrap_or_default();
+    matches!(tokens.as_slice(), [token] if token.is_type(TokenType::Alphanumeric) && token.value == key)
+}
+
+// The reverse of unquoting in the parser.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for char in value.chars() {
+        match char {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            _ => quoted.push(char),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -1,11 +1,24 @@
-use colored::Colorize;
-use regex::Regex;
-use std::io;
-use strum::IntoEnumIterator;
-
 use super::context::Context;
+use super::diagnostic::{Diagnostic, DiagnosticCode};
+use super::number::Number;
+use super::span::Span;
 use super::tokens::{Token, TokenType, WHITESPACE_TOKENS};
 
+const BLOCK_COMMENT_START: &str = "/*";
+const BLOCK_COMMENT_END: &str = "*/";
+const DOC_COMMENT_START: &str = "///";
+const LINE_COMMENT_START: &str = "//";
+
+type DigitTest = fn(&u8) -> bool;
+
+const RADIXES: [(&str, &str, DigitTest); 3] = [
+    ("0x", "hexadecimal", u8::is_ascii_hexdigit),
+    ("0o", "octal", is_octal_digit),
+    ("0b", "binary", is_binary_digit),
+];
+
+// Scans the code once from left to right. Every token is the longest one that
+// can be read at the current position, so `>=` is never split into `>` and `=`.
 pub struct Lexer {
     pub context: Context,
     code: String,
@@ -25,79 +38,111 @@ impl Lexer {
         &mut self.context
     }
 
-    pub fn analyze(mut self) -> io::Result<Vec<Token>> {
-        loop {
-            match self.recognize_next_token() {
-                Ok(proceed_parsing) => {
-                    if !proceed_parsing {
-                        self.tokens.retain(|token| !token.is_type(TokenType::Space));
-                        return Ok(self.tokens);
-                    }
-                    let last_token = self.tokens.last().unwrap();
-                    if !WHITESPACE_TOKENS.contains(&last_token.token_type) {
-                        let start_position =
-                            1 + self.context.position - last_token.value.len() as u64;
-                        println!(
-                            "{}:{} = {}",
-                            start_position, last_token.value, last_token.token_type
-                        );
-                    }
-                }
-                Err(err) => {
-                    eprintln!("{}", err);
-                    break;
-                }
-            };
-        }
-        Ok(vec![])
+    pub fn analyze(self) -> Result<Vec<Token>, Box<Diagnostic>> {
+        let mut tokens = self.analyze_with_comments()?;
+        tokens.retain(|token| !token.is_type(TokenType::Comment));
+        Ok(tokens)
+    }
+
+    // Keeps the comments for tools that reprint the code, like the formatter.
+    pub fn analyze_with_comments(mut self) -> Result<Vec<Token>, Box<Diagnostic>> {
+        while self.recognize_next_token()? {}
+        self.tokens
+            .retain(|token| !WHITESPACE_TOKENS.contains(&token.token_type));
+        Ok(self.tokens)
     }
 
-    fn recognize_next_token(&mut self) -> io::Result<bool> {
-        if self.context.position >= self.code.len().try_into().unwrap() {
+    fn recognize_next_token(&mut self) -> Result<bool, Box<Diagnostic>> {
+        let position = self.context.position;
+        if position >= self.code.len() {
             return Ok(false);
         };
-        let positioned_code = &self.code[self.context.position as usize..];
-        for token_type in TokenType::iter() {
-            let token_regex_string = TokenType::regex_str(&token_type);
-            let token_regex = Regex::new(&format!(r#"^{}"#, token_regex_string)).unwrap();
-            if let Some(matches) = token_regex.find(positioned_code) {
-                let matched_str = matches.as_str();
-                self.tokens.push(Token {
-                    token_type,
-                    start: self.context.position,
-                    stop: self.context.position + matched_str.len() as u64,
-                    line: self.context.line,
-                    value: matched_str.to_string(),
-                });
-                if matched_str == TokenType::ExpressionEnd.to_string() {
-                    self.context.line += 1;
-                };
-                self.context.position += matched_str.len() as u64;
-                self.find_lexical_errors()?;
-                return Ok(true);
+        let positioned_code = &self.code[position..];
+        let (token_type, length) = match scan_token(positioned_code) {
+            Some(Ok(scanned_token)) => scanned_token,
+            Some(Err((code, message))) => return Err(self.lexical_error(code, message).into()),
+            None => {
+                let unrecognized_char = positioned_code.chars().next().unwrap();
+                return Err(self
+                    .lexical_error(
+                        DiagnosticCode::UnrecognizedCharacter,
+                        format!("'{}' is not recognized", unrecognized_char),
+                    )
+                    .into());
+            }
+        };
+        let value = &positioned_code[..length];
+        let span = Span::new(
+            position,
+            position + length,
+            self.context.line,
+            self.context.column,
+        );
+        for char in value.chars() {
+            if char == '\n' {
+                self.context.line += 1;
+                self.context.column = 1;
+            } else {
+                self.context.column += 1;
             }
         }
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!(
-                "{}: '{}' {} <-= at {}:{}:{}",
-                "Lexical Error".bright_red(),
-                positioned_code.trim(),
-                "is not recognized",
-                self.context.code_source,
-                self.context.line + 1,
-                self.context.position + 1
-            ),
-        ))
-    }
-
-    fn find_lexical_errors(&mut self) -> io::Result<()> {
+        self.tokens.push(Token {
+            token_type,
+            span,
+            value: value.to_string(),
+        });
+        self.context.position += length;
+        self.find_lexical_errors()?;
+        Ok(true)
+    }
+
+    // Points at the character the lexer stopped on.
+    fn lexical_error(&self, code: DiagnosticCode, message: impl Into<String>) -> Diagnostic {
+        let position = self.context.position;
+        let char_length = self.code[position..]
+            .chars()
+            .next()
+            .map_or(0, char::len_utf8);
+        let span = Span::new(
+            position,
+            position + char_length,
+            self.context.line,
+            self.context.column,
+        );
+        Diagnostic::error(code, message, &self.context.code_source, span)
+    }
+
+    fn find_lexical_errors(&mut self) -> Result<(), Box<Diagnostic>> {
+        self.throw_error_if_invalid_digit_in_number()?;
         self.throw_error_if_alphanumeric_in_number()?;
+        self.throw_error_if_number_out_of_range()?;
         self.throw_error_if_unresolved_chars_near_string()?;
         Ok(())
     }
 
-    fn throw_error_if_alphanumeric_in_number(&self) -> io::Result<()> {
+    // A prefixed number ends at the first digit its radix doesn't have, so `0b102` stops before `2`.
+    fn throw_error_if_invalid_digit_in_number(&self) -> Result<(), Box<Diagnostic>> {
+        let current_token = self.tokens.last().unwrap();
+        if !current_token.is_type(TokenType::Number) {
+            return Ok(());
+        }
+        let radix_name = RADIXES
+            .iter()
+            .find(|(prefix, _, _)| current_token.value.starts_with(prefix))
+            .map(|(_, radix_name, _)| radix_name);
+        let next_char = self.code[self.context.position..].chars().next();
+        match (radix_name, next_char) {
+            (Some(radix_name), Some(char)) if char.is_alphanumeric() => Err(self
+                .lexical_error(
+                    DiagnosticCode::InvalidDigit,
+                    format!("'{}' is not a digit of {} numbers", char, radix_name),
+                )
+                .into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn throw_error_if_alphanumeric_in_number(&self) -> Result<(), Box<Diagnostic>> {
         if self.tokens.len() >= 2 {
             let current_token = self.tokens.last().unwrap();
             let last_token = self.tokens.get(self.tokens.len() - 2).unwrap();
@@ -105,78 +150,206 @@ impl Lexer {
             let current_token_is_alphanumeric = current_token.is_type(TokenType::Alphanumeric);
             let last_token_is_number = last_token.is_type(TokenType::Number);
             if last_token_is_number && current_token_is_alphanumeric {
-                return Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!(
-                        "{}: numbers cannot end with alphanumeric <-= at {}:{}:{}",
-                        "Lexical Error".bright_red(),
-                        self.context.code_source,
-                        last_token.line + 1,
-                        last_token.start + 1
-                    )
-                    .to_string(),
-                ));
+                let span = Span {
+                    stop: current_token.span.stop,
+                    ..last_token.span
+                };
+                return Err(Diagnostic::error(
+                    DiagnosticCode::AlphanumericInNumber,
+                    "numbers cannot end with alphanumeric",
+                    &self.context.code_source,
+                    span,
+                )
+                .into());
             }
         }
         Ok(())
     }
 
-    fn throw_error_if_unresolved_chars_near_string(&mut self) -> io::Result<()> {
+    fn throw_error_if_number_out_of_range(&self) -> Result<(), Box<Diagnostic>> {
+        let current_token = self.tokens.last().unwrap();
+        if !current_token.is_type(TokenType::Number) {
+            return Ok(());
+        }
+        match Number::parse_literal(&current_token.value) {
+            Ok(_) => Ok(()),
+            Err(message) => Err(Diagnostic::error(
+                DiagnosticCode::NumberOutOfRange,
+                message,
+                &self.context.code_source,
+                current_token.span,
+            )
+            .into()),
+        }
+    }
+
+    fn throw_error_if_unresolved_chars_near_string(&mut self) -> Result<(), Box<Diagnostic>> {
         let current_token = self.tokens.last().unwrap();
 
-        if !self.tokens.is_empty() && current_token.is_type(TokenType::CharArray) {
-            let both_sides_unresolved_chars_regex = Regex::new(r"[\w\d]").unwrap();
-            let left_side_unresolved_chars_regex = Regex::new(r"[\.]").unwrap();
-
-            let char_before_index: u64 = current_token.start - 1;
-            let char_after_index: u64 = current_token.start + current_token.value.len() as u64;
-
-            if char_before_index as i32 > 0 {
-                let char_before = &self
-                    .code
-                    .chars()
-                    .nth((current_token.start - 1) as usize)
-                    .unwrap()
-                    .to_string();
-                if both_sides_unresolved_chars_regex.is_match(char_before)
-                    || left_side_unresolved_chars_regex.is_match(char_before)
-                {
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!(
-                            "{}: \"{}\" near a string with no space between <-= at {}:{}:{}",
-                            "Lexical Error".bright_red(),
-                            char_before,
-                            self.context.code_source,
-                            current_token.line + 1,
-                            char_before_index + 1
-                        ),
-                    ));
-                }
+        if current_token.is_type(TokenType::CharArray) {
+            let is_unresolved_on_both_sides = |char: char| char.is_alphanumeric() || char == '_';
+
+            let char_before = self.code[..current_token.span.start].chars().next_back();
+            if let Some(char_before) =
+                char_before.filter(|char| is_unresolved_on_both_sides(*char) || *char == '.')
+            {
+                let start = current_token.span.start - char_before.len_utf8();
+                let span = Span::new(
+                    start,
+                    current_token.span.start,
+                    current_token.span.line,
+                    current_token.span.column - 1,
+                );
+                return Err(Diagnostic::error(
+                    DiagnosticCode::CharacterNearString,
+                    format!("\"{}\" near a string with no space between", char_before),
+                    &self.context.code_source,
+                    span,
+                )
+                .into());
             };
-            if char_after_index < self.code.len() as u64 {
-                let char_after = &self
-                    .code
-                    .chars()
-                    .nth(char_after_index as usize)
-                    .unwrap()
-                    .to_string();
-                if both_sides_unresolved_chars_regex.is_match(char_after) {
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!(
-                            "{}: \"{}\" after a string with no space between <-= at {}:{}:{}",
-                            "Lexical Error".bright_red(),
-                            char_after,
-                            self.context.code_source,
-                            current_token.line + 1,
-                            char_after_index + 1
-                        ),
-                    ));
-                }
+            let char_after = self.code[current_token.span.stop..].chars().next();
+            if let Some(char_after) = char_after.filter(|char| is_unresolved_on_both_sides(*char)) {
+                return Err(self
+                    .lexical_error(
+                        DiagnosticCode::CharacterNearString,
+                        format!("\"{}\" after a string with no space between", char_after),
+                    )
+                    .into());
             };
         }
 
         Ok(())
     }
 }
+
+type ScanError = (DiagnosticCode, &'static str);
+
+// Returns the type and the length in bytes of the token the code starts with.
+fn scan_token(code: &str) -> Option<Result<(TokenType, usize), ScanError>> {
+    let first_char = code.chars().next()?;
+    let scanned_token = match first_char {
+        '\n' => (TokenType::NewLine, 1),
+        '\t' => (TokenType::Indent, 1),
+        '\r' => (TokenType::Carriage, 1),
+        char if char.is_whitespace() => (TokenType::Space, char.len_utf8()),
+        '"' => return Some(scan_string(code).map(|length| (TokenType::CharArray, length))),
+        '/' if code.starts_with(BLOCK_COMMENT_START) => {
+            return Some(scan_block_comment(code).map(|length| (TokenType::Comment, length)))
+        }
+        '/' if code.starts_with(LINE_COMMENT_START) => {
+            let length = code.find('\n').unwrap_or(code.len());
+            // Four and more slashes make a regular comment, like in Rust.
+            let is_doc_comment = code.starts_with(DOC_COMMENT_START)
+                && !code[DOC_COMMENT_START.len()..].starts_with('/');
+            match is_doc_comment {
+                true => (TokenType::DocComment, length),
+                false => (TokenType::Comment, length),
+            }
+        }
+        char if char.is_ascii_digit() => (TokenType::Number, scan_number(code)),
+        char if char.is_alphabetic() || char == '_' => {
+            let length = code
+                .find(|char: char| !char.is_alphanumeric() && char != '_')
+                .unwrap_or(code.len());
+            let token_type =
+                TokenType::from_keyword(&code[..length]).unwrap_or(TokenType::Alphanumeric);
+            (token_type, length)
+        }
+        _ => {
+            let symbol_token = [2, 1].into_iter().find_map(|length| {
+                let symbol = code.get(..length)?;
+                TokenType::from_symbol(symbol).map(|token_type| (token_type, length))
+            });
+            return symbol_token.map(Ok);
+        }
+    };
+    Some(Ok(scanned_token))
+}
+
+fn is_octal_digit(byte: &u8) -> bool {
+    matches!(byte, b'0'..=b'7')
+}
+
+fn is_binary_digit(byte: &u8) -> bool {
+    matches!(byte, b'0' | b'1')
+}
+
+// Numbers are `0x` hexadecimal, `0o` octal, `0b` binary or decimal with an optional fraction and exponent,
+// and `_` can separate their digits. The `m` suffix makes a decimal, like `19.99m`.
+fn scan_number(code: &str) -> usize {
+    let count_digits = |code: &str, is_digit: DigitTest| {
+        code.bytes()
+            .take_while(|byte| is_digit(byte) || *byte == b'_')
+            .count()
+    };
+    let starts_with_digit =
+        |code: &str, is_digit: DigitTest| code.as_bytes().first().is_some_and(is_digit);
+    // The prefix is kept before a wrong digit too, so that the digit is reported.
+    for (prefix, _, is_digit) in RADIXES {
+        if let Some(digits) = code.strip_prefix(prefix) {
+            if digits.starts_with(|char: char| char.is_alphanumeric()) {
+                return prefix.len() + count_digits(digits, is_digit);
+            }
+        }
+    }
+
+    let mut length = count_digits(code, u8::is_ascii_digit);
+    // A dot belongs to a number only when digits follow it, and so does an exponent.
+    if let Some(fraction) = code[length..].strip_prefix('.') {
+        if starts_with_digit(fraction, u8::is_ascii_digit) {
+            length += 1 + count_digits(fraction, u8::is_ascii_digit);
+        }
+    }
+    if let Some(exponent) = code[length..].strip_prefix(['e', 'E']) {
+        let sign_length = usize::from(exponent.starts_with(['+', '-']));
+        if starts_with_digit(&exponent[sign_length..], u8::is_ascii_digit) {
+            length += 1 + sign_length + count_digits(&exponent[sign_length..], u8::is_ascii_digit);
+        }
+    }
+    let suffix = &code[length..];
+    let is_name_char = |char: char| char.is_alphanumeric() || char == '_';
+    if suffix.starts_with('m') && !suffix[1..].starts_with(is_name_char) {
+        length += 1;
+    }
+    length
+}
+
+fn scan_string(code: &str) -> Result<usize, ScanError> {
+    let mut chars = code.char_indices().skip(1);
+    while let Some((index, char)) = chars.next() {
+        match char {
+            '"' => return Ok(index + 1),
+            '\\' => {
+                chars.next();
+            }
+            '\n' => break,
+            _ => {}
+        }
+    }
+    Err((DiagnosticCode::UnterminatedString, "unterminated string"))
+}
+
+fn scan_block_comment(code: &str) -> Result<usize, ScanError> {
+    let mut depth = 0;
+    let mut length = 0;
+    while length < code.len() {
+        let rest = &code[length..];
+        if rest.starts_with(BLOCK_COMMENT_START) {
+            depth += 1;
+            length += BLOCK_COMMENT_START.len();
+        } else if rest.starts_with(BLOCK_COMMENT_END) {
+            depth -= 1;
+            length += BLOCK_COMMENT_END.len();
+            if depth == 0 {
+                return Ok(length);
+            }
+        } else {
+            length += rest.chars().next().unwrap().len_utf8();
+        }
+    }
+    Err((
+        DiagnosticCode::UnterminatedComment,
+        "unterminated block comment",
+    ))
+}
@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::ast::attach::AttachNode;
+use super::ast::body::BodyNode;
+use super::ast::enum_declaration::EnumDeclarationNode;
+use super::ast::event_declaration::EventDeclarationNode;
+use super::ast::function_declaration::FunctionDeclarationNode;
+use super::ast::import::{Import, ImportNode};
+use super::ast::package::PackageNode;
+use super::ast::table_declaration::TableDeclarationNode;
+use super::ast::variable_declaration::VariableDeclaration;
+use super::diagnostic::{Diagnostic, Diagnostics};
+use super::executor::Executor;
+use super::lexer::Lexer;
+use super::parser::Parser;
+use super::resolver::Resolver;
+use super::source_map::SourceMap;
+use super::value::Value;
+
+pub const BLAZE_EXTENSION: &str = "blz";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolKind {
+    Function,
+    Table,
+    Enum,
+    Event,
+    Variable,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    name: String,
+    kind: SymbolKind,
+    package: String,
+    path: PathBuf,
+}
+
+impl Symbol {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_kind(&self) -> &SymbolKind {
+        &self.kind
+    }
+
+    pub fn get_package(&self) -> &str {
+        &self.package
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+}
+
+pub struct Module {
+    path: PathBuf,
+    package: Option<String>,
+    body: BodyNode,
+    is_resolved: bool,
+}
+
+impl Module {
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    // The package the module declares with `package name;`.
+    pub fn get_package(&self) -> Option<&str> {
+        self.package.as_deref()
+    }
+
+    pub fn get_body(&self) -> &BodyNode {
+        &self.body
+    }
+
+    pub fn get_imports(&self) -> Vec<&Import> {
+        self.body
+            .nodes
+            .iter()
+            .filter_map(|node| node.downcast_ref::<ImportNode>())
+            .flat_map(|import_node| import_node.get_imports())
+            .collect()
+    }
+
+    // Attached paths are relative to the directory of the module.
+    pub fn get_attachments(&self) -> Vec<PathBuf> {
+        let directory = self.path.parent().unwrap_or(Path::new(""));
+        self.body
+            .nodes
+            .iter()
+            .filter_map(|node| node.downcast_ref::<AttachNode>())
+            .map(|attach_node| directory.join(attach_node.get_path()))
+            .collect()
+    }
+
+    fn get_symbols(&self, package: &str) -> Vec<Symbol> {
+        self.body
+            .nodes
+            .iter()
+            .filter_map(|node| {
+                let (name, kind) =
+                    if let Some(function) = node.downcast_ref::<FunctionDeclarationNode>() {
+                        (function.get_name(), SymbolKind::Function)
+                    } else if let Some(table) = node.downcast_ref::<TableDeclarationNode>() {
+                        (table.get_name(), SymbolKind::Table)
+                    } else if let Some(enum_node) = node.downcast_ref::<EnumDeclarationNode>() {
+                        (enum_node.get_name(), SymbolKind::Enum)
+                    } else if let Some(event) = node.downcast_ref::<EventDeclarationNode>() {
+                        (event.get_name(), SymbolKind::Event)
+                    } else if let Some(variable) = node.downcast_ref::<VariableDeclaration>() {
+                        (variable.get_name(), SymbolKind::Variable)
+                    } else {
+                        return None;
+                    };
+                Some(Symbol {
+                    name: name.to_string(),
+                    kind,
+                    package: package.to_string(),
+                    path: self.path.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+// Finds and parses the packages of a datablaze. Every file is parsed only once,
+// no matter how many times it's loaded or imported.
+pub struct PackageLoader {
+    packages_dir: PathBuf,
+    modules: Vec<Module>,
+    module_indexes: HashMap<PathBuf, usize>,
+    packages: HashMap<String, Vec<usize>>,
+    // The code of every read file, for rendering the diagnostics of any of them.
+    sources: SourceMap,
+    // The warnings of the resolved modules, as only errors stop the loading.
+    warnings: Vec<Diagnostic>,
+}
+
+impl PackageLoader {
+    pub fn new(packages_dir: impl Into<PathBuf>) -> Self {
+        PackageLoader {
+            packages_dir: packages_dir.into(),
+            modules: vec![],
+            module_indexes: HashMap::new(),
+            packages: HashMap::new(),
+            sources: SourceMap::new(),
+            warnings: vec![],
+        }
+    }
+
+    pub fn get_packages_dir(&self) -> &Path {
+        &self.packages_dir
+    }
+
+    pub fn get_sources(&self) -> &SourceMap {
+        &self.sources
+    }
+
+    pub fn take_warnings(&mut self) -> Diagnostics {
+        Diagnostics(std::mem::take(&mut self.warnings))
+    }
+
+    // Loads every .blz file under the packages directory. A package can be split
+    // across several files; files without a package declaration are named after themselves.
+    pub fn load_packages(&mut self) -> io::Result<()> {
+        for path in find_blaze_files(&self.packages_dir)? {
+            let index = self.load_module(&path)?;
+            let module = &self.modules[index];
+            let package = match module.get_package() {
+                Some(package) => package.to_string(),
+                None => path.file_stem().unwrap().to_string_lossy().to_string(),
+            };
+            let package_indexes = self.packages.entry(package).or_default();
+            if !package_indexes.contains(&index) {
+                package_indexes.push(index);
+            }
+        }
+
+        let mut package_names: Vec<&String> = self.packages.keys().collect();
+        package_names.sort();
+        for package in package_names {
+            self.check_duplicate_symbols(package)?;
+            self.resolve_package_order(package, &mut vec![], &mut vec![], &mut vec![])?;
+            for index in &self.packages[package] {
+                self.get_imported_symbols(*index)?;
+            }
+        }
+        for index in 0..self.modules.len() {
+            self.resolve_module(index)?;
+        }
+        Ok(())
+    }
+
+    // The module that is run, like manage.blz, is loaded once the packages are.
+    pub fn load_entry(&mut self, path: &Path) -> io::Result<usize> {
+        let index = self.load_module(path)?;
+        self.resolve_module(index)?;
+        Ok(index)
+    }
+
+    // Code that isn't read from a file, like the one given to the shell, is named by its source.
+    pub fn load_entry_code(&mut self, code_source: &str, code: String) -> io::Result<usize> {
+        let index = self.add_module(PathBuf::from(code_source), code)?;
+        self.resolve_module(index)?;
+        Ok(index)
+    }
+
+    pub fn load_module(&mut self, path: &Path) -> io::Result<usize> {
+        let path = fs::canonicalize(path)?;
+        if let Some(index) = self.module_indexes.get(&path) {
+            return Ok(*index);
+        }
+        let code = fs::read_to_string(&path)?;
+        self.add_module(path, code)
+    }
+
+    fn add_module(&mut self, path: PathBuf, code: String) -> io::Result<usize> {
+        let code_source = path.display().to_string();
+        self.sources.add(code_source.clone(), code.clone());
+        let mut code_lexer = Lexer::new(code);
+        code_lexer
+            .get_context()
+            .set_code_source(code_source.clone());
+        let tokens = code_lexer.analyze()?;
+        let mut code_parser = Parser::new(tokens);
+        code_parser.get_context().set_code_source(code_source);
+        let body = code_parser.parse()?;
+
+        let mut package_nodes = body
+            .nodes
+            .iter()
+            .filter_map(|node| node.downcast_ref::<PackageNode>());
+        let package = package_nodes.next().map(|node| node.get_name().to_string());
+        if package_nodes.next().is_some() {
+            return Err(package_error(format!(
+                "{} declares more than one package",
+                path.display()
+            )));
+        }
+
+        self.modules.push(Module {
+            path: path.clone(),
+            package,
+            body,
+            is_resolved: false,
+        });
+        let index = self.modules.len() - 1;
+        self.module_indexes.insert(path, index);
+        Ok(index)
+    }
+
+    // Modules see what they import as well as what the other files of their package declare,
+    // so their names are resolved once every package is loaded.
+    fn resolve_module(&mut self, module_index: usize) -> io::Result<()> {
+        if self.modules[module_index].is_resolved {
+            return Ok(());
+        }
+        let mut symbols = self.get_imported_symbols(module_index)?;
+        if let Some(package) = self.get_module_package(module_index) {
+            symbols.extend(self.get_package_symbols(package)?);
+        }
+        let module = &mut self.modules[module_index];
+        let mut resolver = Resolver::new(&module.path.display().to_string());
+        resolver.import_symbols(&symbols);
+        let diagnostics = resolver.resolve(&mut module.body);
+        module.is_resolved = true;
+        if diagnostics.has_errors() {
+            return Err(diagnostics.into());
+        }
+        self.warnings.extend(diagnostics.0);
+        Ok(())
+    }
+
+    // Runs the modules the given one depends on, in the order of their imports,
+    // and then the module itself. The value of the module is returned.
+    pub fn execute_module<'a>(
+        &'a self,
+        module_index: usize,
+        executor: &mut Executor<'a>,
+    ) -> io::Result<Value> {
+        let mut value = Value::Null;
+        for module in self.get_execution_order(module_index)? {
+            value = executor.execute(module.get_body())?;
+        }
+        Ok(value)
+    }
+
+    pub fn get_module(&self, index: usize) -> &Module {
+        &self.modules[index]
+    }
+
+    pub fn get_package_modules(&self, package: &str) -> Option<Vec<&Module>> {
+        let indexes = self.packages.get(package)?;
+        Some(indexes.iter().map(|index| &self.modules[*index]).collect())
+    }
+
+    pub fn get_package_symbols(&self, package: &str) -> io::Result<Vec<Symbol>> {
+        let indexes = self.packages.get(package).ok_or_else(|| {
+            package_error(format!(
+                "package '{}' is not found in {}",
+                package,
+                self.packages_dir.display()
+            ))
+        })?;
+        Ok(indexes
+            .iter()
+            .flat_map(|index| self.modules[*index].get_symbols(package))
+            .collect())
+    }
+
+    // The symbols the module brings into its scope with `import` statements.
+    pub fn get_imported_symbols(&self, module_index: usize) -> io::Result<Vec<Symbol>> {
+        let mut symbols: Vec<Symbol> = vec![];
+        for import in self.modules[module_index].get_imports() {
+            let package_symbols = self.get_package_symbols(import.get_package())?;
+            if import.is_wildcard() {
+                symbols.extend(package_symbols);
+                continue;
+            }
+            let symbol = package_symbols
+                .into_iter()
+                .find(|symbol| symbol.get_name() == import.get_symbol())
+                .ok_or_else(|| {
+                    package_error(format!(
+                        "package '{}' has no symbol '{}'",
+                        import.get_package(),
+                        import.get_symbol()
+                    ))
+                })?;
+            symbols.push(symbol);
+        }
+        Ok(symbols)
+    }
+
+    // The modules to execute before the given one can be, with the module itself at the end.
+    pub fn get_execution_order(&self, module_index: usize) -> io::Result<Vec<&Module>> {
+        let mut order: Vec<usize> = vec![];
+        let mut resolved_packages: Vec<String> = vec![];
+        let module = &self.modules[module_index];
+        match self.get_module_package(module_index) {
+            Some(package) => self.resolve_package_order(
+                package,
+                &mut vec![],
+                &mut resolved_packages,
+                &mut order,
+            )?,
+            None => {
+                for import in module.get_imports() {
+                    self.resolve_package_order(
+                        import.get_package(),
+                        &mut vec![],
+                        &mut resolved_packages,
+                        &mut order,
+                    )?;
+                }
+            }
+        }
+        if !order.contains(&module_index) {
+            order.push(module_index);
+        }
+        Ok(order
+            .into_iter()
+            .map(|index| &self.modules[index])
+            .collect())
+    }
+
+    fn get_module_package(&self, module_index: usize) -> Option<&str> {
+        self.packages
+            .iter()
+            .find(|(_, indexes)| indexes.contains(&module_index))
+            .map(|(package, _)| package.as_str())
+    }
+
+    // Depth-first search over the imports of the packages: the packages being resolved
+    // are kept in the stack, so meeting one of them again means there's an import cycle.
+    fn resolve_package_order(
+        &self,
+        package: &str,
+        stack: &mut Vec<String>,
+        resolved_packages: &mut Vec<String>,
+        order: &mut Vec<usize>,
+    ) -> io::Result<()> {
+        if resolved_packages.iter().any(|resolved| resolved == package) {
+            return Ok(());
+        }
+        if let Some(position) = stack.iter().position(|pending| pending == package) {
+            let mut cycle = stack[position..].to_vec();
+            cycle.push(package.to_string());
+            return Err(package_error(format!(
+                "import cycle detected: {}",
+                cycle.join(" -> ")
+            )));
+        }
+        let indexes = self.packages.get(package).ok_or_else(|| {
+            package_error(format!(
+                "package '{}' is not found in {}",
+                package,
+                self.packages_dir.display()
+            ))
+        })?;
+
+        stack.push(package.to_string());
+        for index in indexes {
+            for import in self.modules[*index].get_imports() {
+                if import.get_package() != package {
+                    self.resolve_package_order(
+                        import.get_package(),
+                        stack,
+                        resolved_packages,
+                        order,
+                    )?;
+                }
+            }
+        }
+        stack.pop();
+        resolved_packages.push(package.to_string());
+        order.extend(indexes);
+        Ok(())
+    }
+
+    fn check_duplicate_symbols(&self, package: &str) -> io::Result<()> {
+        let symbols = self.get_package_symbols(package)?;
+        for (index, symbol) in symbols.iter().enumerate() {
+            if let Some(duplicate) = symbols[..index]
+                .iter()
+                .find(|declared| declared.get_name() == symbol.get_name())
+            {
+                return Err(package_error(format!(
+                    "'{}' is declared twice in package '{}': in {} and {}",
+                    symbol.get_name(),
+                    package,
+                    duplicate.get_path().display(),
+                    symbol.get_path().display()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn package_error(message: impl AsRef<str>) -> io::Error {
+    io::Error::other(format!("Package Error: {}", message.as_ref()))
+}
+
+pub fn find_blaze_files(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = vec![];
+    let mut entries: Vec<PathBuf> = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            files.extend(find_blaze_files(&path)?);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == BLAZE_EXTENSION)
+        {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
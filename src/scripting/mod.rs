@@ -1,6 +1,15 @@
 pub mod ast;
 pub mod context;
+pub mod diagnostic;
 pub mod executor;
+pub mod formatter;
 pub mod lexer;
+pub mod loader;
+pub mod number;
 pub mod parser;
+pub mod resolver;
+pub mod source_map;
+pub mod span;
 pub mod tokens;
+pub mod type_checker;
+pub mod value;
@@ -0,0 +1,493 @@
+use bson::Bson;
+use std::cmp::Ordering;
+use std::fmt;
+
+// Decimals keep up to this many significant digits, like the 96-bit decimals of other databases.
+const MAX_DECIMAL_DIGITS: u32 = 28;
+
+// Numbers keep the kind they are written with: `1`, `0xFF` and `0o17` are ints, `1.5` and `1e9`
+// are floats and `19.99m` is a decimal. Integers too big for an int are uints.
+#[derive(Debug, Clone, Copy)]
+pub enum Number {
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+    Decimal(Decimal),
+}
+
+impl Number {
+    // The lexer reports the literals that are out of range, so the parser never sees them.
+    pub fn parse_literal(literal: &str) -> Result<Number, String> {
+        let out_of_range = |type_name| format!("{} is out of range of '{}'", literal, type_name);
+        let text: String = literal.chars().filter(|char| *char != '_').collect();
+        let (is_negative, unsigned_text) = match text.strip_prefix('-') {
+            Some(unsigned_text) => (true, unsigned_text),
+            None => (false, text.as_str()),
+        };
+        let radix_digits = [("0x", 16), ("0o", 8), ("0b", 2)]
+            .into_iter()
+            .find_map(|(prefix, radix)| Some((unsigned_text.strip_prefix(prefix)?, radix)));
+        if let Some(decimal_text) = text.strip_suffix('m') {
+            return Decimal::parse(decimal_text)
+                .map(Number::Decimal)
+                .ok_or_else(|| out_of_range("decimal"));
+        }
+        if radix_digits.is_none() && unsigned_text.contains(['.', 'e', 'E']) {
+            return match text.parse::<f64>() {
+                Ok(float) if float.is_finite() => Ok(Number::Float(float)),
+                _ => Err(out_of_range("float")),
+            };
+        }
+        let (digits, radix) = radix_digits.unwrap_or((unsigned_text, 10));
+        let integer =
+            u64::from_str_radix(digits, radix)
+                .ok()
+                .and_then(|magnitude| match is_negative {
+                    true => narrow(-i128::from(magnitude), false),
+                    false => narrow(i128::from(magnitude), false),
+                });
+        integer.ok_or_else(|| out_of_range(if is_negative { "int" } else { "uint" }))
+    }
+
+    pub fn get_type_name(&self) -> &'static str {
+        match self {
+            Number::Int(_) => "int",
+            Number::Uint(_) => "uint",
+            Number::Float(_) => "float",
+            Number::Decimal(_) => "decimal",
+        }
+    }
+
+    // How the number is written in the code, so it's read back as the same kind.
+    pub fn to_literal(&self) -> String {
+        match self {
+            Number::Float(float) => format!("{:?}", float),
+            Number::Decimal(decimal) => format!("{}m", decimal),
+            _ => self.to_string(),
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Int(int) => *int as f64,
+            Number::Uint(uint) => *uint as f64,
+            Number::Float(float) => *float,
+            Number::Decimal(decimal) => decimal.to_f64(),
+        }
+    }
+
+    // Only whole numbers, like the positions of items, are integers.
+    pub fn to_i128(&self) -> Option<i128> {
+        match self {
+            Number::Int(int) => Some(i128::from(*int)),
+            Number::Uint(uint) => Some(i128::from(*uint)),
+            Number::Float(float) if float.fract() == 0.0 && float.abs() < 2f64.powi(64) => {
+                Some(*float as i128)
+            }
+            Number::Decimal(decimal) => decimal.to_whole(),
+            Number::Float(_) => None,
+        }
+    }
+
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        match self {
+            Number::Int(int) => Decimal::new(i128::from(*int), 0),
+            Number::Uint(uint) => Decimal::new(i128::from(*uint), 0),
+            Number::Float(float) => Decimal::parse(&float.to_string()),
+            Number::Decimal(decimal) => Some(*decimal),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.to_f64() == 0.0
+    }
+
+    // Casts to the kind of a built-in type, where the fraction is cut off for integers.
+    pub fn convert(&self, type_name: &str) -> Option<Number> {
+        let truncated = || match self {
+            Number::Float(float) if float.is_finite() && float.abs() < 2f64.powi(64) => {
+                Some(float.trunc() as i128)
+            }
+            Number::Float(_) => None,
+            Number::Decimal(decimal) => Some(decimal.truncate()),
+            _ => self.to_i128(),
+        };
+        match type_name {
+            "int" => truncated()
+                .and_then(|int| i64::try_from(int).ok())
+                .map(Number::Int),
+            "uint" => truncated()
+                .and_then(|uint| u64::try_from(uint).ok())
+                .map(Number::Uint),
+            "float" => Some(Number::Float(self.to_f64())),
+            "decimal" => self.to_decimal().map(Number::Decimal),
+            _ => None,
+        }
+    }
+
+    // Negative uints are ints, so only the ones below the smallest int are out of range.
+    pub fn checked_neg(self) -> Option<Number> {
+        match self {
+            Number::Float(float) => Some(Number::Float(-float)),
+            Number::Decimal(decimal) => Some(Number::Decimal(Decimal {
+                mantissa: -decimal.mantissa,
+                ..decimal
+            })),
+            Number::Int(int) => int.checked_neg().map(Number::Int),
+            Number::Uint(_) => narrow(-self.to_i128()?, false),
+        }
+    }
+
+    // Floats win over decimals and decimals win over integers. None is out of range.
+    pub fn checked_add(self, other: Number) -> Option<Number> {
+        self.combine(
+            other,
+            i128::checked_add,
+            Decimal::checked_add,
+            |left, right| left + right,
+        )
+    }
+
+    pub fn checked_sub(self, other: Number) -> Option<Number> {
+        self.combine(
+            other,
+            i128::checked_sub,
+            Decimal::checked_sub,
+            |left, right| left - right,
+        )
+    }
+
+    pub fn checked_mul(self, other: Number) -> Option<Number> {
+        self.combine(
+            other,
+            i128::checked_mul,
+            Decimal::checked_mul,
+            |left, right| left * right,
+        )
+    }
+
+    // Integers are divided into floats, like in the type checker.
+    pub fn checked_div(self, other: Number) -> Option<Number> {
+        match (self, other) {
+            (Number::Decimal(_), Number::Int(_) | Number::Uint(_) | Number::Decimal(_))
+            | (Number::Int(_) | Number::Uint(_), Number::Decimal(_)) => {
+                let quotient = self.to_decimal()?.checked_div(other.to_decimal()?)?;
+                Some(Number::Decimal(quotient))
+            }
+            _ => finite(self.to_f64() / other.to_f64()),
+        }
+    }
+
+    // Decimals raised to whole powers stay exact, everything else is a float.
+    pub fn checked_pow(self, exponent: Number) -> Option<Number> {
+        let whole_exponent = match exponent {
+            Number::Int(_) | Number::Uint(_) => exponent.to_i128(),
+            _ => None,
+        };
+        match (
+            self,
+            whole_exponent.and_then(|exponent| u32::try_from(exponent).ok()),
+        ) {
+            (Number::Decimal(base), Some(exponent)) => {
+                base.checked_pow(exponent).map(Number::Decimal)
+            }
+            _ => finite(self.to_f64().powf(exponent.to_f64())),
+        }
+    }
+
+    fn combine(
+        self,
+        other: Number,
+        integer_operation: fn(i128, i128) -> Option<i128>,
+        decimal_operation: fn(Decimal, Decimal) -> Option<Decimal>,
+        float_operation: fn(f64, f64) -> f64,
+    ) -> Option<Number> {
+        match (self, other) {
+            (Number::Float(_), _) | (_, Number::Float(_)) => {
+                finite(float_operation(self.to_f64(), other.to_f64()))
+            }
+            (Number::Decimal(_), _) | (_, Number::Decimal(_)) => {
+                let decimal = decimal_operation(self.to_decimal()?, other.to_decimal()?)?;
+                Some(Number::Decimal(decimal))
+            }
+            _ => {
+                let integer = integer_operation(self.to_i128()?, other.to_i128()?)?;
+                // Ints never turn into uints, so `9223372036854775807 + 1` is out of range.
+                match (self, other) {
+                    (Number::Int(_), Number::Int(_)) => {
+                        i64::try_from(integer).ok().map(Number::Int)
+                    }
+                    (Number::Uint(_), Number::Uint(_)) => narrow(integer, true),
+                    _ => narrow(integer, false),
+                }
+            }
+        }
+    }
+}
+
+// Results of uints stay uints while they are positive, other integers are ints while they fit.
+fn narrow(integer: i128, is_unsigned: bool) -> Option<Number> {
+    match (i64::try_from(integer), u64::try_from(integer)) {
+        (_, Ok(uint)) if is_unsigned => Some(Number::Uint(uint)),
+        (Ok(int), _) => Some(Number::Int(int)),
+        (_, Ok(uint)) => Some(Number::Uint(uint)),
+        _ => None,
+    }
+}
+
+fn finite(float: f64) -> Option<Number> {
+    float.is_finite().then_some(Number::Float(float))
+}
+
+// Numbers of different kinds are equal when their values are, so `1 == 1.0`.
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Float(_), _) | (_, Number::Float(_)) => {
+                self.to_f64().partial_cmp(&other.to_f64())
+            }
+            (Number::Decimal(_), _) | (_, Number::Decimal(_)) => {
+                Some(self.to_decimal()?.cmp(&other.to_decimal()?))
+            }
+            _ => Some(self.to_i128()?.cmp(&other.to_i128()?)),
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Int(int) => write!(f, "{}", int),
+            Number::Uint(uint) => write!(f, "{}", uint),
+            Number::Float(float) => write!(f, "{}", float),
+            Number::Decimal(decimal) => write!(f, "{}", decimal),
+        }
+    }
+}
+
+// Uints that don't fit into BSON integers and decimals are stored as Decimal128.
+impl From<Number> for Bson {
+    fn from(number: Number) -> Self {
+        match number {
+            Number::Int(int) => Bson::Int64(int),
+            Number::Uint(uint) => match i64::try_from(uint) {
+                Ok(int) => Bson::Int64(int),
+                Err(_) => Bson::Decimal128(uint.to_string().parse().unwrap()),
+            },
+            Number::Float(float) => Bson::Double(float),
+            Number::Decimal(decimal) => Bson::Decimal128(decimal.to_string().parse().unwrap()),
+        }
+    }
+}
+
+// An exact fraction, `mantissa / 10^scale`, for values like prices that floats can't hold.
+// The scale is kept as written, so `1.50m` is printed with both digits.
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    // Digits beyond the limit are rounded off the fraction, None when the whole part doesn't fit.
+    pub fn new(mut mantissa: i128, mut scale: u32) -> Option<Self> {
+        let limit = 10u128.pow(MAX_DECIMAL_DIGITS);
+        while (mantissa.unsigned_abs() >= limit || scale > MAX_DECIMAL_DIGITS) && scale > 0 {
+            mantissa = round_last_digit(mantissa);
+            scale -= 1;
+        }
+        (mantissa.unsigned_abs() < limit).then_some(Decimal { mantissa, scale })
+    }
+
+    // Reads `-12.5`, `1_000.25` without the underscores, or `1.5e3`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (is_negative, text) = match text.strip_prefix('-') {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+        let (significand, exponent) = match text.split_once(['e', 'E']) {
+            Some((significand, exponent)) => (significand, exponent.parse::<i64>().ok()?),
+            None => (text, 0),
+        };
+        let (integer, fraction) = significand.split_once('.').unwrap_or((significand, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return None;
+        }
+        let mut mantissa: i128 = 0;
+        for digit in integer.bytes().chain(fraction.bytes()) {
+            if !digit.is_ascii_digit() {
+                return None;
+            }
+            mantissa = mantissa
+                .checked_mul(10)?
+                .checked_add(i128::from(digit - b'0'))?;
+        }
+        if is_negative {
+            mantissa = -mantissa;
+        }
+        let scale = fraction.len() as i64 - exponent;
+        if scale < 0 {
+            let factor = 10i128.checked_pow(u32::try_from(-scale).ok()?)?;
+            return Decimal::new(mantissa.checked_mul(factor)?, 0);
+        }
+        // Every digit is rounded off long before such a scale.
+        if scale > i64::from(MAX_DECIMAL_DIGITS) * 2 {
+            return Decimal::new(0, 0);
+        }
+        Decimal::new(mantissa, scale as u32)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap()
+    }
+
+    // The whole part, with the fraction cut off.
+    pub fn truncate(&self) -> i128 {
+        self.mantissa / 10i128.pow(self.scale)
+    }
+
+    fn to_whole(self) -> Option<i128> {
+        let divisor = 10i128.pow(self.scale);
+        (self.mantissa % divisor == 0).then_some(self.mantissa / divisor)
+    }
+
+    fn rescale(&self, scale: u32) -> Option<i128> {
+        let factor = 10i128.checked_pow(scale.checked_sub(self.scale)?)?;
+        self.mantissa.checked_mul(factor)
+    }
+
+    pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(other.scale);
+        Decimal::new(
+            self.rescale(scale)?.checked_add(other.rescale(scale)?)?,
+            scale,
+        )
+    }
+
+    pub fn checked_sub(self, other: Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(other.scale);
+        Decimal::new(
+            self.rescale(scale)?.checked_sub(other.rescale(scale)?)?,
+            scale,
+        )
+    }
+
+    pub fn checked_mul(self, other: Decimal) -> Option<Decimal> {
+        Decimal::new(
+            self.mantissa.checked_mul(other.mantissa)?,
+            self.scale + other.scale,
+        )
+    }
+
+    // Digits are added to the fraction until the division is exact or the digits run out,
+    // then the last one is rounded half away from zero.
+    pub fn checked_div(self, other: Decimal) -> Option<Decimal> {
+        if other.mantissa == 0 {
+            return None;
+        }
+        let mut numerator = self.mantissa;
+        let mut scale = i64::from(self.scale) - i64::from(other.scale);
+        while numerator % other.mantissa != 0 && scale < i64::from(MAX_DECIMAL_DIGITS) {
+            let Some(scaled_numerator) = numerator.checked_mul(10) else {
+                break;
+            };
+            numerator = scaled_numerator;
+            scale += 1;
+        }
+        let mut quotient = numerator / other.mantissa;
+        let remainder = numerator % other.mantissa;
+        if remainder.unsigned_abs() * 2 >= other.mantissa.unsigned_abs() {
+            quotient += numerator.signum() * other.mantissa.signum();
+        }
+        if scale < 0 {
+            let factor = 10i128.checked_pow(u32::try_from(-scale).ok()?)?;
+            return Decimal::new(quotient.checked_mul(factor)?, 0);
+        }
+        Decimal::new(quotient, scale as u32)
+    }
+
+    // Squares the base for every bit of the exponent, so even huge exponents take a few steps.
+    pub fn checked_pow(self, exponent: u32) -> Option<Decimal> {
+        let one = Decimal::new(1, 0)?;
+        match self.to_whole() {
+            Some(0) if exponent > 0 => return Decimal::new(0, 0),
+            Some(0 | 1) => return Some(one),
+            Some(-1) if exponent.is_multiple_of(2) => return Some(one),
+            Some(-1) => return Some(self),
+            _ => {}
+        }
+        // Powers with more whole digits than a decimal holds are out of range,
+        // and the ones smaller than its last fraction digit are rounded to zero.
+        let digits = f64::from(exponent) * self.to_f64().abs().log10();
+        if digits > f64::from(MAX_DECIMAL_DIGITS) {
+            return None;
+        }
+        if digits < -f64::from(MAX_DECIMAL_DIGITS + 1) {
+            return Decimal::new(0, 0);
+        }
+        let (mut power, mut base, mut exponent) = (one, self, exponent);
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                power = power.checked_mul(base)?;
+            }
+            exponent /= 2;
+            if exponent > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+        Some(power)
+    }
+}
+
+fn round_last_digit(mantissa: i128) -> i128 {
+    let rounded = mantissa / 10;
+    match (mantissa % 10).abs() >= 5 {
+        true => rounded + mantissa.signum(),
+        false => rounded,
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        match (self.rescale(scale), other.rescale(scale)) {
+            (Some(left), Some(right)) => left.cmp(&right),
+            // Only decimals far apart can't be brought to the same scale.
+            _ => self.to_f64().total_cmp(&other.to_f64()),
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
@@ -1,65 +1,87 @@
+use super::ast::array::ArrayNode;
+use super::ast::attach::AttachNode;
 use super::ast::binary_operator::BinaryOperatorNode;
 use super::ast::body::BodyNode;
 use super::ast::boolean::BooleanNode;
-use super::ast::call::CallNode;
+use super::ast::call::{CallNode, CallType};
 use super::ast::conditional_tree::{ConditionalTreeNode, Conditions};
+use super::ast::document::{DocumentFields, DocumentNode};
+use super::ast::enum_declaration::EnumDeclarationNode;
+use super::ast::event_declaration::{ChangeKind, EventDeclarationNode};
 use super::ast::expression::ExpressionNode;
+use super::ast::for_loop::ForLoopNode;
 use super::ast::function_declaration::FunctionDeclarationNode;
 use super::ast::functional_return::FunctionalReturnNode;
 use super::ast::identifier::IdentifierNode;
+use super::ast::import::{Import, ImportNode};
+use super::ast::inspect::InspectNode;
 use super::ast::loop_control::{LoopControlNode, LoopControlType};
+use super::ast::manage::ManageNode;
 use super::ast::member::MemberNode;
 use super::ast::null::NullNode;
 use super::ast::number::NumberNode;
+use super::ast::package::PackageNode;
 use super::ast::parameter::{Parameter, ParameterType, Parameters};
+use super::ast::parenthesized::ParenthesizedNode;
 use super::ast::string::StringNode;
+use super::ast::table_declaration::{
+    Column, ColumnType, Columns, Constraint, ReferenceModifier, TableDeclarationNode,
+};
 use super::ast::unary_operator::UnaryOperatorNode;
 use super::ast::variable_declaration::VariableDeclaration;
 use super::ast::while_loop::WhileLoopNode;
 use super::context::Context;
+use super::diagnostic::{Diagnostic, DiagnosticCode, Diagnostics};
+use super::number::Number;
+use super::span::Span;
 use super::tokens::{
-    Token, TokenSide, TokenType, BINARY_OPERATOR_TOKENS, FORMULA_TOKENS, UNARY_OPERATOR_TOKENS,
-    VARIABLE_ASSIGNMENT_TOKENS,
+    Token, TokenSide, TokenType, BINARY_OPERATOR_TOKENS, CONSTRAINT_TOKENS, FORMULA_TOKENS,
+    UNARY_OPERATOR_TOKENS, VARIABLE_ASSIGNMENT_TOKENS,
 };
-use colored::*;
-use rand::seq::SliceRandom;
-use std::io::{self, Result};
+
+type Result<T> = std::result::Result<T, Box<Diagnostic>>;
+
+// Brackets, unary operators and chains of right-associative operators are parsed recursively,
+// so deeper code is reported instead of overflowing the stack, even on 2 MB thread stacks.
+const MAX_NESTING_DEPTH: usize = 64;
 
 pub struct Parser {
     tokens: Vec<Token>,
     context: Context,
     parser_position: u64,
-
-    syntax_error_marking: ColoredString,
+    // The brackets and unary operators the parser is inside of.
+    nesting_depth: usize,
+    // The doc comments waiting for the declaration they are written above.
+    pending_doc: Option<String>,
+    // The errors of the statements skipped so far.
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
         Parser {
             context: Context::default(),
-            tokens,
+            tokens: keep_declaration_docs(tokens),
             parser_position: 0,
-
-            syntax_error_marking: "Syntax Error".bright_red(),
+            nesting_depth: 0,
+            pending_doc: None,
+            diagnostics: vec![],
         }
     }
 
-    pub fn parse(&mut self) -> Result<BodyNode> {
-        let body = self.run_parsing_logic();
-        if let Err(error) = body {
-            eprintln!("{}", error);
-            return Ok(BodyNode::new());
+    // A statement with an error is skipped, so the ones after it are still checked.
+    pub fn parse(&mut self) -> std::result::Result<BodyNode, Diagnostics> {
+        let mut body = self.parse_body_outside_brackets();
+        // Only a '}' without a pair stops the body before the end of the code.
+        while self.get_current_token().is_ok() {
+            let diagnostic = self.unexpected_tokens_error(vec![]);
+            self.diagnostics.push(diagnostic);
+            self.move_position();
+            body.append(self.parse_body_outside_brackets());
+        }
+        if !self.diagnostics.is_empty() {
+            return Err(Diagnostics(std::mem::take(&mut self.diagnostics)));
         }
-        body
-    }
-
-    fn run_parsing_logic(&mut self) -> Result<BodyNode> {
-        let body = self.parse_body_outside_brackets()?;
-        if self.get_current_token().is_ok()
-            && self.get_current_token()?.is_type(TokenType::RBracket)
-        {
-            self.raise_unexpected_tokens_error(vec![])?;
-        };
         Ok(body)
     }
 
@@ -69,9 +91,12 @@ impl Parser {
         {
             return Ok(None);
         }
-        self.move_position();
-        let body = self.parse_body_outside_brackets()?;
-        self.require_token(vec![TokenType::RBracket])?;
+        self.enter_nesting()?;
+        let opening_bracket = self.move_position();
+        let mut body = self.parse_body_outside_brackets();
+        self.exit_nesting();
+        let closing_bracket = self.require_token(vec![TokenType::RBracket])?;
+        body.set_span(join_spans(&opening_bracket, &closing_bracket));
         Ok(Some(body))
     }
 
@@ -83,17 +108,10 @@ impl Parser {
         Ok(body.unwrap())
     }
 
-    fn parse_body_outside_brackets(&mut self) -> Result<BodyNode> {
+    fn parse_body_outside_brackets(&mut self) -> BodyNode {
         let mut root = BodyNode::new();
-        let mut add_node = |node: Box<dyn ExpressionNode>| {
-            root.add_node(node);
-        };
-        loop {
-            if self.get_current_token().is_err() {
-                break;
-            };
-
-            match self.get_current_token()?.token_type {
+        while let Ok(current_token) = self.get_current_token() {
+            match current_token.token_type {
                 TokenType::RBracket => {
                     break;
                 }
@@ -101,22 +119,82 @@ impl Parser {
                     self.move_position();
                     continue;
                 }
+                TokenType::DocComment => {
+                    let doc_line = self.move_position().value;
+                    let doc_line = doc_line[3..].strip_prefix(' ').unwrap_or(&doc_line[3..]);
+                    let doc = self.pending_doc.get_or_insert_with(String::new);
+                    if !doc.is_empty() {
+                        doc.push('\n');
+                    }
+                    doc.push_str(doc_line);
+                    continue;
+                }
                 _ => {}
             };
 
-            let parsed_expression = self.parse_expression()?;
-            add_node(parsed_expression);
-            self.move_position();
+            let statement_start = self.parser_position;
+            let nesting_depth = self.nesting_depth;
+            match self.parse_statement() {
+                Ok((node, span)) => root.add_spanned_node(node, span),
+                Err(diagnostic) => {
+                    self.nesting_depth = nesting_depth;
+                    self.diagnostics.push(*diagnostic);
+                    self.synchronize(statement_start);
+                }
+            }
+        }
+        root
+    }
 
-            if let Ok(next_token) = self.get_current_token() {
-                if !next_token.is_type(TokenType::ExpressionEnd)
-                    && !next_token.is_type(TokenType::RBracket)
-                {
-                    self.raise_unexpected_tokens_error(vec![TokenType::ExpressionEnd])?;
+    fn parse_statement(&mut self) -> Result<(Box<dyn ExpressionNode>, Span)> {
+        let first_token = self.get_current_token()?;
+        let parsed_expression = self.parse_expression()?;
+        let last_token = self.move_position();
+        let span = join_spans(&first_token, &last_token);
+
+        if let Ok(next_token) = self.get_current_token() {
+            // Expressions closed with a bracket, like declarations, don't need ';' after them.
+            if !next_token.is_type(TokenType::ExpressionEnd)
+                && !next_token.is_type(TokenType::RBracket)
+                && !last_token.is_type(TokenType::RBracket)
+            {
+                self.raise_unexpected_tokens_error(vec![TokenType::ExpressionEnd])?;
+            }
+        }
+        Ok((parsed_expression, span))
+    }
+
+    // Skips the rest of a failed statement: everything up to the next ';' or up to
+    // the '}' closing the brackets opened in the statement. The '}' of the body around it is kept.
+    fn synchronize(&mut self, statement_start: u64) {
+        let skipped_tokens = &self.tokens[statement_start as usize..self.parser_position as usize];
+        let mut depth = skipped_tokens
+            .iter()
+            .fold(0usize, |depth, token| match token.token_type {
+                TokenType::LBracket => depth + 1,
+                TokenType::RBracket => depth.saturating_sub(1),
+                _ => depth,
+            });
+        while let Ok(current_token) = self.get_current_token() {
+            match current_token.token_type {
+                TokenType::ExpressionEnd if depth == 0 => {
+                    self.move_position();
+                    break;
+                }
+                TokenType::RBracket if depth == 0 => break,
+                TokenType::RBracket => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.move_position();
+                        break;
+                    }
                 }
+                TokenType::LBracket => depth += 1,
+                _ => {}
             }
+            self.move_position();
         }
-        Ok(root)
+        self.pending_doc = None;
     }
 
     pub fn get_context(&mut self) -> &mut Context {
@@ -128,31 +206,73 @@ impl Parser {
             let current_token = self.tokens[self.parser_position as usize].clone();
             return Ok(current_token);
         }
-        Err(io::Error::new(
-            io::ErrorKind::Other,
-            format!(
-                "{}: {}",
-                "FATAL".red(),
-                "Attempted to access a non-existent token"
+        Err(self
+            .syntax_error(
+                DiagnosticCode::UnexpectedEnd,
+                "unexpected end of code",
+                self.get_error_span(),
+            )
+            .into())
+    }
+
+    fn enter_nesting(&mut self) -> Result<()> {
+        if self.nesting_depth == MAX_NESTING_DEPTH {
+            return Err(self
+                .syntax_error(
+                    DiagnosticCode::NestingTooDeep,
+                    format!("code is nested deeper than {} levels", MAX_NESTING_DEPTH),
+                    self.get_error_span(),
+                )
+                .with_span_label("too deep")
+                .into());
+        }
+        self.nesting_depth += 1;
+        Ok(())
+    }
+
+    fn exit_nesting(&mut self) {
+        self.nesting_depth -= 1;
+    }
+
+    fn syntax_error(
+        &self,
+        code: DiagnosticCode,
+        message: impl Into<String>,
+        span: Span,
+    ) -> Diagnostic {
+        Diagnostic::error(code, message, &self.context.code_source, span)
+    }
+
+    // The span of the current token, or the empty one right after the code when it's over.
+    fn get_error_span(&self) -> Span {
+        if let Some(token) = self.tokens.get(self.parser_position as usize) {
+            return token.span;
+        }
+        match self.tokens.last() {
+            Some(token) => Span::new(
+                token.span.stop,
+                token.span.stop,
+                token.span.line,
+                token.span.column + token.value.chars().count(),
             ),
-        ))
+            None => Span::new(0, 0, 1, 1),
+        }
     }
 
     fn move_position(&mut self) -> Token {
         let current_token = self.get_current_token().unwrap();
         self.parser_position += 1;
-        // There is no reason to start line and position variables from zero
-        // since a parser is not supposed to work with code, but with tokens instead.
-        self.context.line = current_token.line + 1;
-        self.context.position = current_token.start + 1;
         current_token
     }
 
+    fn is_next_token(&self, token_type: TokenType) -> bool {
+        self.tokens
+            .get(self.parser_position as usize + 1)
+            .is_some_and(|token| token.is_type(token_type))
+    }
+
     fn move_position_back(&mut self) {
         self.parser_position -= 1;
-        let current_token = self.get_current_token().unwrap();
-        self.context.line = current_token.line + 1;
-        self.context.position = current_token.start + 1;
     }
 
     fn is_position_movable(&self) -> bool {
@@ -196,49 +316,39 @@ impl Parser {
     }
 
     fn raise_unexpected_tokens_error(&mut self, expected_tokens: Vec<TokenType>) -> Result<()> {
-        let mut shuffled_tokens = expected_tokens;
+        Err(self.unexpected_tokens_error(expected_tokens).into())
+    }
+
+    fn unexpected_tokens_error(&mut self, expected_tokens: Vec<TokenType>) -> Diagnostic {
         let current_token = self.get_current_token();
-        let error_location_notation = format!(
-            "<-= {}:{}:{}",
-            self.context.code_source, self.context.line, self.context.position
-        );
-        shuffled_tokens.shuffle(&mut rand::thread_rng());
-        let error_message = if !shuffled_tokens.is_empty() {
-            format!(
-                "{}: expected {}{} {}",
-                self.syntax_error_marking,
-                &match &shuffled_tokens[..] {
-                    [] => "void".to_string(),
-                    [first] => format!("'{}'", first),
-                    [first, second] => format!("'{}' or '{}'", first, second),
-                    [first, second, third] => format!("'{}', '{}', or '{}'", first, second, third),
-                    [first, second, third, fourth] => {
-                        format!("{}, {}, {}, or {}", first, second, third, fourth)
-                    }
-                    _ => {
-                        format!(
-                            "'{}', '{}', '{}', or one of {} other tokens",
-                            shuffled_tokens[0],
-                            shuffled_tokens[1],
-                            shuffled_tokens[2],
-                            shuffled_tokens.len() - 3
-                        )
-                    }
-                },
-                if current_token.is_ok() {
-                    format!(", found '{}'", current_token?.value)
-                } else {
-                    String::new()
-                },
-                error_location_notation
-            )
-        } else {
-            format!(
-                "{}: unexpected '{}' found {}",
-                self.syntax_error_marking, current_token?.value, error_location_notation
-            )
+        let (code, found) = match &current_token {
+            Ok(token) => (
+                DiagnosticCode::UnexpectedToken,
+                format!("'{}'", token.value),
+            ),
+            Err(_) => (DiagnosticCode::UnexpectedEnd, "end of code".to_string()),
+        };
+        let expected_texts: Vec<String> = expected_tokens.iter().map(get_token_type_text).collect();
+        let expected = match &expected_texts[..] {
+            [] => {
+                let message = format!("unexpected {} found", found);
+                return self.syntax_error(code, message, self.get_error_span());
+            }
+            [first] => format!("'{}'", first),
+            [first, second] => format!("'{}' or '{}'", first, second),
+            [first, second, third] => format!("'{}', '{}', or '{}'", first, second, third),
+            [first, second, third, rest @ ..] => format!(
+                "'{}', '{}', '{}', or one of {} other tokens",
+                first,
+                second,
+                third,
+                rest.len()
+            ),
         };
-        Err(io::Error::new(io::ErrorKind::Other, error_message))
+        let message = format!("expected {}, found {}", expected, found);
+        self.syntax_error(code, message, self.get_error_span())
+            .with_span_label(format!("expected {} here", expected))
+            .with_expected_tokens(expected_tokens)
     }
 
     fn parse_expression(&mut self) -> Result<Box<dyn ExpressionNode>> {
@@ -249,12 +359,20 @@ impl Parser {
                 let name_token = self.require_token(vec![TokenType::Alphanumeric])?;
                 let datatype = self.parse_datatype()?;
                 let value_node = self.parse_assignment()?;
-                Ok(Box::new(VariableDeclaration::new(
+                let mut declaration = VariableDeclaration::new(
                     name_token.value,
+                    x == TokenType::Mut,
                     datatype,
                     value_node,
-                )))
+                );
+                declaration.set_span(name_token.span);
+                Ok(Box::new(declaration))
+            }
+            TokenType::For => {
+                self.move_position();
+                self.parse_for_loop()
             }
+            TokenType::Alphanumeric if self.is_next_token(TokenType::Of) => self.parse_for_loop(),
             x if FORMULA_TOKENS.contains(&x) => {
                 let formula_node = self.require_formula()?;
                 Ok(formula_node)
@@ -262,26 +380,32 @@ impl Parser {
             TokenType::Function => {
                 self.move_position();
                 let name_token = self.require_token(vec![TokenType::Alphanumeric])?;
+                let generics = match self.move_if_next_token_is(vec![TokenType::Less]) {
+                    true => self.parse_type_names()?,
+                    false => vec![],
+                };
                 self.move_position();
                 let arguments = self.parse_parameters_in_parenthesis(ParameterType::Function)?;
                 let datatype = self.parse_datatype()?;
                 self.move_position();
-                Ok(Box::new(FunctionDeclarationNode::new(
-                    name_token.value,
-                    datatype,
-                    arguments,
-                    Some(self.require_body()?),
-                )))
+                let doc = self.pending_doc.take();
+                let body = self.require_body()?;
+                let mut function_node =
+                    FunctionDeclarationNode::new(name_token.value, datatype, arguments, Some(body));
+                function_node.set_doc(doc);
+                function_node.set_generics(generics.into_iter().map(|token| token.value).collect());
+                function_node.set_span(name_token.span);
+                Ok(Box::new(function_node))
             }
             TokenType::Return => {
-                let returned_formula_node 
-                    = if self.move_if_next_token_is(FORMULA_TOKENS.to_vec()) {
+                let returned_formula_node = if self.move_if_next_token_is(FORMULA_TOKENS.to_vec()) {
                     Some(self.require_formula()?)
-                } 
-                else {
+                } else {
                     None
                 };
-                Ok(Box::new(FunctionalReturnNode::new(returned_formula_node)))
+                let mut return_node = FunctionalReturnNode::new(returned_formula_node);
+                return_node.set_span(current_token.span);
+                Ok(Box::new(return_node))
             }
             TokenType::While => {
                 self.move_position();
@@ -289,36 +413,319 @@ impl Parser {
                 self.move_position();
                 Ok(Box::new(WhileLoopNode::new(
                     condition_node,
-                    self.require_body()?
+                    self.require_body()?,
                 )))
             }
-            TokenType::Continue => {
-                Ok(Box::new(LoopControlNode::new(LoopControlType::Continue)))
+            TokenType::Package => {
+                self.move_position();
+                let name_token = self.require_token(vec![TokenType::Alphanumeric])?;
+                Ok(Box::new(PackageNode::new(name_token.value)))
+            }
+            TokenType::Import => {
+                let mut imports: Vec<Import> = vec![];
+                loop {
+                    self.move_position();
+                    let package_token = self.require_token(vec![TokenType::Alphanumeric])?;
+                    self.move_position();
+                    self.require_token(vec![TokenType::Colon])?;
+                    self.move_position();
+                    let symbol_token = self.require_token(vec![TokenType::Alphanumeric])?;
+                    imports.push(Import::new(package_token.value, symbol_token.value));
+                    if !self.move_if_next_token_is(vec![TokenType::Comma]) {
+                        break;
+                    }
+                }
+                Ok(Box::new(ImportNode::new(imports)))
+            }
+            TokenType::Inspect => {
+                let mut targets = vec![];
+                loop {
+                    self.move_position();
+                    let target_token = self.require_token(vec![TokenType::Alphanumeric])?;
+                    targets.push(target_token.value);
+                    if !self.move_if_next_token_is(vec![TokenType::Comma]) {
+                        break;
+                    }
+                }
+                let mut inspect_node = InspectNode::new(targets);
+                inspect_node.set_span(current_token.span);
+                Ok(Box::new(inspect_node))
+            }
+            TokenType::Attach => {
+                self.move_position();
+                let path_token = self.require_token(vec![TokenType::CharArray])?;
+                Ok(Box::new(AttachNode::new(unquote(&path_token.value))))
+            }
+            TokenType::Manage => {
+                self.move_position();
+                let settings = self.parse_parameters_in_parenthesis(ParameterType::Call)?;
+                Ok(Box::new(ManageNode::new(settings)))
+            }
+            TokenType::Table => self.parse_table_declaration(),
+            TokenType::Enum => self.parse_enum_declaration(),
+            TokenType::Event => self.parse_event_declaration(),
+            TokenType::Continue | TokenType::Break => {
+                let control_type = match current_token.token_type {
+                    TokenType::Continue => LoopControlType::Continue,
+                    _ => LoopControlType::Break,
+                };
+                let mut loop_control_node = LoopControlNode::new(control_type);
+                loop_control_node.set_span(current_token.span);
+                Ok(Box::new(loop_control_node))
+            }
+            _ => Err(self
+                .syntax_error(
+                    DiagnosticCode::UnexpectedToken,
+                    format!(
+                        "{} is not being considered in this context",
+                        current_token.token_type
+                    ),
+                    current_token.span,
+                )
+                .into()),
+        }
+    }
+
+    fn parse_table_declaration(&mut self) -> Result<Box<dyn ExpressionNode>> {
+        let doc = self.pending_doc.take();
+        self.move_position();
+        let name_token = self.require_token(vec![TokenType::Alphanumeric])?;
+        let primary_key = self.parse_datatype()?;
+        self.move_position();
+        self.require_token(vec![TokenType::LBracket])?;
+        self.move_position();
+
+        let mut columns: Columns = vec![];
+        loop {
+            let column_name_token =
+                self.require_token(vec![TokenType::Alphanumeric, TokenType::RBracket])?;
+            if column_name_token.is_type(TokenType::RBracket) {
+                break;
+            }
+            self.move_position();
+            self.require_token(vec![TokenType::Colon])?;
+            self.move_position();
+            let column_type = self.parse_column_type()?;
+            let constraints = self.parse_constraints()?;
+            let default = self.parse_assignment()?;
+            columns.push(Column::new(
+                column_name_token.value,
+                column_type,
+                constraints,
+                default,
+            ));
+
+            self.move_position();
+            // Columns are separated by commas, yet the last one is usually closed by ';'.
+            let separator_token = self.require_token(vec![
+                TokenType::Comma,
+                TokenType::ExpressionEnd,
+                TokenType::RBracket,
+            ])?;
+            if !separator_token.is_type(TokenType::RBracket) {
+                self.move_position();
+            }
+        }
+        let mut table_node = TableDeclarationNode::new(name_token.value, primary_key, columns);
+        table_node.set_doc(doc);
+        Ok(Box::new(table_node))
+    }
+
+    fn parse_enum_declaration(&mut self) -> Result<Box<dyn ExpressionNode>> {
+        let doc = self.pending_doc.take();
+        self.move_position();
+        let name_token = self.require_token(vec![TokenType::Alphanumeric])?;
+        let datatype = self.parse_datatype()?;
+        self.move_position();
+        self.require_token(vec![TokenType::LBracket])?;
+        self.move_position();
+
+        let mut variant_tokens: Vec<Token> = vec![];
+        loop {
+            let variant_token =
+                self.require_token(vec![TokenType::Alphanumeric, TokenType::RBracket])?;
+            if variant_token.is_type(TokenType::RBracket) {
+                break;
+            }
+            if let Some(declared_token) = variant_tokens
+                .iter()
+                .find(|token| token.value == variant_token.value)
+            {
+                return Err(self
+                    .syntax_error(
+                        DiagnosticCode::DuplicateVariant,
+                        format!("'{}' variant is already declared", variant_token.value),
+                        variant_token.span,
+                    )
+                    .with_label(declared_token.span, "first declared here")
+                    .into());
             }
-            TokenType::Break => {
-                Ok(Box::new(LoopControlNode::new(LoopControlType::Break)))
+            variant_tokens.push(variant_token);
+            self.move_position();
+            let separator_token =
+                self.require_token(vec![TokenType::Comma, TokenType::RBracket])?;
+            if separator_token.is_type(TokenType::Comma) {
+                self.move_position();
             }
-            _ => Err(io::Error::new(
-                io::ErrorKind::Other,
+        }
+        let variants = variant_tokens
+            .into_iter()
+            .map(|token| token.value)
+            .collect();
+        let mut enum_node = EnumDeclarationNode::new(name_token.value, datatype, variants);
+        enum_node.set_doc(doc);
+        Ok(Box::new(enum_node))
+    }
+
+    // event name (&table.column, "change") { ... }
+    fn parse_event_declaration(&mut self) -> Result<Box<dyn ExpressionNode>> {
+        self.move_position();
+        let name_token = self.require_token(vec![TokenType::Alphanumeric])?;
+        self.move_position();
+        self.require_token(vec![TokenType::LPar])?;
+        self.move_position();
+        self.require_token(vec![TokenType::Link])?;
+        self.move_position();
+        let table_token = self.require_token(vec![TokenType::Alphanumeric])?;
+        let column = if self.move_if_next_token_is(vec![TokenType::Dot]) {
+            self.move_position();
+            Some(self.require_token(vec![TokenType::Alphanumeric])?.value)
+        } else {
+            None
+        };
+        self.move_position();
+        self.require_token(vec![TokenType::Comma])?;
+        self.move_position();
+        let kind_token = self.require_token(vec![TokenType::CharArray])?;
+        let change_kind = ChangeKind::from_name(&unquote(&kind_token.value)).ok_or_else(|| {
+            self.syntax_error(
+                DiagnosticCode::UnknownChangeKind,
                 format!(
-                    "{}: {} is not being considered in this context <-= at {}:{}:{}",
-                    self.syntax_error_marking,
-                    current_token.token_type,
-                    self.context.code_source,
-                    self.context.line,
-                    self.context.position
+                    "unknown change kind {}, expected \"insert\", \"update\", \"delete\" or \"change\"",
+                    kind_token.value
                 ),
-            )),
+                kind_token.span,
+            )
+        })?;
+        self.move_position();
+        self.require_token(vec![TokenType::RPar])?;
+        self.move_position();
+        Ok(Box::new(EventDeclarationNode::new(
+            name_token.value,
+            table_token.value,
+            column,
+            change_kind,
+            self.require_body()?,
+        )))
+    }
+
+    // [for] item of collection { ... }
+    fn parse_for_loop(&mut self) -> Result<Box<dyn ExpressionNode>> {
+        let item_token = self.require_token(vec![TokenType::Alphanumeric])?;
+        self.move_position();
+        self.require_token(vec![TokenType::Of])?;
+        self.move_position();
+        let collection_node = self.require_formula()?;
+        self.move_position();
+        Ok(Box::new(ForLoopNode::new(
+            item_token.value,
+            collection_node,
+            self.require_body()?,
+        )))
+    }
+
+    fn parse_column_type(&mut self) -> Result<ColumnType> {
+        let type_token = self.require_token(vec![TokenType::Alphanumeric, TokenType::Link])?;
+        if type_token.is_type(TokenType::Alphanumeric) {
+            return Ok(ColumnType::Datatype(type_token.value));
+        }
+        self.move_position();
+        let table_token = self.require_token(vec![TokenType::Alphanumeric])?;
+        let modifier = if self.move_if_next_token_is(vec![TokenType::QuestionMark]) {
+            ReferenceModifier::Optional
+        } else if self.move_if_next_token_is(vec![TokenType::Negotion]) {
+            ReferenceModifier::Unique
+        } else if self.move_if_next_token_is(vec![TokenType::LSquareBracket]) {
+            self.move_position();
+            self.require_token(vec![TokenType::RSquareBracket])?;
+            ReferenceModifier::Many
+        } else {
+            ReferenceModifier::Single
+        };
+        Ok(ColumnType::Reference(table_token.value, modifier))
+    }
+
+    fn parse_constraints(&mut self) -> Result<Vec<Constraint>> {
+        let mut constraints = vec![];
+        while self.move_if_next_token_is(CONSTRAINT_TOKENS.to_vec()) {
+            let constraint_token = self.get_current_token()?;
+            if constraint_token.is_type(TokenType::Number) {
+                constraints.push(Constraint::Exact(parse_number(&constraint_token)));
+                continue;
+            }
+            self.move_position();
+            let bound = self.require_signed_number()?;
+            constraints.push(Constraint::Bound(constraint_token.token_type, bound));
         }
+        Ok(constraints)
     }
 
+    // Datatypes can refer to tables as well, e.g. `products: &products[]`.
+    // Other types become arrays with `[]`, e.g. `items: T[]`.
     fn parse_datatype(&mut self) -> Result<Option<String>> {
-        if self.move_if_next_token_is(vec![TokenType::Colon]) {
+        if !self.move_if_next_token_is(vec![TokenType::Colon]) {
+            return Ok(None);
+        };
+        self.move_position();
+        let column_type = self.parse_column_type()?;
+        let mut datatype = column_type.to_string();
+        if matches!(column_type, ColumnType::Datatype(_))
+            && self.move_if_next_token_is(vec![TokenType::LSquareBracket])
+        {
+            self.move_position();
+            self.require_token(vec![TokenType::RSquareBracket])?;
+            datatype.push_str("[]");
+        }
+        Ok(Some(datatype))
+    }
+
+    // Parses `<T, U>` from the '<' token to the '>' one.
+    fn parse_type_names(&mut self) -> Result<Vec<Token>> {
+        let mut type_tokens = vec![];
+        loop {
             self.move_position();
-            let datatype_token = self.require_token(vec![TokenType::Alphanumeric])?;
-            return Ok(Some(datatype_token.value));
+            type_tokens.push(self.require_token(vec![TokenType::Alphanumeric])?);
+            if !self.move_if_next_token_is(vec![TokenType::Comma]) {
+                break;
+            }
+        }
+        self.move_position();
+        self.require_token(vec![TokenType::Greater])?;
+        Ok(type_tokens)
+    }
+
+    // `first<int>(numbers)` is told apart from comparisons by the '(' right after the '>'.
+    fn is_type_arguments_next(&self) -> bool {
+        let tokens = &self.tokens[self.parser_position as usize + 1..];
+        let is_type_at = |index: usize, token_type| {
+            tokens
+                .get(index)
+                .is_some_and(|token| token.is_type(token_type))
         };
-        Ok(None)
+        if !is_type_at(0, TokenType::Less) {
+            return false;
+        }
+        let mut index = 1;
+        while is_type_at(index, TokenType::Alphanumeric) {
+            if is_type_at(index + 1, TokenType::Greater) {
+                return is_type_at(index + 2, TokenType::LPar);
+            }
+            if !is_type_at(index + 1, TokenType::Comma) {
+                return false;
+            }
+            index += 2;
+        }
+        false
     }
 
     fn parse_assignment(&mut self) -> Result<Option<Box<dyn ExpressionNode>>> {
@@ -335,10 +742,12 @@ impl Parser {
         parameter_type: ParameterType,
     ) -> Result<Parameters> {
         self.require_token(vec![TokenType::LPar])?;
+        self.enter_nesting()?;
         self.move_position();
         let arguments = self.parse_parameters(parameter_type)?;
         let _ = self.move_position();
         self.require_token(vec![TokenType::RPar])?;
+        self.exit_nesting();
         Ok(arguments)
     }
 
@@ -347,19 +756,16 @@ impl Parser {
 
         let mut keyword_arguments_time: bool = false;
         let mut check_if_incorrect_argument_sequence =
-            |is_keyword_argument: bool, this: &mut Self| {
+            |is_keyword_argument: bool, this: &mut Self| -> Result<()> {
                 if !is_keyword_argument && keyword_arguments_time {
                     let current_token = this.get_current_token()?;
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!(
-                            "{}: Positional argument follows keyword argument <-= at {}:{}:{}",
-                            this.syntax_error_marking,
-                            this.context.code_source,
-                            current_token.line + 1,
-                            current_token.start + 1
-                        ),
-                    ));
+                    return Err(this
+                        .syntax_error(
+                            DiagnosticCode::PositionalAfterKeyword,
+                            "positional argument follows keyword argument",
+                            current_token.span,
+                        )
+                        .into());
                 };
                 if is_keyword_argument {
                     keyword_arguments_time = true;
@@ -383,20 +789,15 @@ impl Parser {
                         self.move_position_back();
                         break;
                     };
-                    let datatype_string = self
-                        .parse_datatype()
-                        .expect("Error occured while datatype parsing");
+                    let datatype_string = self.parse_datatype()?;
                     if datatype_string.is_none() {
-                        return Err(io::Error::new(
-                            io::ErrorKind::Other,
-                            format!(
-                                "{}: Argument type is expected <-= {}:{}:{}",
-                                self.syntax_error_marking,
-                                self.context.code_source,
-                                self.context.line,
-                                self.context.position
-                            ),
-                        ));
+                        return Err(self
+                            .syntax_error(
+                                DiagnosticCode::MissingArgumentType,
+                                "argument type is expected",
+                                first_token.span,
+                            )
+                            .into());
                     }
                     let default_value = self.parse_assignment()?;
                     check_if_incorrect_argument_sequence(default_value.is_some(), self)?;
@@ -440,32 +841,239 @@ impl Parser {
 
     fn parse_identifiers(&mut self) -> Result<Box<dyn ExpressionNode>> {
         let object_token = self.get_current_token()?;
-        let mut object_node: Box<dyn ExpressionNode> =
-            Box::new(IdentifierNode::new(object_token.value));
+        let mut identifier = IdentifierNode::new(object_token.value.clone());
+        identifier.set_span(object_token.span);
+        if self.is_type_arguments_next() {
+            self.move_position();
+            let types = self.parse_type_names()?.into_iter().map(|type_token| {
+                let mut type_identifier = IdentifierNode::new(type_token.value);
+                type_identifier.set_span(type_token.span);
+                type_identifier
+            });
+            identifier.set_types(types.collect());
+        }
+        let mut object_node: Box<dyn ExpressionNode> = Box::new(identifier);
+        // Calls of types are told apart by the resolver, which knows every table.
         if self.move_if_next_token_is(vec![TokenType::LPar]) {
             let arguments = self.parse_parameters_in_parenthesis(ParameterType::Call)?;
-            object_node = Box::new(CallNode::new(object_node, arguments));
+            let mut call_node = CallNode::new(object_node, arguments);
+            call_node.set_span(join_spans(&object_token, &self.get_current_token()?));
+            object_node = Box::new(call_node);
         };
-        if self.move_if_next_token_is(vec![TokenType::Dot]) {
-            if !self.move_if_position_is_movable() {
-                return Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!(
-                        "{}: Child call expected <-= at {}:{}:{}",
-                        self.syntax_error_marking,
-                        self.context.code_source,
-                        self.context.line,
-                        self.context.position + 1
-                    ),
-                ));
-            };
-            let next_member = self.parse_identifiers()?;
-            object_node = Box::new(MemberNode::new(object_node, next_member));
+        self.parse_postfix_calls(object_node, &object_token)
+    }
+
+    // Positions and members can follow any operand, like `[1, 2][0]` and `{a: 1}.a`.
+    fn parse_postfix_calls(
+        &mut self,
+        object_node: Box<dyn ExpressionNode>,
+        first_token: &Token,
+    ) -> Result<Box<dyn ExpressionNode>> {
+        let object_node = self.parse_position_calls(object_node, first_token)?;
+        if !self.move_if_next_token_is(vec![TokenType::Dot]) {
+            return Ok(object_node);
+        }
+        if !self.move_if_position_is_movable() {
+            let dot_token = self.get_current_token()?;
+            return Err(self
+                .syntax_error(
+                    DiagnosticCode::MissingMember,
+                    "child call expected",
+                    dot_token.span,
+                )
+                .into());
         };
+        let next_member = self.parse_identifiers()?;
+        Ok(Box::new(MemberNode::new(object_node, next_member)))
+    }
+
+    fn parse_position_calls(
+        &mut self,
+        mut object_node: Box<dyn ExpressionNode>,
+        first_token: &Token,
+    ) -> Result<Box<dyn ExpressionNode>> {
+        while self.move_if_next_token_is(vec![TokenType::LSquareBracket]) {
+            self.enter_nesting()?;
+            let mut call_node = self.parse_position_call(object_node)?;
+            call_node.set_span(join_spans(first_token, &self.get_current_token()?));
+            object_node = Box::new(call_node);
+            self.exit_nesting();
+        }
         Ok(object_node)
     }
 
+    // `items[1]` gets an item, while `items[1:3]` gets a slice with either bound optional.
+    fn parse_position_call(&mut self, callee: Box<dyn ExpressionNode>) -> Result<CallNode> {
+        self.move_position();
+        let start = self.parse_formula()?;
+        if start.is_some() {
+            self.move_position();
+        }
+        let arguments = match self.get_current_token()?.is_type(TokenType::Colon) {
+            true => {
+                self.move_position();
+                let stop = self.parse_formula()?;
+                if stop.is_some() {
+                    self.move_position();
+                }
+                vec![
+                    Parameter::new("start".to_string(), None, start),
+                    Parameter::new("stop".to_string(), None, stop),
+                ]
+            }
+            false => {
+                let Some(index) = start else {
+                    return Err(self.unexpected_tokens_error(FORMULA_TOKENS.to_vec()).into());
+                };
+                vec![Parameter::new_calling(None, index)]
+            }
+        };
+        self.require_token(vec![TokenType::RSquareBracket])?;
+        let mut call_node = CallNode::new(callee, arguments);
+        call_node.set_call_type(CallType::Position);
+        Ok(call_node)
+    }
+
+    // `[1, 2, 3]`, where a comma may follow the last item.
+    fn parse_array(&mut self) -> Result<ArrayNode> {
+        let mut items = vec![];
+        self.move_position();
+        while let Some(item) = self.parse_formula()? {
+            items.push(item);
+            self.move_position();
+            if !self.get_current_token()?.is_type(TokenType::Comma) {
+                break;
+            }
+            self.move_position();
+        }
+        self.require_token(vec![TokenType::RSquareBracket])?;
+        Ok(ArrayNode::new(items))
+    }
+
+    // Bodies never start with a key and a colon, so `{ name: "x" }` is a document
+    // while `{ name }` is a body. `{:}` is the empty document, as `{}` is the empty body.
+    fn is_document_next(&self) -> bool {
+        let position = self.parser_position as usize;
+        let is_type_at = |index: usize, token_types: &[TokenType]| {
+            self.tokens
+                .get(position + index)
+                .is_some_and(|token| token_types.contains(&token.token_type))
+        };
+        match is_type_at(1, &[TokenType::Colon]) {
+            true => is_type_at(2, &[TokenType::RBracket]),
+            false => {
+                is_type_at(1, &[TokenType::Alphanumeric, TokenType::CharArray])
+                    && is_type_at(2, &[TokenType::Colon])
+            }
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<DocumentNode> {
+        let mut fields: DocumentFields = vec![];
+        let mut key_spans: Vec<Span> = vec![];
+        self.move_position();
+        if self.get_current_token()?.is_type(TokenType::Colon) {
+            self.move_position();
+            self.require_token(vec![TokenType::RBracket])?;
+            return Ok(DocumentNode::new(fields));
+        }
+        loop {
+            let key_token = self.require_token(vec![
+                TokenType::Alphanumeric,
+                TokenType::CharArray,
+                TokenType::RBracket,
+            ])?;
+            if key_token.is_type(TokenType::RBracket) {
+                break;
+            }
+            let key = match key_token.is_type(TokenType::CharArray) {
+                true => unquote(&key_token.value),
+                false => key_token.value.clone(),
+            };
+            if let Some(index) = fields.iter().position(|(field_key, _)| *field_key == key) {
+                return Err(self
+                    .syntax_error(
+                        DiagnosticCode::DuplicateField,
+                        format!("'{}' field is already written", key),
+                        key_token.span,
+                    )
+                    .with_label(key_spans[index], "first written here")
+                    .into());
+            }
+            key_spans.push(key_token.span);
+            self.move_position();
+            self.require_token(vec![TokenType::Colon])?;
+            self.move_position();
+            fields.push((key, self.require_formula()?));
+            self.move_position();
+            let separator_token =
+                self.require_token(vec![TokenType::Comma, TokenType::RBracket])?;
+            if separator_token.is_type(TokenType::RBracket) {
+                break;
+            }
+            self.move_position();
+        }
+        Ok(DocumentNode::new(fields))
+    }
+
     fn parse_formula(&mut self) -> Result<Option<Box<dyn ExpressionNode>>> {
+        let first_token = self.get_current_token();
+        let Some(left_operand) = self.parse_operand()? else {
+            return Ok(None);
+        };
+        Ok(Some(self.parse_binary_operations(
+            left_operand,
+            &first_token?,
+            0,
+        )?))
+    }
+
+    // Precedence climbing: operators binding at least as tight as the minimal precedence
+    // are folded into the left operand, while tighter ones on the right are parsed recursively.
+    // The first token is where the left operand starts, so the operations span from it.
+    fn parse_binary_operations(
+        &mut self,
+        mut left_operand: Box<dyn ExpressionNode>,
+        first_token: &Token,
+        minimal_precedence: u8,
+    ) -> Result<Box<dyn ExpressionNode>> {
+        while self.move_if_next_token_is(BINARY_OPERATOR_TOKENS.to_vec()) {
+            let operator = self.get_current_token()?;
+            let precedence = operator.token_type.get_precedence();
+            if precedence < minimal_precedence {
+                self.move_position_back();
+                break;
+            }
+            self.move_position();
+            let right_token = self.get_current_token();
+            let right_operand = self.require_operand()?;
+            let right_minimal_precedence = if operator.token_type.is_right_associative() {
+                precedence
+            } else {
+                precedence + 1
+            };
+            // Only right-associative operators can follow each other without returning.
+            let is_nested = operator.token_type.is_right_associative();
+            if is_nested {
+                self.enter_nesting()?;
+            }
+            let right_operand = self.parse_binary_operations(
+                right_operand,
+                &right_token?,
+                right_minimal_precedence,
+            )?;
+            if is_nested {
+                self.exit_nesting();
+            }
+            let mut operator_node =
+                BinaryOperatorNode::new(operator.token_type, left_operand, right_operand);
+            operator_node.set_span(join_spans(first_token, &self.get_current_token()?));
+            left_operand = Box::new(operator_node);
+        }
+        Ok(left_operand)
+    }
+
+    fn parse_operand(&mut self) -> Result<Option<Box<dyn ExpressionNode>>> {
         let mut unary_operator_tokens: Vec<Token> = vec![];
         let mut prohibited_unary_operator_types: Vec<TokenType> = vec![];
 
@@ -475,17 +1083,11 @@ impl Parser {
                     .into_iter()
                     .any(|x| token_to_check.is_type(x))
                 {
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!(
-                            "{}: '{}' operator is already used <-= at {}:{}:{}",
-                            this.syntax_error_marking,
-                            token_to_check.token_type,
-                            this.context.code_source,
-                            token_to_check.line,
-                            token_to_check.start
-                        ),
-                    ));
+                    return Err(Box::new(this.syntax_error(
+                        DiagnosticCode::RepeatedUnaryOperator,
+                        format!("'{}' operator is already used", token_to_check.token_type),
+                        token_to_check.span,
+                    )));
                 }
                 Ok(())
             };
@@ -496,8 +1098,13 @@ impl Parser {
             return Ok(None);
         };
         loop {
-            if UNARY_OPERATOR_TOKENS.contains(&self.get_current_token()?.token_type) {
+            let current_token_type = self.get_current_token()?.token_type;
+            // The sign binds looser than the other unary operators, so it's parsed as an operand.
+            if current_token_type != TokenType::Subtraction
+                && UNARY_OPERATOR_TOKENS.contains(&current_token_type)
+            {
                 let current_unary_operator_token = self.get_current_token()?;
+                self.enter_nesting()?;
                 unary_operator_tokens.push(current_unary_operator_token.clone());
                 is_unary_operator_prohibited(
                     current_unary_operator_token.clone(),
@@ -519,13 +1126,20 @@ impl Parser {
             break;
         }
         let formula_token = self.get_current_token()?;
+        // Identifiers take their positions along with their members.
+        let is_identifier = formula_token.is_type(TokenType::Alphanumeric);
         let mut left_operand: Box<dyn ExpressionNode> = match formula_token.token_type {
             TokenType::Alphanumeric => self.parse_identifiers()?,
-            TokenType::CharArray => Box::new(StringNode::new(formula_token.value)),
-            TokenType::Number => Box::new(NumberNode::new(formula_token.value.parse().unwrap())),
+            TokenType::CharArray => Box::new(StringNode::new(unquote(&formula_token.value))),
+            TokenType::Number => {
+                let mut number_node = NumberNode::new(parse_number(&formula_token));
+                number_node.set_span(formula_token.span);
+                Box::new(number_node)
+            }
+            TokenType::Subtraction => self.parse_negation()?,
             TokenType::Null => Box::new(NullNode),
             TokenType::True | TokenType::False => {
-                Box::new(BooleanNode::new(formula_token.token_type)?)
+                Box::new(BooleanNode::new(formula_token.token_type.clone()).unwrap())
             }
             TokenType::If => {
                 let mut conditions: Conditions = vec![];
@@ -555,36 +1169,111 @@ impl Parser {
 
                 Box::new(ConditionalTreeNode::new(conditions, default_node))
             }
+            TokenType::LBracket if self.is_document_next() => {
+                self.enter_nesting()?;
+                let document_node = self.parse_document()?;
+                self.exit_nesting();
+                Box::new(document_node)
+            }
             TokenType::LBracket => {
                 let body_node = self.require_body()?;
                 Box::new(body_node)
             }
+            TokenType::LSquareBracket => {
+                self.enter_nesting()?;
+                let array_node = self.parse_array()?;
+                self.exit_nesting();
+                Box::new(array_node)
+            }
+            TokenType::LPar => {
+                self.enter_nesting()?;
+                self.move_position();
+                let expression_node = self.require_formula()?;
+                self.move_position();
+                let closing_token = self.require_token(vec![TokenType::RPar])?;
+                self.exit_nesting();
+                let mut parenthesized_node = ParenthesizedNode::new(expression_node);
+                parenthesized_node.set_span(join_spans(&formula_token, &closing_token));
+                Box::new(parenthesized_node)
+            }
             _ => {
                 self.raise_unexpected_tokens_error(FORMULA_TOKENS.to_vec())?;
                 Box::new(NullNode {})
             }
         };
-        for unary_operator_token in unary_operator_tokens {
+        if !is_identifier {
+            left_operand = self.parse_postfix_calls(left_operand, &formula_token)?;
+        }
+        // The operator closest to the operand is applied first.
+        for unary_operator_token in unary_operator_tokens.into_iter().rev() {
+            self.exit_nesting();
             left_operand = Box::new(UnaryOperatorNode::new(
                 unary_operator_token.token_type,
                 left_operand,
                 TokenSide::Left,
             ));
         }
-        if self.move_if_next_token_is(BINARY_OPERATOR_TOKENS.to_vec()) {
-            let operator = self.get_current_token()?;
-            self.move_position();
-            let right_operand = self.require_formula()?;
-            let binary_operator_node = Box::new(BinaryOperatorNode::new(
-                operator.token_type,
-                left_operand,
-                right_operand,
-            ));
-            return Ok(Some(binary_operator_node));
-        };
         Ok(Some(left_operand))
     }
 
+    // The sign binds looser than '^', so `-2 ^ 2` is `-(2 ^ 2)`. It's folded into
+    // number literals, so `-1` and `- -1` are still numbers.
+    fn parse_negation(&mut self) -> Result<Box<dyn ExpressionNode>> {
+        let sign_token = self.move_position();
+        self.enter_nesting()?;
+        let operand_token = self.get_current_token()?;
+        let operand = self.require_operand()?;
+        let operand =
+            self.parse_binary_operations(operand, &operand_token, TokenType::Hat.get_precedence())?;
+        self.exit_nesting();
+        if let Some(number_node) = operand.downcast_ref::<NumberNode>() {
+            let span = join_spans(&sign_token, &self.get_current_token()?);
+            let mut number_node =
+                NumberNode::new(self.negate_number(number_node.get_value(), span)?);
+            number_node.set_span(span);
+            return Ok(Box::new(number_node));
+        }
+        Ok(Box::new(UnaryOperatorNode::new(
+            TokenType::Subtraction,
+            operand,
+            TokenSide::Left,
+        )))
+    }
+
+    // Bounds of constraints can be negative, e.g. `>= -1`.
+    fn require_signed_number(&mut self) -> Result<Number> {
+        let sign_token = self.get_current_token()?;
+        if !sign_token.is_type(TokenType::Subtraction) {
+            let number_token = self.require_token(vec![TokenType::Number])?;
+            return Ok(parse_number(&number_token));
+        }
+        self.move_position();
+        let number_token = self.require_token(vec![TokenType::Number])?;
+        self.negate_number(
+            parse_number(&number_token),
+            join_spans(&sign_token, &number_token),
+        )
+    }
+
+    fn negate_number(&self, number: Number, span: Span) -> Result<Number> {
+        number.checked_neg().ok_or_else(|| {
+            self.syntax_error(
+                DiagnosticCode::NumberOutOfRange,
+                format!("-{} is out of range of 'int'", number),
+                span,
+            )
+            .into()
+        })
+    }
+
+    fn require_operand(&mut self) -> Result<Box<dyn ExpressionNode>> {
+        let operand_node = self.parse_operand()?;
+        if operand_node.is_none() {
+            self.raise_unexpected_tokens_error(FORMULA_TOKENS.to_vec())?;
+        }
+        Ok(operand_node.unwrap())
+    }
+
     fn require_formula(&mut self) -> Result<Box<dyn ExpressionNode>> {
         let formula_node = self.parse_formula()?;
         if formula_node.is_none() {
@@ -593,3 +1282,68 @@ impl Parser {
         Ok(formula_node.unwrap())
     }
 }
+
+// Types written differently every time, like numbers, are called by their names.
+fn get_token_type_text(token_type: &TokenType) -> String {
+    match token_type.get_text() {
+        Some(text) => text.to_string(),
+        None => token_type.to_string(),
+    }
+}
+
+// The span from the start of the first token to the end of the last one.
+fn join_spans(first_token: &Token, last_token: &Token) -> Span {
+    Span::new(
+        first_token.span.start,
+        last_token.span.stop,
+        first_token.span.line,
+        first_token.span.column,
+    )
+}
+
+// Doc comments are only kept above the declarations that can be documented,
+// any other ones are regular comments.
+fn keep_declaration_docs(tokens: Vec<Token>) -> Vec<Token> {
+    let mut kept_tokens: Vec<Token> = Vec::with_capacity(tokens.len());
+    for (index, token) in tokens.iter().enumerate() {
+        if token.is_type(TokenType::DocComment) {
+            let documented_token = tokens[index..]
+                .iter()
+                .find(|token| !token.is_type(TokenType::DocComment));
+            let is_declaration = documented_token.is_some_and(|token| {
+                [TokenType::Table, TokenType::Enum, TokenType::Function].contains(&token.token_type)
+            });
+            if !is_declaration {
+                continue;
+            }
+        }
+        kept_tokens.push(token.clone());
+    }
+    kept_tokens
+}
+
+// The lexer has already reported the numbers that are out of range.
+fn parse_number(number_token: &Token) -> Number {
+    Number::parse_literal(&number_token.value).unwrap()
+}
+
+// Strips the surrounding quotes of a string literal and resolves its escape sequences.
+fn unquote(literal: &str) -> String {
+    let content = &literal[1..literal.len() - 1];
+    let mut unquoted = String::with_capacity(content.len());
+    let mut chars = content.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            unquoted.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unquoted.push('\n'),
+            Some('t') => unquoted.push('\t'),
+            Some('r') => unquoted.push('\r'),
+            Some(escaped) => unquoted.push(escaped),
+            None => unquoted.push('\\'),
+        }
+    }
+    unquoted
+}
@@ -0,0 +1,487 @@
+use std::collections::{HashMap, HashSet};
+
+use super::ast::binary_operator::BinaryOperatorNode;
+use super::ast::body::BodyNode;
+use super::ast::call::{CallNode, CallType, BUILTIN_DATATYPES};
+use super::ast::enum_declaration::EnumDeclarationNode;
+use super::ast::event_declaration::EventDeclarationNode;
+use super::ast::expression::ExpressionNode;
+use super::ast::for_loop::ForLoopNode;
+use super::ast::function_declaration::FunctionDeclarationNode;
+use super::ast::identifier::IdentifierNode;
+use super::ast::import::ImportNode;
+use super::ast::inspect::InspectNode;
+use super::ast::loop_control::LoopControlNode;
+use super::ast::member::MemberNode;
+use super::ast::table_declaration::TableDeclarationNode;
+use super::ast::unary_operator::UnaryOperatorNode;
+use super::ast::variable_declaration::VariableDeclaration;
+use super::ast::visitor::{walk_call_mut, walk_parameter, Visitor, VisitorMut};
+use super::ast::while_loop::WhileLoopNode;
+use super::diagnostic::{Diagnostic, DiagnosticCode, Diagnostics};
+use super::loader::{Symbol, SymbolKind};
+use super::span::Span;
+use super::tokens::TokenType;
+
+#[derive(Clone, Copy)]
+struct Binding {
+    span: Span,
+    is_mutable: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum NameKind {
+    Variable,
+    Function,
+    Enum,
+}
+
+impl NameKind {
+    fn get_name(&self) -> &'static str {
+        match self {
+            NameKind::Variable => "variable",
+            NameKind::Function => "function",
+            NameKind::Enum => "enum",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Scope {
+    // Names by their kinds, like the executor keeps them apart.
+    names: HashMap<(NameKind, String), Binding>,
+    // Everything the body of the scope declares, including what isn't declared yet.
+    later_names: HashMap<(NameKind, String), Binding>,
+    // Function and event bodies are run after the scopes around them have been filled.
+    is_boundary: bool,
+}
+
+// Checks that names refer to declarations the executor will find, and that
+// immutable bindings and loop controls are used where they are allowed.
+pub struct Resolver {
+    code_source: String,
+    scopes: Vec<Scope>,
+    // Imported symbols, or none of them when a whole package is imported.
+    imported_names: Option<HashSet<String>>,
+    // Tables are global, so calling them builds rows wherever they are declared.
+    table_names: TableNames,
+    loop_depth: usize,
+    statement_span: Span,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Resolver {
+    pub fn new(code_source: &str) -> Self {
+        Resolver {
+            code_source: code_source.to_string(),
+            scopes: vec![Scope::default()],
+            imported_names: Some(HashSet::new()),
+            table_names: TableNames::default(),
+            loop_depth: 0,
+            statement_span: Span::new(0, 0, 1, 1),
+            diagnostics: vec![],
+        }
+    }
+
+    // The symbols other modules declare, which the package loader finds for the imports.
+    pub fn import_symbols(&mut self, symbols: &[Symbol]) {
+        for symbol in symbols {
+            let name = symbol.get_name().to_string();
+            if *symbol.get_kind() == SymbolKind::Table {
+                self.table_names.0.insert(name.clone());
+            }
+            if let Some(imported_names) = &mut self.imported_names {
+                imported_names.insert(name);
+            }
+        }
+    }
+
+    // Calls of types are marked in the body, then the names are checked.
+    // Warnings are returned along with errors, so callers can tell them apart.
+    pub fn resolve(&mut self, body: &mut BodyNode) -> Diagnostics {
+        self.table_names.visit_body(body);
+        self.table_names.visit_body_mut(body);
+        self.resolve_statements(body);
+        Diagnostics(std::mem::take(&mut self.diagnostics))
+    }
+
+    fn resolve_statements(&mut self, body: &BodyNode) {
+        let statement_span = self.statement_span;
+        let scope = self.scopes.last_mut().unwrap();
+        for (index, node) in body.nodes.iter().enumerate() {
+            let Some((kind, name, span, is_mutable)) = get_declared_name(node.as_ref()) else {
+                continue;
+            };
+            let span = span.or(body.get_node_span(index)).unwrap_or(statement_span);
+            let binding = Binding { span, is_mutable };
+            scope.later_names.insert((kind, name.to_string()), binding);
+        }
+
+        for (index, statement) in body.nodes.iter().enumerate() {
+            if let Some(span) = body.get_node_span(index) {
+                self.statement_span = span;
+            }
+            self.visit_expression(statement.as_ref());
+        }
+        self.statement_span = statement_span;
+    }
+
+    // Parameters, loop items and event rows are declared with the scope.
+    fn in_scope(
+        &mut self,
+        bindings: Vec<(&str, Span)>,
+        is_boundary: bool,
+        resolve: impl FnOnce(&mut Self),
+    ) {
+        self.scopes.push(Scope {
+            is_boundary,
+            ..Default::default()
+        });
+        for (name, span) in bindings {
+            self.declare(NameKind::Variable, name, span, false);
+        }
+        let loop_depth = self.loop_depth;
+        if is_boundary {
+            self.loop_depth = 0;
+        }
+        resolve(self);
+        self.loop_depth = loop_depth;
+        self.scopes.pop();
+    }
+
+    // Bodies of functions and events see everything declared around them,
+    // because they can only run once the surrounding code has been executed.
+    fn find(&self, kind: NameKind, name: &str) -> Option<Binding> {
+        let key = (kind, name.to_string());
+        let mut is_deferred = false;
+        for scope in self.scopes.iter().rev() {
+            if let Some(binding) = scope.names.get(&key) {
+                return Some(*binding);
+            }
+            if is_deferred {
+                if let Some(binding) = scope.later_names.get(&key) {
+                    return Some(*binding);
+                }
+            }
+            is_deferred |= scope.is_boundary;
+        }
+        None
+    }
+
+    fn declare(&mut self, kind: NameKind, name: &str, span: Span, is_mutable: bool) {
+        let key = (kind, name.to_string());
+        if let Some(previous) = self.scopes.last().unwrap().names.get(&key) {
+            let previous_span = previous.span;
+            let diagnostic = Diagnostic::error(
+                DiagnosticCode::DuplicateDeclaration,
+                format!(
+                    "{} '{}' is already declared in this scope",
+                    kind.get_name(),
+                    name
+                ),
+                &self.code_source,
+                span,
+            )
+            .with_span_label("declared again")
+            .with_label(previous_span, format!("'{}' is first declared here", name));
+            self.diagnostics.push(diagnostic);
+            return;
+        }
+        let scope = self.scopes.last_mut().unwrap();
+        scope.names.insert(key, Binding { span, is_mutable });
+    }
+
+    // Parameters and loop items aren't warned about, as they are expected to reuse common names.
+    fn check_shadowing(&mut self, name: &str, span: Span) {
+        let key = (NameKind::Variable, name.to_string());
+        if self.scopes.last().unwrap().names.contains_key(&key) {
+            return;
+        }
+        let Some(outer) = self.find(NameKind::Variable, name) else {
+            return;
+        };
+        let diagnostic = Diagnostic::warning(
+            DiagnosticCode::ShadowedName,
+            format!("'{}' shadows a variable of an outer scope", name),
+            &self.code_source,
+            span,
+        )
+        .with_label(outer.span, format!("'{}' is declared here", name));
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn is_imported(&self, name: &str) -> bool {
+        self.imported_names
+            .as_ref()
+            .is_none_or(|imported_names| imported_names.contains(name))
+    }
+
+    fn resolve_name(&mut self, kinds: &[NameKind], name: &str, span: Span) -> Option<Binding> {
+        let binding = kinds.iter().find_map(|kind| self.find(*kind, name));
+        if binding.is_none() && !self.is_imported(name) {
+            let message = match kinds {
+                [NameKind::Function] => format!("function '{}' is not defined", name),
+                _ => format!("'{}' is not defined", name),
+            };
+            let diagnostic = Diagnostic::error(
+                DiagnosticCode::UndefinedName,
+                message,
+                &self.code_source,
+                span,
+            )
+            .with_span_label("not found in this scope");
+            self.diagnostics.push(diagnostic);
+        }
+        binding
+    }
+
+    fn resolve_assignment(&mut self, target: &dyn ExpressionNode) {
+        // Items are assigned along with what holds them, so `a[0] = 5` assigns `a`.
+        let item_call = target.downcast_ref::<CallNode>().filter(|call| {
+            *call.get_call_type() == CallType::Position && call.get_slice_bounds().is_none()
+        });
+        if let Some(call) = item_call {
+            for argument in call.get_arguments() {
+                if let Some(value) = argument.get_value() {
+                    self.visit_expression(value);
+                }
+            }
+            self.resolve_assignment(call.get_callee());
+            return;
+        }
+        let Some(identifier) = target.downcast_ref::<IdentifierNode>() else {
+            // Columns are assigned through links, like `&carts.owner = "x"`.
+            let is_link = target
+                .downcast_ref::<UnaryOperatorNode>()
+                .is_some_and(|operator_node| *operator_node.get_operator() == TokenType::Link);
+            if !is_link {
+                let diagnostic = Diagnostic::error(
+                    DiagnosticCode::InvalidAssignment,
+                    "only variables and their items can be assigned to",
+                    &self.code_source,
+                    self.statement_span,
+                )
+                .with_span_label("cannot be assigned to");
+                self.diagnostics.push(diagnostic);
+            }
+            self.visit_expression(target);
+            return;
+        };
+        let name = identifier.get_name();
+        let span = identifier.get_span().unwrap_or(self.statement_span);
+        let Some(binding) = self.resolve_name(&[NameKind::Variable], name, span) else {
+            return;
+        };
+        if !binding.is_mutable {
+            let diagnostic = Diagnostic::error(
+                DiagnosticCode::ImmutableAssignment,
+                format!("cannot assign twice to immutable variable '{}'", name),
+                &self.code_source,
+                span,
+            )
+            .with_span_label("cannot assign twice")
+            .with_label(binding.span, format!("'{}' is declared here", name));
+            self.diagnostics.push(diagnostic);
+        }
+    }
+}
+
+#[derive(Default)]
+struct TableNames(HashSet<String>);
+
+impl Visitor for TableNames {
+    fn visit_table_declaration(&mut self, node: &TableDeclarationNode) {
+        self.0.insert(node.get_name().to_string());
+    }
+}
+
+// Built-in types cast values and tables build rows, like `int(x)` and `accounts(name = "x")`.
+impl VisitorMut for TableNames {
+    fn visit_call_mut(&mut self, node: &mut CallNode) {
+        let is_type = node
+            .get_callee()
+            .downcast_ref::<IdentifierNode>()
+            .is_some_and(|identifier| {
+                let name = identifier.get_name();
+                BUILTIN_DATATYPES.contains(&name) || self.0.contains(name)
+            });
+        if is_type && *node.get_call_type() == CallType::Function {
+            node.set_call_type(CallType::Type);
+        }
+        walk_call_mut(self, node);
+    }
+}
+
+fn get_declared_name(node: &dyn ExpressionNode) -> Option<(NameKind, &str, Option<Span>, bool)> {
+    if let Some(declaration) = node.downcast_ref::<VariableDeclaration>() {
+        Some((
+            NameKind::Variable,
+            declaration.get_name(),
+            declaration.get_span(),
+            declaration.is_mutable(),
+        ))
+    } else if let Some(declaration) = node.downcast_ref::<FunctionDeclarationNode>() {
+        Some((
+            NameKind::Function,
+            declaration.get_name(),
+            declaration.get_span(),
+            false,
+        ))
+    } else {
+        node.downcast_ref::<EnumDeclarationNode>()
+            .map(|declaration| (NameKind::Enum, declaration.get_name(), None, false))
+    }
+}
+
+impl Visitor for Resolver {
+    fn visit_binary_operator(&mut self, node: &BinaryOperatorNode) {
+        // The assigned value is evaluated first.
+        self.visit_expression(node.get_right_operand());
+        match node.get_operator().is_assignment() {
+            true => self.resolve_assignment(node.get_left_operand()),
+            false => self.visit_expression(node.get_left_operand()),
+        }
+    }
+
+    fn visit_body(&mut self, node: &BodyNode) {
+        self.in_scope(vec![], false, |this| this.resolve_statements(node));
+    }
+
+    fn visit_call(&mut self, node: &CallNode) {
+        let callee = node.get_callee();
+        match callee.downcast_ref::<IdentifierNode>() {
+            // Types are named by built-in names or global tables.
+            Some(_) if *node.get_call_type() == CallType::Type => {}
+            Some(identifier) if *node.get_call_type() == CallType::Function => {
+                let span = identifier.get_span().unwrap_or(self.statement_span);
+                self.resolve_name(&[NameKind::Function], identifier.get_name(), span);
+            }
+            _ => self.visit_expression(callee),
+        }
+        // Keyword arguments are named by the parameters, so only their values are resolved.
+        for argument in node.get_arguments() {
+            if let Some(value) = argument.get_value() {
+                self.visit_expression(value);
+            }
+        }
+    }
+
+    fn visit_enum_declaration(&mut self, node: &EnumDeclarationNode) {
+        self.declare(NameKind::Enum, node.get_name(), self.statement_span, false);
+    }
+
+    fn visit_event_declaration(&mut self, node: &EventDeclarationNode) {
+        let span = self.statement_span;
+        self.in_scope(vec![("old", span), ("new", span)], true, |this| {
+            this.resolve_statements(node.get_body())
+        });
+    }
+
+    fn visit_for_loop(&mut self, node: &ForLoopNode) {
+        self.visit_expression(node.get_collection());
+        let item = (node.get_item(), self.statement_span);
+        self.loop_depth += 1;
+        self.in_scope(vec![item], false, |this| this.visit_body(node.get_body()));
+        self.loop_depth -= 1;
+    }
+
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationNode) {
+        let span = node.get_span().unwrap_or(self.statement_span);
+        // Declared before the body is resolved, so the function can call itself.
+        self.declare(NameKind::Function, node.get_name(), span, false);
+        self.in_scope(vec![], true, |this| {
+            for parameter in node.get_arguments() {
+                walk_parameter(this, parameter);
+                this.declare(NameKind::Variable, parameter.get_name(), span, false);
+            }
+            if let Some(body) = node.get_body() {
+                this.resolve_statements(body);
+            }
+        });
+    }
+
+    fn visit_identifier(&mut self, node: &IdentifierNode) {
+        let span = node.get_span().unwrap_or(self.statement_span);
+        self.resolve_name(&[NameKind::Variable], node.get_name(), span);
+    }
+
+    fn visit_import(&mut self, node: &ImportNode) {
+        for import in node.get_imports() {
+            match import.is_wildcard() {
+                true => self.imported_names = None,
+                false => {
+                    if let Some(imported_names) = &mut self.imported_names {
+                        imported_names.insert(import.get_symbol().to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    // Older manage.blz files inspect their packages, which isn't needed anymore.
+    fn visit_inspect(&mut self, node: &InspectNode) {
+        let span = node.get_span().unwrap_or(self.statement_span);
+        let diagnostic = Diagnostic::warning(
+            DiagnosticCode::DeprecatedStatement,
+            "'inspect' is deprecated and has no effect",
+            &self.code_source,
+            span,
+        )
+        .with_span_label("can be removed");
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn visit_loop_control(&mut self, node: &LoopControlNode) {
+        if self.loop_depth == 0 {
+            let span = node.get_span().unwrap_or(self.statement_span);
+            let diagnostic = Diagnostic::error(
+                DiagnosticCode::StrayLoopControl,
+                "loop control is used outside of a loop",
+                &self.code_source,
+                span,
+            )
+            .with_span_label("not inside a loop");
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    // Only the parent is a name, the rest are variants, fields or columns.
+    fn visit_member(&mut self, node: &MemberNode) {
+        let parent = node.get_parent();
+        match parent.downcast_ref::<IdentifierNode>() {
+            Some(identifier) => {
+                let span = identifier.get_span().unwrap_or(self.statement_span);
+                let kinds = [NameKind::Variable, NameKind::Enum];
+                self.resolve_name(&kinds, identifier.get_name(), span);
+            }
+            None => self.visit_expression(parent),
+        }
+    }
+
+    fn visit_unary_operator(&mut self, node: &UnaryOperatorNode) {
+        match node.get_operator() {
+            TokenType::Increment | TokenType::Decrement => {
+                self.resolve_assignment(node.get_operand())
+            }
+            // Links refer to tables, which are global.
+            TokenType::Link => {}
+            _ => self.visit_expression(node.get_operand()),
+        }
+    }
+
+    fn visit_variable_declaration(&mut self, node: &VariableDeclaration) {
+        if let Some(value) = node.get_value() {
+            self.visit_expression(value);
+        }
+        let span = node.get_span().unwrap_or(self.statement_span);
+        self.check_shadowing(node.get_name(), span);
+        self.declare(NameKind::Variable, node.get_name(), span, node.is_mutable());
+    }
+
+    fn visit_while_loop(&mut self, node: &WhileLoopNode) {
+        self.visit_expression(node.get_condition());
+        self.loop_depth += 1;
+        self.visit_body(node.get_body());
+        self.loop_depth -= 1;
+    }
+}
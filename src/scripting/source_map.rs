@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+
+// The code of every file or input by its code source, so diagnostics
+// can show the lines they point at.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    sources: HashMap<String, String>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        SourceMap::default()
+    }
+
+    pub fn add(&mut self, code_source: impl Into<String>, code: impl Into<String>) {
+        self.sources.insert(code_source.into(), code.into());
+    }
+
+    pub fn get(&self, code_source: &str) -> Option<&str> {
+        self.sources.get(code_source).map(String::as_str)
+    }
+}
@@ -0,0 +1,33 @@
+// The place of a piece of code: a range of bytes in the source,
+// and the line and column it starts at. Lines and columns start from 1,
+// and columns are counted in characters rather than in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub stop: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, stop: usize, line: usize, column: usize) -> Self {
+        Span {
+            start,
+            stop,
+            line,
+            column,
+        }
+    }
+}
+
+// The span of an AST node, which is only known when the node comes from the parser.
+// Nodes are compared by what they contain, so the same code written in different
+// places gives equal nodes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NodeSpan(pub Option<Span>);
+
+impl PartialEq for NodeSpan {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
@@ -1,11 +1,11 @@
 use strum_macros::{Display, EnumIter};
 
+use super::span::Span;
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct Token {
     pub token_type: TokenType,
-    pub start: u64,
-    pub stop: u64,
-    pub line: u64,
+    pub span: Span,
     pub value: String,
 }
 
@@ -15,15 +15,18 @@ impl Token {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum TokenSide {
     Left,
     Right,
 }
 
-// It's necessary to put tokens that structure more longer ones
-// below in order to make sure the lexer recognizes tokens properly.
 #[derive(Debug, EnumIter, Display, Clone, PartialEq)]
 pub enum TokenType {
+    // Comments
+    DocComment,
+    Comment,
+    Package,
     Import,
     Manage,
     Attach,
@@ -33,14 +36,19 @@ pub enum TokenType {
     Elif,
     Else,
     While,
+    For,
+    Of,
     Continue,
     Break,
     Return,
     // Unary Operators
-    Negotion,
-    Link,
     Increment,
     Decrement,
+    // Compound Assignment
+    AddAssign,
+    SubtractAssign,
+    MultiplyAssign,
+    DivideAssign,
     // Binary Operators
     Addition,
     Subtraction,
@@ -48,19 +56,24 @@ pub enum TokenType {
     Division,
     EqualSign,
     NotEqualSign,
-    Greater,
-    Less,
     GreaterOrEqual,
     LessOrEqual,
+    Greater,
+    Less,
     Hat,
     And,
     Or,
+    // Unary Operators structuring the binary ones
+    Negotion,
+    Link,
     // Assignment
     Assign,
     Mut,
     Fin,
     Function,
     Enum,
+    Table,
+    Event,
     // Brackets
     LPar,
     RPar,
@@ -75,6 +88,7 @@ pub enum TokenType {
     Dot,
     Comma,
     Colon,
+    QuestionMark,
     True,
     False,
     Null,
@@ -86,62 +100,132 @@ pub enum TokenType {
     ExpressionEnd,
 }
 
+const KEYWORDS: [(&str, TokenType); 23] = [
+    ("package", TokenType::Package),
+    ("import", TokenType::Import),
+    ("manage", TokenType::Manage),
+    ("attach", TokenType::Attach),
+    ("inspect", TokenType::Inspect),
+    ("if", TokenType::If),
+    ("elif", TokenType::Elif),
+    ("else", TokenType::Else),
+    ("while", TokenType::While),
+    ("for", TokenType::For),
+    ("of", TokenType::Of),
+    ("continue", TokenType::Continue),
+    ("break", TokenType::Break),
+    ("return", TokenType::Return),
+    ("mut", TokenType::Mut),
+    ("fin", TokenType::Fin),
+    ("function", TokenType::Function),
+    ("enum", TokenType::Enum),
+    ("table", TokenType::Table),
+    ("event", TokenType::Event),
+    ("true", TokenType::True),
+    ("false", TokenType::False),
+    ("null", TokenType::Null),
+];
+
+const SYMBOLS: [(&str, TokenType); 33] = [
+    ("++", TokenType::Increment),
+    ("--", TokenType::Decrement),
+    ("+=", TokenType::AddAssign),
+    ("-=", TokenType::SubtractAssign),
+    ("*=", TokenType::MultiplyAssign),
+    ("/=", TokenType::DivideAssign),
+    ("==", TokenType::EqualSign),
+    ("!=", TokenType::NotEqualSign),
+    (">=", TokenType::GreaterOrEqual),
+    ("<=", TokenType::LessOrEqual),
+    ("&&", TokenType::And),
+    ("||", TokenType::Or),
+    ("+", TokenType::Addition),
+    ("-", TokenType::Subtraction),
+    ("*", TokenType::Multiplication),
+    ("/", TokenType::Division),
+    (">", TokenType::Greater),
+    ("<", TokenType::Less),
+    ("^", TokenType::Hat),
+    ("!", TokenType::Negotion),
+    ("&", TokenType::Link),
+    ("=", TokenType::Assign),
+    ("(", TokenType::LPar),
+    (")", TokenType::RPar),
+    ("{", TokenType::LBracket),
+    ("}", TokenType::RBracket),
+    ("[", TokenType::LSquareBracket),
+    ("]", TokenType::RSquareBracket),
+    (".", TokenType::Dot),
+    (",", TokenType::Comma),
+    (":", TokenType::Colon),
+    ("?", TokenType::QuestionMark),
+    (";", TokenType::ExpressionEnd),
+];
+
 impl TokenType {
-    pub fn regex_str(&self) -> &str {
+    pub fn from_keyword(word: &str) -> Option<TokenType> {
+        KEYWORDS
+            .iter()
+            .find(|(keyword, _)| *keyword == word)
+            .map(|(_, token_type)| token_type.clone())
+    }
+
+    // Operators and punctuation, the longer ones are looked up first by the lexer.
+    pub fn from_symbol(symbol: &str) -> Option<TokenType> {
+        SYMBOLS
+            .iter()
+            .find(|(known_symbol, _)| *known_symbol == symbol)
+            .map(|(_, token_type)| token_type.clone())
+    }
+
+    // How the token is written in the code, if it's always written the same way.
+    pub fn get_text(&self) -> Option<&'static str> {
+        KEYWORDS
+            .iter()
+            .chain(SYMBOLS.iter())
+            .find(|(_, token_type)| token_type == self)
+            .map(|(text, _)| *text)
+    }
+
+    // The higher the precedence is, the tighter the binary operator binds its operands.
+    pub fn get_precedence(&self) -> u8 {
+        match self {
+            TokenType::Assign
+            | TokenType::AddAssign
+            | TokenType::SubtractAssign
+            | TokenType::MultiplyAssign
+            | TokenType::DivideAssign => 1,
+            TokenType::Or => 2,
+            TokenType::And => 3,
+            TokenType::EqualSign
+            | TokenType::NotEqualSign
+            | TokenType::Greater
+            | TokenType::Less
+            | TokenType::GreaterOrEqual
+            | TokenType::LessOrEqual => 4,
+            TokenType::Addition | TokenType::Subtraction => 5,
+            TokenType::Multiplication | TokenType::Division => 6,
+            TokenType::Hat => 7,
+            _ => 0,
+        }
+    }
+
+    pub fn is_right_associative(&self) -> bool {
+        self.is_assignment() || *self == TokenType::Hat
+    }
+
+    pub fn is_assignment(&self) -> bool {
+        *self == TokenType::Assign || self.get_compound_operator().is_some()
+    }
+
+    // The operator a compound assignment applies before assigning, e.g. '+' for '+='.
+    pub fn get_compound_operator(&self) -> Option<TokenType> {
         match self {
-            TokenType::If => r"if\b",
-            TokenType::Elif => r"elif\b",
-            TokenType::Else => r"else\b",
-            TokenType::Mut => r"mut\b",
-            TokenType::Fin => r"fin\b",
-            TokenType::Enum => r"enum\b",
-            TokenType::True => r"true\b",
-            TokenType::False => r"false\b",
-            TokenType::Null => r"null\b",
-            TokenType::While => r"while\b",
-            TokenType::Import => r"import\b",
-            TokenType::Manage => r"manage\b",
-            TokenType::Attach => r"attach\b",
-            TokenType::Inspect => r"inspect\b",
-            TokenType::Function => r"function\b",
-            TokenType::Continue => r"continue\b",
-            TokenType::Break => r"break\b",
-            TokenType::Return => r"return\b",
-            TokenType::Negotion => r"\!",
-            TokenType::Link => r"&",
-            TokenType::Increment => r"\+\+",
-            TokenType::Decrement => r"\-\-",
-            TokenType::Addition => r"\+",
-            TokenType::Subtraction => r"-",
-            TokenType::Multiplication => r"\*",
-            TokenType::Division => r"\/",
-            TokenType::Assign => r"=",
-            TokenType::EqualSign => r"==",
-            TokenType::NotEqualSign => r"\!=",
-            TokenType::Greater => r">",
-            TokenType::Less => r"<",
-            TokenType::GreaterOrEqual => r">=",
-            TokenType::LessOrEqual => r"<=",
-            TokenType::And => r"&&",
-            TokenType::Or => r"\|\|",
-            TokenType::Hat => r"\^",
-            TokenType::LPar => r"\(",
-            TokenType::RPar => r"\)",
-            TokenType::LBracket => r"\{",
-            TokenType::RBracket => r"\}",
-            TokenType::LSquareBracket => r"\[",
-            TokenType::RSquareBracket => r"\]",
-            TokenType::CharArray => r#"".*?[^\\]"|"""#,
-            TokenType::Alphanumeric => r"[a-zA-Z_]\w*",
-            TokenType::Number => r"\d+(\.\d+)?",
-            TokenType::Space => r#"\s"#,
-            TokenType::Dot => r"\.",
-            TokenType::Comma => r",",
-            TokenType::Colon => r":",
-            TokenType::ExpressionEnd => r";",
-            TokenType::NewLine => r"\n",
-            TokenType::Indent => r"\t",
-            TokenType::Carriage => r"\r",
+            TokenType::AddAssign => Some(TokenType::Addition),
+            TokenType::SubtractAssign => Some(TokenType::Subtraction),
+            TokenType::MultiplyAssign => Some(TokenType::Multiplication),
+            TokenType::DivideAssign => Some(TokenType::Division),
+            _ => None,
         }
     }
 }
@@ -153,7 +237,7 @@ pub const WHITESPACE_TOKENS: [TokenType; 4] = [
     TokenType::Carriage,
 ];
 
-pub const BINARY_OPERATOR_TOKENS: [TokenType; 14] = [
+pub const BINARY_OPERATOR_TOKENS: [TokenType; 18] = [
     TokenType::Addition,
     TokenType::Subtraction,
     TokenType::Multiplication,
@@ -166,19 +250,24 @@ pub const BINARY_OPERATOR_TOKENS: [TokenType; 14] = [
     TokenType::GreaterOrEqual,
     TokenType::Hat,
     TokenType::Assign,
+    TokenType::AddAssign,
+    TokenType::SubtractAssign,
+    TokenType::MultiplyAssign,
+    TokenType::DivideAssign,
     TokenType::And,
     TokenType::Or,
 ];
 
-pub const UNARY_OPERATOR_TOKENS: [TokenType; 4] = [
+pub const UNARY_OPERATOR_TOKENS: [TokenType; 5] = [
     TokenType::Increment,
     TokenType::Decrement,
     TokenType::Negotion,
     TokenType::Link,
+    TokenType::Subtraction,
 ];
 
 // The tokens formulas can start with.
-pub const FORMULA_TOKENS: [TokenType; 12] = [
+pub const FORMULA_TOKENS: [TokenType; 15] = [
     TokenType::CharArray,
     TokenType::Number,
     TokenType::Alphanumeric,
@@ -189,8 +278,20 @@ pub const FORMULA_TOKENS: [TokenType; 12] = [
     TokenType::Decrement,
     TokenType::Negotion,
     TokenType::Link,
+    TokenType::Subtraction,
     TokenType::LBracket,
+    TokenType::LSquareBracket,
+    TokenType::LPar,
     TokenType::If,
 ];
 
+// The comparisons that can bound a column.
+pub const CONSTRAINT_TOKENS: [TokenType; 5] = [
+    TokenType::Greater,
+    TokenType::Less,
+    TokenType::GreaterOrEqual,
+    TokenType::LessOrEqual,
+    TokenType::Number,
+];
+
 pub const VARIABLE_ASSIGNMENT_TOKENS: [TokenType; 2] = [TokenType::Mut, TokenType::Fin];
@@ -0,0 +1,1174 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use super::ast::array::ArrayNode;
+use super::ast::binary_operator::BinaryOperatorNode;
+use super::ast::body::BodyNode;
+use super::ast::boolean::BooleanNode;
+use super::ast::call::{CallNode, CallType};
+use super::ast::conditional_tree::ConditionalTreeNode;
+use super::ast::document::DocumentNode;
+use super::ast::enum_declaration::EnumDeclarationNode;
+use super::ast::event_declaration::EventDeclarationNode;
+use super::ast::expression::ExpressionNode;
+use super::ast::for_loop::ForLoopNode;
+use super::ast::function_declaration::FunctionDeclarationNode;
+use super::ast::functional_return::FunctionalReturnNode;
+use super::ast::identifier::IdentifierNode;
+use super::ast::member::MemberNode;
+use super::ast::null::NullNode;
+use super::ast::number::NumberNode;
+use super::ast::parenthesized::ParenthesizedNode;
+use super::ast::string::StringNode;
+use super::ast::table_declaration::{ColumnType, ReferenceModifier, TableDeclarationNode};
+use super::ast::unary_operator::UnaryOperatorNode;
+use super::ast::variable_declaration::VariableDeclaration;
+use super::ast::visitor::Visitor;
+use super::ast::while_loop::WhileLoopNode;
+use super::diagnostic::{Diagnostic, DiagnosticCode, Diagnostics};
+use super::number::Number;
+use super::span::Span;
+use super::tokens::TokenType;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Str,
+    Int,
+    Uint,
+    Float,
+    Decimal,
+    Bool,
+    Datetime,
+    Uuid,
+    Geo,
+    Null,
+    Enum(String),
+    // A row of a table, e.g. `&users`.
+    Row(String),
+    Array(Box<Type>),
+    // A type parameter of the function being checked, e.g. `T` in `function first<T>`.
+    Generic(String),
+    // Anything, when the type can't be told before execution.
+    Unknown,
+}
+
+impl Type {
+    pub fn from_builtin(name: &str) -> Option<Self> {
+        match name {
+            "str" => Some(Type::Str),
+            "int" => Some(Type::Int),
+            "uint" => Some(Type::Uint),
+            "float" => Some(Type::Float),
+            "decimal" => Some(Type::Decimal),
+            "bool" => Some(Type::Bool),
+            "datetime" => Some(Type::Datetime),
+            "uuid" => Some(Type::Uuid),
+            "geo" => Some(Type::Geo),
+            _ => None,
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Type::Int | Type::Uint | Type::Float | Type::Decimal)
+    }
+
+    // Null can be stored anywhere, like in a column without a value.
+    // Integers widen to floats and decimals, and dates and ids are written as strings.
+    pub fn is_assignable_to(&self, expected: &Type) -> bool {
+        match (self, expected) {
+            _ if self == expected => true,
+            (Type::Null | Type::Unknown, _) | (_, Type::Unknown) => true,
+            (Type::Int | Type::Uint, Type::Int | Type::Uint | Type::Float | Type::Decimal) => true,
+            (Type::Str, Type::Datetime | Type::Uuid) => true,
+            (Type::Array(item), Type::Array(expected_item)) => item.is_assignable_to(expected_item),
+            _ => false,
+        }
+    }
+
+    // Learns what the type parameters stand for from the type of a given argument.
+    pub fn bind_generics(&self, found: &Type, bindings: &mut HashMap<String, Type>) {
+        match (self, found) {
+            (_, Type::Null | Type::Unknown) => {}
+            (Type::Generic(name), _) => {
+                bindings
+                    .entry(name.clone())
+                    .or_insert_with(|| found.clone());
+            }
+            (Type::Array(item), Type::Array(found_item)) => {
+                item.bind_generics(found_item, bindings)
+            }
+            _ => {}
+        }
+    }
+
+    // Type parameters that weren't bound can be anything.
+    pub fn substitute(&self, bindings: &HashMap<String, Type>) -> Type {
+        match self {
+            Type::Generic(name) => bindings.get(name).cloned().unwrap_or(Type::Unknown),
+            Type::Array(item) => Type::Array(Box::new(item.substitute(bindings))),
+            _ => self.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Str => write!(f, "str"),
+            Type::Int => write!(f, "int"),
+            Type::Uint => write!(f, "uint"),
+            Type::Float => write!(f, "float"),
+            Type::Decimal => write!(f, "decimal"),
+            Type::Bool => write!(f, "bool"),
+            Type::Datetime => write!(f, "datetime"),
+            Type::Uuid => write!(f, "uuid"),
+            Type::Geo => write!(f, "geo"),
+            Type::Null => write!(f, "null"),
+            Type::Enum(name) | Type::Generic(name) => write!(f, "{}", name),
+            Type::Row(table) => write!(f, "&{}", table),
+            Type::Array(item) => write!(f, "{}[]", item),
+            Type::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Signature {
+    generics: Vec<String>,
+    // The name, the type and whether the parameter has a default value.
+    parameters: Vec<(String, Type, bool)>,
+    return_type: Type,
+}
+
+#[derive(Default)]
+struct Scope {
+    variables: HashMap<String, Type>,
+    functions: HashMap<String, Rc<Signature>>,
+    // Enums by the types of their values.
+    enums: HashMap<String, Type>,
+}
+
+// The function whose body is being checked, for its return statements.
+struct FunctionContext {
+    name: String,
+    return_type: Option<Type>,
+    returned_types: Vec<Type>,
+}
+
+// Checks the code against the declared datatypes before it's executed.
+// Types that aren't declared are inferred from the values.
+pub struct TypeChecker {
+    code_source: String,
+    scopes: Vec<Scope>,
+    // Tables are global, like in the executor. Their columns are unknown until declared.
+    tables: HashMap<String, Option<Vec<(String, Type)>>>,
+    functions: Vec<FunctionContext>,
+    // Type parameters of the functions being checked.
+    generics: Vec<String>,
+    // Every visit pushes the type of the visited node.
+    types: Vec<Type>,
+    statement_span: Span,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl TypeChecker {
+    pub fn new(code_source: &str) -> Self {
+        TypeChecker {
+            code_source: code_source.to_string(),
+            scopes: vec![Scope::default()],
+            tables: HashMap::new(),
+            functions: vec![],
+            generics: vec![],
+            types: vec![],
+            statement_span: Span::new(0, 0, 1, 1),
+            diagnostics: vec![],
+        }
+    }
+
+    pub fn check(&mut self, body: &BodyNode) -> Result<(), Diagnostics> {
+        self.infer(body);
+        if !self.diagnostics.is_empty() {
+            return Err(Diagnostics(std::mem::take(&mut self.diagnostics)));
+        }
+        Ok(())
+    }
+
+    fn infer(&mut self, node: &dyn ExpressionNode) -> Type {
+        self.visit_expression(node);
+        self.types.pop().unwrap()
+    }
+
+    fn type_error(&mut self, code: DiagnosticCode, message: String, span: Span) -> &mut Diagnostic {
+        self.diagnostics
+            .push(Diagnostic::error(code, message, &self.code_source, span));
+        self.diagnostics.last_mut().unwrap()
+    }
+
+    fn expect_type(&mut self, found: &Type, expected: &Type, span: Span, label: String) {
+        if self.is_assignable(found, expected) {
+            return;
+        }
+        let diagnostic = self.type_error(
+            DiagnosticCode::TypeMismatch,
+            format!("expected '{}', found '{}'", expected, found),
+            span,
+        );
+        diagnostic.span_label = Some(label);
+    }
+
+    // Integers can be stored as 'uint' unless they are negative, which is known for literals.
+    fn expect_value(
+        &mut self,
+        value: &dyn ExpressionNode,
+        found: &Type,
+        expected: &Type,
+        span: Span,
+        label: String,
+    ) {
+        self.expect_type(found, expected, span, label);
+        let Some(Number::Int(number)) = value
+            .downcast_ref::<NumberNode>()
+            .map(|number_node| number_node.get_value())
+        else {
+            return;
+        };
+        if *expected == Type::Uint && number < 0 {
+            let diagnostic = self.type_error(
+                DiagnosticCode::TypeMismatch,
+                format!("expected 'uint', found {}", number),
+                span,
+            );
+            diagnostic.span_label = Some("'uint' can't be negative".to_string());
+        }
+    }
+
+    fn expect_condition(&mut self, condition: &dyn ExpressionNode) {
+        let condition_type = self.infer(condition);
+        let span = self.get_span(condition);
+        self.expect_type(
+            &condition_type,
+            &Type::Bool,
+            span,
+            "conditions must be 'bool'".to_string(),
+        );
+    }
+
+    // Enum variants can be stored where the values of the enum are expected, and values
+    // can be stored where the enum is expected, as the executor checks them against the variants.
+    fn is_assignable(&self, found: &Type, expected: &Type) -> bool {
+        if let Type::Enum(name) = found {
+            if self
+                .find_enum(name)
+                .is_some_and(|value_type| value_type.is_assignable_to(expected))
+            {
+                return true;
+            }
+        }
+        if let Type::Enum(name) = expected {
+            if self
+                .find_enum(name)
+                .is_some_and(|value_type| found.is_assignable_to(value_type))
+            {
+                return true;
+            }
+        }
+        found.is_assignable_to(expected)
+    }
+
+    fn find_variable(&self, name: &str) -> Option<&Type> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.variables.get(name))
+    }
+
+    fn find_function(&self, name: &str) -> Option<Rc<Signature>> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.functions.get(name))
+            .cloned()
+    }
+
+    fn find_enum(&self, name: &str) -> Option<&Type> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.enums.get(name))
+    }
+
+    fn define_variable(&mut self, name: &str, variable_type: Type) {
+        let scope = self.scopes.last_mut().unwrap();
+        scope.variables.insert(name.to_string(), variable_type);
+    }
+
+    fn get_column_type(&self, table: &str, column: &str) -> Type {
+        self.tables
+            .get(table)
+            .and_then(|columns| columns.as_ref())
+            .and_then(|columns| columns.iter().find(|(name, _)| name == column))
+            .map_or(Type::Unknown, |(_, column_type)| column_type.clone())
+    }
+
+    // Datatypes are built-in types, type parameters, enums, or tables with an optional
+    // reference modifier. Types other than tables become arrays with `[]`.
+    fn resolve_datatype(&mut self, datatype: &str, span: Span) -> Type {
+        if self.generics.iter().any(|generic| generic == datatype) {
+            return Type::Generic(datatype.to_string());
+        }
+        if let Some(builtin_type) = Type::from_builtin(datatype) {
+            return builtin_type;
+        }
+        if let Some(reference) = datatype.strip_prefix('&') {
+            return match reference.strip_suffix("[]") {
+                Some(table) => self.resolve_table(table, ReferenceModifier::Many, span),
+                None => self.resolve_table(
+                    reference.trim_end_matches(['?', '!']),
+                    ReferenceModifier::Single,
+                    span,
+                ),
+            };
+        }
+        if let Some(item) = datatype.strip_suffix("[]") {
+            return Type::Array(Box::new(self.resolve_datatype(item, span)));
+        }
+        if self.find_enum(datatype).is_some() {
+            return Type::Enum(datatype.to_string());
+        }
+        if self.tables.contains_key(datatype) {
+            return Type::Row(datatype.to_string());
+        }
+        self.type_error(
+            DiagnosticCode::UnknownType,
+            format!("unknown type '{}'", datatype),
+            span,
+        );
+        Type::Unknown
+    }
+
+    fn resolve_table(&mut self, table: &str, modifier: ReferenceModifier, span: Span) -> Type {
+        if !self.tables.contains_key(table) {
+            self.type_error(
+                DiagnosticCode::UnknownType,
+                format!("unknown table '{}'", table),
+                span,
+            );
+            return Type::Unknown;
+        }
+        let row_type = Type::Row(table.to_string());
+        match modifier {
+            ReferenceModifier::Many => Type::Array(Box::new(row_type)),
+            _ => row_type,
+        }
+    }
+
+    // Enums are used by the types of their values in calculations.
+    fn get_value_type(&self, value_type: Type) -> Type {
+        match &value_type {
+            Type::Enum(name) => self.find_enum(name).cloned().unwrap_or(Type::Unknown),
+            _ => value_type,
+        }
+    }
+
+    fn infer_operation(
+        &mut self,
+        operator: &TokenType,
+        left: Type,
+        right: Type,
+        span: Span,
+    ) -> Type {
+        let (left, right) = (self.get_value_type(left), self.get_value_type(right));
+        let result_type = match operator {
+            _ if left == Type::Unknown || right == Type::Unknown => Some(Type::Unknown),
+            TokenType::EqualSign | TokenType::NotEqualSign => Some(Type::Bool),
+            TokenType::Greater
+            | TokenType::Less
+            | TokenType::GreaterOrEqual
+            | TokenType::LessOrEqual => {
+                let is_comparable = (left.is_numeric() && right.is_numeric())
+                    || (left == Type::Str && right == Type::Str);
+                is_comparable.then_some(Type::Bool)
+            }
+            TokenType::Addition if left == Type::Str && right == Type::Str => Some(Type::Str),
+            _ if left.is_numeric() && right.is_numeric() => {
+                Some(get_numeric_result_type(operator, &left, &right))
+            }
+            _ => None,
+        };
+        result_type.unwrap_or_else(|| {
+            self.type_error(
+                DiagnosticCode::TypeMismatch,
+                format!(
+                    "'{}' cannot be applied to '{}' and '{}'",
+                    operator.get_text().unwrap_or_default(),
+                    left,
+                    right
+                ),
+                span,
+            );
+            Type::Unknown
+        })
+    }
+
+    // Fields of rows are found through the columns of their tables.
+    fn infer_field(&self, parent_type: &Type, child: &dyn ExpressionNode) -> Type {
+        if let Some(identifier) = child.downcast_ref::<IdentifierNode>() {
+            return match parent_type {
+                Type::Row(table) => self.get_column_type(table, identifier.get_name()),
+                _ => Type::Unknown,
+            };
+        }
+        if let Some(member) = child.downcast_ref::<MemberNode>() {
+            let field_type = self.infer_field(parent_type, member.get_parent());
+            return self.infer_field(&field_type, member.get_child());
+        }
+        if let Some(call) = child.downcast_ref::<CallNode>() {
+            if *call.get_call_type() == CallType::Position {
+                let field_type = self.infer_field(parent_type, call.get_callee());
+                let is_slice = call.get_slice_bounds().is_some();
+                return get_position_type(&field_type, is_slice).unwrap_or(Type::Unknown);
+            }
+        }
+        Type::Unknown
+    }
+
+    // Arrays and strings take whole numbers, while rows take the names of their columns.
+    fn infer_position(&mut self, call: &CallNode) -> Type {
+        let span = self.get_span(call);
+        let callee_type = self.infer(call.get_callee());
+        let position_type = match callee_type {
+            Type::Row(_) => Type::Str,
+            _ => Type::Int,
+        };
+        for argument in call.get_arguments() {
+            if let Some(position) = argument.get_value() {
+                let found_type = self.infer(position);
+                let position_span = self.get_span(position);
+                self.expect_type(
+                    &found_type,
+                    &position_type,
+                    position_span,
+                    format!("positions of '{}' are '{}'", callee_type, position_type),
+                );
+            }
+        }
+        let is_slice = call.get_slice_bounds().is_some();
+        get_position_type(&callee_type, is_slice).unwrap_or_else(|| {
+            self.type_error(
+                DiagnosticCode::TypeMismatch,
+                format!("'{}' cannot be indexed", callee_type),
+                span,
+            );
+            Type::Unknown
+        })
+    }
+
+    // Casts take a single value, while rows are built out of their columns.
+    fn infer_type_call(&mut self, call: &CallNode) -> Type {
+        let span = self.get_span(call);
+        let name = call
+            .get_callee()
+            .downcast_ref::<IdentifierNode>()
+            .map_or("", |callee| callee.get_name());
+        let mut given_arguments = vec![];
+        for argument in call.get_arguments() {
+            let argument_type = match argument.get_value() {
+                Some(value) => self.infer(value),
+                None => Type::Null,
+            };
+            given_arguments.push((argument.get_name(), argument_type, argument.get_value()));
+        }
+        if let Some(builtin_type) = Type::from_builtin(name) {
+            if !matches!(given_arguments.as_slice(), [("", _, _)]) {
+                self.type_error(
+                    DiagnosticCode::InvalidArguments,
+                    format!(
+                        "'{}' takes 1 positional argument but {} were given",
+                        name,
+                        given_arguments.len()
+                    ),
+                    span,
+                );
+            }
+            return builtin_type;
+        }
+        let Some(Some(columns)) = self.tables.get(name).cloned() else {
+            return Type::Unknown;
+        };
+        for (index, (keyword, argument_type, value)) in given_arguments.into_iter().enumerate() {
+            let column = match keyword {
+                "" => columns.get(index),
+                _ => columns.iter().find(|(column, _)| column == keyword),
+            };
+            let Some((column, column_type)) = column else {
+                let message = match keyword {
+                    "" => format!("table '{}' has {} columns", name, columns.len()),
+                    _ => format!("table '{}' has no column '{}'", name, keyword),
+                };
+                self.type_error(DiagnosticCode::InvalidArguments, message, span);
+                continue;
+            };
+            let label = format!("column '{}' is '{}'", column, column_type);
+            match value {
+                Some(value) => self.expect_value(value, &argument_type, column_type, span, label),
+                None => self.expect_type(&argument_type, column_type, span, label),
+            }
+        }
+        Type::Row(name.to_string())
+    }
+
+    // Returns what the call evaluates to, with the type parameters of the function bound.
+    fn check_arguments(
+        &mut self,
+        call: &CallNode,
+        callee: &IdentifierNode,
+        signature: &Signature,
+    ) -> Type {
+        let name = callee.get_name();
+        let span = self.get_span(call);
+        let mut positional_count = 0;
+        let mut given_arguments = vec![];
+        let mut given_values = vec![];
+        for argument in call.get_arguments() {
+            let argument_type = match argument.get_value() {
+                Some(value) => self.infer(value),
+                None => Type::Null,
+            };
+            let parameter_index = match argument.is_positional() {
+                true => {
+                    positional_count += 1;
+                    Some(positional_count - 1).filter(|index| *index < signature.parameters.len())
+                }
+                false => signature
+                    .parameters
+                    .iter()
+                    .position(|(parameter, _, _)| parameter == argument.get_name()),
+            };
+            match parameter_index {
+                Some(index) => {
+                    given_arguments.push((index, argument_type));
+                    given_values.push(argument.get_value());
+                }
+                None if !argument.is_positional() => {
+                    self.type_error(
+                        DiagnosticCode::InvalidArguments,
+                        format!("'{}' has no argument '{}'", name, argument.get_name()),
+                        span,
+                    );
+                }
+                None => {}
+            }
+        }
+        if positional_count > signature.parameters.len() {
+            self.type_error(
+                DiagnosticCode::InvalidArguments,
+                format!(
+                    "'{}' takes {} arguments but {} were given",
+                    name,
+                    signature.parameters.len(),
+                    positional_count
+                ),
+                span,
+            );
+        }
+        for (index, (parameter, _, has_default)) in signature.parameters.iter().enumerate() {
+            if !has_default && !given_arguments.iter().any(|(given, _)| *given == index) {
+                self.type_error(
+                    DiagnosticCode::InvalidArguments,
+                    format!("'{}' is missing argument '{}'", name, parameter),
+                    span,
+                );
+            }
+        }
+
+        let bindings = self.bind_generics(callee, signature, &given_arguments, span);
+        for ((index, argument_type), value) in given_arguments.into_iter().zip(given_values) {
+            let (parameter, parameter_type, _) = &signature.parameters[index];
+            let parameter_type = parameter_type.substitute(&bindings);
+            let label = format!(
+                "argument '{}' of '{}' is '{}'",
+                parameter, name, parameter_type
+            );
+            match value {
+                Some(value) => {
+                    self.expect_value(value, &argument_type, &parameter_type, span, label)
+                }
+                None => self.expect_type(&argument_type, &parameter_type, span, label),
+            }
+        }
+        signature.return_type.substitute(&bindings)
+    }
+
+    // Type arguments are either written at the call, like `first<int>(numbers)`,
+    // or inferred from the arguments.
+    fn bind_generics(
+        &mut self,
+        callee: &IdentifierNode,
+        signature: &Signature,
+        given_arguments: &[(usize, Type)],
+        span: Span,
+    ) -> HashMap<String, Type> {
+        let mut bindings = HashMap::new();
+        let type_arguments = callee.get_types();
+        if type_arguments.is_empty() {
+            for (index, argument_type) in given_arguments {
+                let (_, parameter_type, _) = &signature.parameters[*index];
+                parameter_type.bind_generics(argument_type, &mut bindings);
+            }
+            return bindings;
+        }
+        if type_arguments.len() != signature.generics.len() {
+            self.type_error(
+                DiagnosticCode::InvalidArguments,
+                format!(
+                    "'{}' takes {} type arguments but {} were given",
+                    callee.get_name(),
+                    signature.generics.len(),
+                    type_arguments.len()
+                ),
+                span,
+            );
+            return bindings;
+        }
+        for (generic, type_argument) in signature.generics.iter().zip(type_arguments) {
+            let type_span = type_argument.get_span().unwrap_or(span);
+            let argument_type = self.resolve_datatype(type_argument.get_name(), type_span);
+            bindings.insert(generic.clone(), argument_type);
+        }
+        bindings
+    }
+
+    // Hoists the tables and enums of a body, so they can be referred to before their declarations.
+    fn declare_types(&mut self, body: &BodyNode) {
+        for node in &body.nodes {
+            if let Some(table) = node.downcast_ref::<TableDeclarationNode>() {
+                self.tables.entry(table.get_name().to_string()).or_default();
+            }
+            if let Some(declaration) = node.downcast_ref::<EnumDeclarationNode>() {
+                let value_type = match declaration.get_datatype() {
+                    Some("int") => Type::Int,
+                    Some("uint") => Type::Uint,
+                    _ => Type::Str,
+                };
+                let scope = self.scopes.last_mut().unwrap();
+                scope
+                    .enums
+                    .insert(declaration.get_name().to_string(), value_type);
+            }
+        }
+    }
+
+    fn get_span(&self, node: &dyn ExpressionNode) -> Span {
+        get_expression_span(node).unwrap_or(self.statement_span)
+    }
+}
+
+// The type of all the values, if they agree. Null doesn't affect it.
+fn get_common_type(types: Vec<Type>) -> Type {
+    let mut types = types
+        .into_iter()
+        .filter(|value_type| *value_type != Type::Null);
+    let Some(first_type) = types.next() else {
+        return Type::Null;
+    };
+    match types.all(|value_type| value_type.is_assignable_to(&first_type)) {
+        true => first_type,
+        false => Type::Unknown,
+    }
+}
+
+// Floats win over decimals and decimals win over integers, like in the executor.
+// Integers are divided into floats, while decimals stay exact with whole powers.
+fn get_numeric_result_type(operator: &TokenType, left: &Type, right: &Type) -> Type {
+    let is_integer = |numeric_type: &Type| matches!(numeric_type, Type::Int | Type::Uint);
+    match (operator, left, right) {
+        (_, Type::Float, _) | (_, _, Type::Float) => Type::Float,
+        (TokenType::Hat, Type::Decimal, exponent) if is_integer(exponent) => Type::Decimal,
+        (TokenType::Hat, _, _) => Type::Float,
+        (_, Type::Decimal, _) | (_, _, Type::Decimal) => Type::Decimal,
+        (TokenType::Division, _, _) => Type::Float,
+        (_, Type::Uint, Type::Uint) => Type::Uint,
+        _ => Type::Int,
+    }
+}
+
+// Slices keep the type of what they are taken from.
+fn get_position_type(container_type: &Type, is_slice: bool) -> Option<Type> {
+    match container_type {
+        Type::Array(_) | Type::Str if is_slice => Some(container_type.clone()),
+        Type::Array(item_type) => Some(item_type.as_ref().clone()),
+        Type::Str => Some(Type::Str),
+        Type::Row(_) | Type::Unknown if !is_slice => Some(Type::Unknown),
+        Type::Unknown => Some(Type::Unknown),
+        _ => None,
+    }
+}
+
+// Nodes without spans of their own are found by their first spanned part.
+fn get_expression_span(node: &dyn ExpressionNode) -> Option<Span> {
+    if let Some(identifier) = node.downcast_ref::<IdentifierNode>() {
+        identifier.get_span()
+    } else if let Some(call) = node.downcast_ref::<CallNode>() {
+        get_expression_span(call.get_callee())
+    } else if let Some(member) = node.downcast_ref::<MemberNode>() {
+        get_expression_span(member.get_parent())
+    } else if let Some(operator) = node.downcast_ref::<BinaryOperatorNode>() {
+        get_expression_span(operator.get_left_operand())
+    } else if let Some(operator) = node.downcast_ref::<UnaryOperatorNode>() {
+        get_expression_span(operator.get_operand())
+    } else if let Some(parenthesized) = node.downcast_ref::<ParenthesizedNode>() {
+        get_expression_span(parenthesized.get_expression())
+    } else {
+        None
+    }
+}
+
+impl Visitor for TypeChecker {
+    // The items take the type of the first one, or the wider type when integers meet floats.
+    fn visit_array(&mut self, node: &ArrayNode) {
+        let mut item_type = Type::Null;
+        for item in node.get_items() {
+            let found_type = self.infer(item.as_ref());
+            if item_type.is_assignable_to(&found_type) && item_type != Type::Unknown {
+                item_type = found_type;
+                continue;
+            }
+            let span = self.get_span(item.as_ref());
+            self.expect_type(
+                &found_type,
+                &item_type,
+                span,
+                format!("the items of the array are '{}'", item_type),
+            );
+        }
+        if item_type == Type::Null {
+            item_type = Type::Unknown;
+        }
+        self.types.push(Type::Array(Box::new(item_type)));
+    }
+
+    fn visit_binary_operator(&mut self, node: &BinaryOperatorNode) {
+        let operator = node.get_operator();
+        let span = self.get_span(node);
+        let left_type = self.infer(node.get_left_operand());
+        let right_type = self.infer(node.get_right_operand());
+        let result_type = match operator {
+            x if x.is_assignment() => {
+                let label = format!("the assigned value has to be '{}'", left_type);
+                match x.get_compound_operator() {
+                    Some(compound_operator) => {
+                        let is_integer =
+                            |operand_type: &Type| matches!(operand_type, Type::Int | Type::Uint);
+                        let keeps_type = is_integer(&left_type) && is_integer(&right_type);
+                        let value_type = self.infer_operation(
+                            &compound_operator,
+                            left_type.clone(),
+                            right_type,
+                            span,
+                        );
+                        // Integers divided in place stay integers, like in the executor.
+                        let value_type = match keeps_type {
+                            true => left_type.clone(),
+                            false => value_type,
+                        };
+                        self.expect_type(&value_type, &left_type, span, label);
+                    }
+                    None => self.expect_value(
+                        node.get_right_operand(),
+                        &right_type,
+                        &left_type,
+                        span,
+                        label,
+                    ),
+                }
+                left_type
+            }
+            TokenType::And | TokenType::Or => {
+                for operand_type in [left_type, right_type] {
+                    self.expect_type(
+                        &operand_type,
+                        &Type::Bool,
+                        span,
+                        format!(
+                            "'{}' takes 'bool' operands",
+                            operator.get_text().unwrap_or_default()
+                        ),
+                    );
+                }
+                Type::Bool
+            }
+            _ => self.infer_operation(operator, left_type, right_type, span),
+        };
+        self.types.push(result_type);
+    }
+
+    fn visit_body(&mut self, node: &BodyNode) {
+        let statement_span = self.statement_span;
+        self.scopes.push(Scope::default());
+        self.declare_types(node);
+        let mut body_type = Type::Null;
+        for (index, statement) in node.nodes.iter().enumerate() {
+            if let Some(span) = node.get_node_span(index) {
+                self.statement_span = span;
+            }
+            body_type = self.infer(statement.as_ref());
+        }
+        self.scopes.pop();
+        self.statement_span = statement_span;
+        self.types.push(body_type);
+    }
+
+    fn visit_boolean(&mut self, _node: &BooleanNode) {
+        self.types.push(Type::Bool);
+    }
+
+    fn visit_call(&mut self, node: &CallNode) {
+        let call_type = match node.get_call_type() {
+            CallType::Function => None,
+            CallType::Position => Some(self.infer_position(node)),
+            CallType::Type => Some(self.infer_type_call(node)),
+        };
+        if let Some(call_type) = call_type {
+            self.types.push(call_type);
+            return;
+        }
+        let signature = node
+            .get_callee()
+            .downcast_ref::<IdentifierNode>()
+            .and_then(|callee| Some((callee, self.find_function(callee.get_name())?)));
+        let Some((callee, signature)) = signature else {
+            for argument in node.get_arguments() {
+                if let Some(value) = argument.get_value() {
+                    self.infer(value);
+                }
+            }
+            self.types.push(Type::Unknown);
+            return;
+        };
+        let return_type = self.check_arguments(node, callee, &signature);
+        self.types.push(return_type);
+    }
+
+    fn visit_conditional_tree(&mut self, node: &ConditionalTreeNode) {
+        let mut branch_types = vec![];
+        for (condition, value) in node.get_conditions() {
+            self.expect_condition(condition.as_ref());
+            branch_types.push(self.infer(value.as_ref()));
+        }
+        if let Some(default) = node.get_default() {
+            branch_types.push(self.infer(default));
+        }
+        self.types.push(get_common_type(branch_types));
+    }
+
+    fn visit_enum_declaration(&mut self, _node: &EnumDeclarationNode) {
+        self.types.push(Type::Null);
+    }
+
+    fn visit_event_declaration(&mut self, node: &EventDeclarationNode) {
+        let row_type = self.resolve_table(
+            node.get_table(),
+            ReferenceModifier::Single,
+            self.statement_span,
+        );
+        self.scopes.push(Scope::default());
+        self.define_variable("old", row_type.clone());
+        self.define_variable("new", row_type);
+        self.infer(node.get_body());
+        self.scopes.pop();
+        self.types.push(Type::Null);
+    }
+
+    fn visit_for_loop(&mut self, node: &ForLoopNode) {
+        let collection_type = self.infer(node.get_collection());
+        let item_type = match collection_type {
+            Type::Array(item_type) => *item_type,
+            Type::Unknown => Type::Unknown,
+            collection_type => {
+                let span = self.get_span(node.get_collection());
+                self.type_error(
+                    DiagnosticCode::TypeMismatch,
+                    format!("'{}' cannot be iterated over", collection_type),
+                    span,
+                );
+                Type::Unknown
+            }
+        };
+        self.scopes.push(Scope::default());
+        self.define_variable(node.get_item(), item_type);
+        self.infer(node.get_body());
+        self.scopes.pop();
+        self.types.push(Type::Null);
+    }
+
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationNode) {
+        let name = node.get_name();
+        let span = node.get_span().unwrap_or(self.statement_span);
+        let generics_count = self.generics.len();
+        self.generics.extend(node.get_generics().iter().cloned());
+        let mut parameters = vec![];
+        for parameter in node.get_arguments() {
+            let parameter_type = match parameter.get_datatype() {
+                Some(datatype) => self.resolve_datatype(datatype, span),
+                None => Type::Unknown,
+            };
+            if let Some(default) = parameter.get_value() {
+                let default_type = self.infer(default);
+                self.expect_value(
+                    default,
+                    &default_type,
+                    &parameter_type,
+                    span,
+                    format!(
+                        "argument '{}' is '{}'",
+                        parameter.get_name(),
+                        parameter_type
+                    ),
+                );
+            }
+            parameters.push((
+                parameter.get_name().to_string(),
+                parameter_type,
+                parameter.get_value().is_some(),
+            ));
+        }
+        let return_type = node
+            .get_datatype()
+            .map(|datatype| self.resolve_datatype(datatype, span));
+        let mut signature = Signature {
+            generics: node.get_generics().to_vec(),
+            parameters,
+            return_type: return_type.clone().unwrap_or(Type::Unknown),
+        };
+        // The function is declared before its body is checked, so it can call itself.
+        let scope = self.scopes.last_mut().unwrap();
+        scope
+            .functions
+            .insert(name.to_string(), Rc::new(signature.clone()));
+        let Some(body) = node.get_body() else {
+            self.generics.truncate(generics_count);
+            self.types.push(Type::Null);
+            return;
+        };
+
+        let mut function_scope = Scope::default();
+        for (parameter, parameter_type, _) in &signature.parameters {
+            function_scope
+                .variables
+                .insert(parameter.clone(), parameter_type.clone());
+        }
+        self.scopes.push(function_scope);
+        self.functions.push(FunctionContext {
+            name: name.to_string(),
+            return_type: return_type.clone(),
+            returned_types: vec![],
+        });
+        let body_type = self.infer(body);
+        let context = self.functions.pop().unwrap();
+        self.scopes.pop();
+
+        match return_type {
+            Some(return_type) => self.expect_type(
+                &body_type,
+                &return_type,
+                span,
+                format!("'{}' returns '{}'", name, return_type),
+            ),
+            None => {
+                let mut returned_types = context.returned_types;
+                returned_types.push(body_type);
+                signature.return_type = get_common_type(returned_types);
+                let scope = self.scopes.last_mut().unwrap();
+                scope.functions.insert(name.to_string(), Rc::new(signature));
+            }
+        }
+        self.generics.truncate(generics_count);
+        self.types.push(Type::Null);
+    }
+
+    fn visit_functional_return(&mut self, node: &FunctionalReturnNode) {
+        let value_type = match node.get_value() {
+            Some(value) => self.infer(value),
+            None => Type::Null,
+        };
+        let span = node.get_span().unwrap_or(self.statement_span);
+        if let Some(context) = self.functions.last_mut() {
+            match context.return_type.clone() {
+                Some(return_type) => {
+                    let label = format!("'{}' returns '{}'", context.name, return_type);
+                    self.expect_type(&value_type, &return_type, span, label);
+                }
+                None => context.returned_types.push(value_type),
+            }
+        }
+        self.types.push(Type::Null);
+    }
+
+    fn visit_identifier(&mut self, node: &IdentifierNode) {
+        let variable_type = self.find_variable(node.get_name()).cloned();
+        self.types.push(variable_type.unwrap_or(Type::Unknown));
+    }
+
+    fn visit_member(&mut self, node: &MemberNode) {
+        let parent = node.get_parent();
+        // Variables shadow the enums declared with the same name, like in the executor.
+        if let Some(identifier) = parent.downcast_ref::<IdentifierNode>() {
+            let name = identifier.get_name();
+            if self.find_variable(name).is_none() && self.find_enum(name).is_some() {
+                self.types.push(Type::Enum(name.to_string()));
+                return;
+            }
+        }
+        let parent_type = self.infer(parent);
+        let member_type = self.infer_field(&parent_type, node.get_child());
+        self.types.push(member_type);
+    }
+
+    fn visit_null(&mut self, _node: &NullNode) {
+        self.types.push(Type::Null);
+    }
+
+    fn visit_number(&mut self, node: &NumberNode) {
+        self.types.push(match node.get_value() {
+            Number::Int(_) => Type::Int,
+            Number::Uint(_) => Type::Uint,
+            Number::Float(_) => Type::Float,
+            Number::Decimal(_) => Type::Decimal,
+        });
+    }
+
+    // Documents can hold anything, so only their values are checked.
+    fn visit_document(&mut self, node: &DocumentNode) {
+        for (_, value) in node.get_fields() {
+            self.infer(value.as_ref());
+        }
+        self.types.push(Type::Unknown);
+    }
+
+    fn visit_parenthesized(&mut self, node: &ParenthesizedNode) {
+        let expression_type = self.infer(node.get_expression());
+        self.types.push(expression_type);
+    }
+
+    fn visit_string(&mut self, _node: &StringNode) {
+        self.types.push(Type::Str);
+    }
+
+    fn visit_table_declaration(&mut self, node: &TableDeclarationNode) {
+        let mut columns = vec![];
+        for column in node.get_columns() {
+            let column_type = match column.get_column_type() {
+                ColumnType::Datatype(datatype) => {
+                    self.resolve_datatype(datatype, self.statement_span)
+                }
+                ColumnType::Reference(table, modifier) => {
+                    self.resolve_table(table, modifier.clone(), self.statement_span)
+                }
+            };
+            if let Some(default) = column.get_default() {
+                let default_type = self.infer(default);
+                self.expect_value(
+                    default,
+                    &default_type,
+                    &column_type,
+                    self.statement_span,
+                    format!("column '{}' is '{}'", column.get_name(), column_type),
+                );
+            }
+            columns.push((column.get_name().to_string(), column_type));
+        }
+        self.tables
+            .insert(node.get_name().to_string(), Some(columns));
+        self.types.push(Type::Null);
+    }
+
+    fn visit_unary_operator(&mut self, node: &UnaryOperatorNode) {
+        let operand = node.get_operand();
+        let operand_type = match node.get_operator() {
+            TokenType::Negotion => {
+                self.expect_condition(operand);
+                Type::Bool
+            }
+            // `&table` queries the rows of a table, while `&table.column` reads a column in events.
+            TokenType::Link => {
+                if let Some(table) = operand.downcast_ref::<IdentifierNode>() {
+                    let row_type = Type::Row(table.get_name().to_string());
+                    Type::Array(Box::new(row_type))
+                } else if let Some(member) = operand.downcast_ref::<MemberNode>() {
+                    let table = member
+                        .get_parent()
+                        .downcast_ref::<IdentifierNode>()
+                        .map(|table| Type::Row(table.get_name().to_string()));
+                    self.infer_field(&table.unwrap_or(Type::Unknown), member.get_child())
+                } else {
+                    Type::Unknown
+                }
+            }
+            operator => {
+                let operand_type = self.infer(operand);
+                let value_type = self.get_value_type(operand_type.clone());
+                if !value_type.is_numeric() && value_type != Type::Unknown {
+                    let span = self.get_span(operand);
+                    self.type_error(
+                        DiagnosticCode::TypeMismatch,
+                        format!(
+                            "'{}' cannot be applied to '{}'",
+                            operator.get_text().unwrap_or_default(),
+                            operand_type
+                        ),
+                        span,
+                    );
+                }
+                // Negative uints are ints.
+                match (operator, value_type) {
+                    (TokenType::Subtraction, Type::Uint) => Type::Int,
+                    _ => operand_type,
+                }
+            }
+        };
+        self.types.push(operand_type);
+    }
+
+    fn visit_variable_declaration(&mut self, node: &VariableDeclaration) {
+        let name = node.get_name();
+        let span = node.get_span().unwrap_or(self.statement_span);
+        let value_type = node.get_value().map(|value| self.infer(value));
+        let declared_type = node
+            .get_datatype()
+            .map(|datatype| self.resolve_datatype(datatype, span));
+        let variable_type = match (declared_type, node.get_value().zip(value_type)) {
+            (Some(declared_type), Some((value, value_type))) => {
+                self.expect_value(
+                    value,
+                    &value_type,
+                    &declared_type,
+                    span,
+                    format!("'{}' is declared as '{}'", name, declared_type),
+                );
+                declared_type
+            }
+            (Some(declared_type), None) => declared_type,
+            // Variables declared as null can get any value later.
+            (None, Some((_, Type::Null))) | (None, None) => Type::Unknown,
+            (None, Some((_, value_type))) => value_type,
+        };
+        self.define_variable(name, variable_type);
+        self.types.push(Type::Null);
+    }
+
+    fn visit_while_loop(&mut self, node: &WhileLoopNode) {
+        self.expect_condition(node.get_condition());
+        self.infer(node.get_body());
+        self.types.push(Type::Null);
+    }
+
+    fn visit_expression(&mut self, node: &dyn ExpressionNode) {
+        let types_count = self.types.len();
+        super::ast::visitor::walk_expression(self, node);
+        // Nodes that say nothing about types, like imports, have no value.
+        if self.types.len() == types_count {
+            self.types.push(Type::Null);
+        }
+    }
+}
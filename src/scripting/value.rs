@@ -0,0 +1,90 @@
+use bson::{Bson, Document};
+use std::fmt;
+
+use super::number::Number;
+
+// Named values in the order they are declared, e.g. the columns of a row.
+pub type Fields = Vec<(String, Value)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Boolean(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    Document(Fields),
+}
+
+impl Value {
+    pub fn get_type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Boolean(_) => "bool",
+            Value::Number(number) => number.get_type_name(),
+            Value::String(_) => "str",
+            Value::Array(_) => "array",
+            Value::Document(_) => "document",
+        }
+    }
+
+    pub fn get_field(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Document(fields) => fields
+                .iter()
+                .find(|(field_name, _)| field_name == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Boolean(state) => write!(f, "{}", state),
+            Value::Number(number) => write!(f, "{}", number),
+            Value::String(string) => write!(f, "\"{}\"", string),
+            Value::Array(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Value::Document(fields) => {
+                write!(f, "{{")?;
+                for (index, (name, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", name, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// Arrays and documents are stored as they are, with the fields in the same order.
+impl From<Value> for Bson {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => Bson::Null,
+            Value::Boolean(state) => Bson::Boolean(state),
+            Value::Number(number) => Bson::from(number),
+            Value::String(string) => Bson::String(string),
+            Value::Array(items) => Bson::Array(items.into_iter().map(Bson::from).collect()),
+            Value::Document(fields) => Bson::Document(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| (name, Bson::from(value)))
+                    .collect::<Document>(),
+            ),
+        }
+    }
+}
@@ -1,12 +1,15 @@
 use dotenvy::dotenv;
 use regex::Regex;
-use std::{env, ffi::OsStr, path::Path};
+use std::{env, ffi::OsStr, io, path::Path};
+
+use crate::db::manage::ManageConfig;
 
 pub struct Config {
     pub host: String,
     pub port: String,
     pub manager_file: String,
     pub password: String,
+    pub max_connections: usize,
 }
 
 impl Config {
@@ -34,20 +37,40 @@ impl Config {
         true
     }
 
-    pub fn parse_arguments(args: Vec<String>) -> Option<Self> {
-        let default = Self::default();
-        let mut host = default.host;
-        let mut port = default.port;
-        let mut manager_file = default.manager_file;
-        let mut password = default.password;
+    pub fn parse_arguments(args: Vec<String>) -> io::Result<Self> {
+        Self::default().apply_arguments(&args)
+    }
+
+    pub fn from_manage_config(manage_config: &ManageConfig) -> Self {
+        let mut config = Self::default();
+        if let Some(host) = &manage_config.host {
+            config.host.clone_from(host);
+        }
+        if let Some(port) = manage_config.port {
+            config.port = port.to_string();
+        }
+        if let Some(max_connections) = manage_config.max_connections {
+            config.max_connections = max_connections;
+        }
+        config
+    }
+
+    pub fn apply_arguments(self, args: &[String]) -> io::Result<Self> {
+        let mut host = self.host;
+        let mut port = self.port;
+        let mut manager_file = self.manager_file;
+        let mut password = self.password;
+        let mut max_connections = self.max_connections;
 
         for arg in 0..args.len() {
             let str = &args[arg];
-            if str.chars().nth(0)? != '-' {
+            if !str.starts_with('-') {
                 continue;
             }
 
-            let arg = args.get(arg + 1)?;
+            let arg = args
+                .get(arg + 1)
+                .ok_or_else(|| argument_error(format!("'{}' needs a value", str)))?;
             let value = if let Some(parse_value) = Config::value(arg.to_string()) {
                 parse_value
             } else {
@@ -56,17 +79,42 @@ impl Config {
 
             match str.as_str() {
                 "-host" => host.clone_from(&value),
-                "-port" => port.clone_from(&value),
+                "-port" => {
+                    value
+                        .parse::<u16>()
+                        .ok()
+                        .filter(|port| *port != 0)
+                        .ok_or_else(|| {
+                            argument_error(format!(
+                                "'-port' must be a port number, found '{}'",
+                                value
+                            ))
+                        })?;
+                    port.clone_from(&value);
+                }
                 "-blz_file" => manager_file.clone_from(&value),
                 "-password" => password.clone_from(&value),
+                "-max_connections" => {
+                    max_connections = value
+                        .parse()
+                        .ok()
+                        .filter(|max_connections| *max_connections != 0)
+                        .ok_or_else(|| {
+                            argument_error(format!(
+                                "'-max_connections' must be a positive number, found '{}'",
+                                value
+                            ))
+                        })?
+                }
                 _ => (),
             }
         }
-        Some(Config {
+        Ok(Config {
             host,
             port,
             manager_file,
             password,
+            max_connections,
         })
     }
 
@@ -76,6 +124,14 @@ impl Config {
             port: "3306".to_string(),
             manager_file: "./db/datablaze/manage.blz".to_string(),
             password: "password".to_string(),
+            max_connections: 8,
         }
     }
 }
+
+fn argument_error(message: impl AsRef<str>) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Argument Error: {}", message.as_ref()),
+    )
+}
@@ -1,28 +1,67 @@
 use crate::{
+    db::manage::ManageConfig,
     server::{config::Config, headers},
-    shell::handling::analyze_syntatically,
+    shell::handling::{
+        analyze_syntatically, exit_with_error, load_packages, run_manage_file, shell_sources,
+    },
 };
-use std::io::{self, Read};
+use std::fs;
+use std::io::{self, Read, Write};
 use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 pub fn server_run(args: Vec<String>) -> io::Result<()> {
-    let config = Config::parse_arguments(args).unwrap();
+    let manager_file = Config::parse_arguments(args.clone())?.manager_file;
 
-    if !Config::blz_exists(&config.manager_file) {
+    if !Config::blz_exists(&manager_file) {
         return Err(io::Error::new(
             io::ErrorKind::NotFound,
             "blz_file not found",
         ));
     }
 
+    // The arguments passed explicitly take priority over the settings of manage.blz.
+    let manage_config = ManageConfig::load(&manager_file)?;
+    let config = Config::from_manage_config(&manage_config).apply_arguments(&args)?;
+
+    // Backups are kept in the work directory of the datablaze, which has to exist.
+    let manager_file = Path::new(&manager_file);
+    let work_dir = manage_config.get_work_dir(manager_file);
+    if !work_dir.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("work_dir '{}' is not a directory", work_dir.display()),
+        ));
+    }
+    fs::create_dir_all(manage_config.get_backups_dir(manager_file))?;
+
+    // The model is run before any connection is accepted, so a broken one stops the server.
+    let mut loader = load_packages(manager_file, &manage_config);
+    if let Err(error) = run_manage_file(&mut loader, manager_file) {
+        exit_with_error(error, loader.get_sources());
+    }
+
     let host = format!("{}:{}", config.host, config.port);
     let listener = TcpListener::bind(host)?;
+    let active_connections = Arc::new(AtomicUsize::new(0));
 
     for stream in listener.incoming() {
         let stream = stream?;
+        if active_connections.load(Ordering::SeqCst) >= config.max_connections {
+            // Dropping the stream closes the connection right away.
+            continue;
+        }
+        active_connections.fetch_add(1, Ordering::SeqCst);
         let password = std::mem::take(&mut config.password.clone());
+        let active_connections = Arc::clone(&active_connections);
 
-        std::thread::spawn(move || handle_connection(stream, password));
+        std::thread::spawn(move || {
+            let result = handle_connection(stream, password);
+            active_connections.fetch_sub(1, Ordering::SeqCst);
+            result
+        });
     }
 
     Ok(())
@@ -37,7 +76,12 @@ fn handle_connection(mut stream: TcpStream, password: String) -> io::Result<()>
     let header = headers::parse_header(request.clone()).unwrap();
     if let Some(value) = header.get("Password") {
         if password == *value {
-            analyze_syntatically(headers::remove_empty_line(request).unwrap())?
+            let code = headers::remove_empty_line(request).unwrap();
+            // The client gets the plain form of the diagnostics, without terminal colors.
+            if let Err(diagnostics) = analyze_syntatically(code.clone()) {
+                let rendered = diagnostics.render_plain(&shell_sources(&code));
+                stream.write_all(rendered.as_bytes())?;
+            }
         };
     };
 
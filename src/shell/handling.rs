@@ -1,10 +1,25 @@
 use crate::db::create_db;
+use crate::db::manage::ManageConfig;
 use crate::fs::filesystem::Fs;
+use crate::scripting::ast::body::BodyNode;
+use crate::scripting::ast::dump::Tree;
+use crate::scripting::diagnostic::{Diagnostic, Diagnostics};
+use crate::scripting::executor::Executor;
+use crate::scripting::loader::PackageLoader;
+use crate::scripting::source_map::SourceMap;
 use crate::scripting::tokens::Token;
-use crate::scripting::{lexer, parser};
+use crate::scripting::type_checker::TypeChecker;
+use crate::scripting::value::Value;
+use crate::scripting::{formatter, lexer, loader, parser};
+use crate::server::config::Config;
 use crate::server::server_bz;
+use colored::Colorize;
 use serde::{Deserialize, Serialize};
-use std::io::{self, Result};
+use std::fs;
+use std::io::{self, IsTerminal, Result};
+use std::path::{Path, PathBuf};
+
+pub const SHELL_CODE_SOURCE: &str = "Shell";
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Person {
@@ -21,7 +36,11 @@ pub fn handle_command_arguments() -> Result<()> {
     };
     match args[1].as_str() {
         "create" => create_db_with_console()?,
-        "run" => server_bz::server_run(args)?,
+        "run" => {
+            if let Err(error) = server_bz::server_run(args) {
+                exit_with_error(error, &SourceMap::new());
+            }
+        }
         "test" => {
             let person = Person {
                 name: "SVD".to_string(),
@@ -38,11 +57,26 @@ pub fn handle_command_arguments() -> Result<()> {
         }
         "lexer" => {
             let text = input_text()?;
-            analyze_lexically(text)?;
+            let tokens =
+                analyze_lexically(text.clone(), SHELL_CODE_SOURCE).unwrap_or_else(|diagnostic| {
+                    exit_with_error(diagnostic.into(), &shell_sources(&text));
+                });
+            for token in tokens {
+                println!(
+                    "{}:{}:{} = {}",
+                    token.span.line, token.span.column, token.value, token.token_type
+                );
+            }
         }
-        "parser" => {
+        "parser" => parse_with_console(&args[2..])?,
+        "fmt" => format_files(&args[2..])?,
+        "executor" => {
             let text = input_text()?;
-            analyze_syntatically(text)?;
+            let mut loader = load_shell_packages(&args[2..])?;
+            match execute(&mut loader, text) {
+                Ok(value) => println!("{}", value),
+                Err(error) => exit_with_error(error, loader.get_sources()),
+            }
         }
         _ => {
             eprintln!("Invalid arguments");
@@ -55,11 +89,13 @@ pub fn handle_command_arguments() -> Result<()> {
 fn print_help_section() {
     let help_list = r#"Blaze Db 0.0.1a - available commands:
     Database management
-        create  - create a new datablaze
+        create   - create a new datablaze
     Blaze Language
-        lexer   - get to see how the code is subjected to lexical analysis under the hood
-        parser  - try the first version of a parser
-        run     - start server"#;
+        lexer    - get to see how the code is subjected to lexical analysis under the hood
+        parser   - parse the code from a file or stdin, --emit=json|sexpr|tokens prints what was parsed
+        executor - evaluate the code and print the resulting value
+        fmt      - rewrite .blz files in the canonical style, with --check only list unformatted ones
+        run      - start server"#;
 
     println!("{}", help_list);
 }
@@ -73,21 +109,58 @@ pub fn create_db_with_console() -> Result<()> {
     Ok(())
 }
 
-fn analyze_lexically(code_to_parse: String) -> Result<Vec<Token>> {
+// Diagnostics are rendered with colors, other errors are printed as they are.
+pub fn exit_with_error(error: io::Error, sources: &SourceMap) -> ! {
+    match Diagnostics::from_io_error(&error) {
+        Some(diagnostics) => eprintln!("{}", diagnostics.render(sources)),
+        None => eprintln!("{}", render_error(&error)),
+    }
+    std::process::exit(1);
+}
+
+// Errors without code to point at are written as "<kind> Error: <message>",
+// and only their kind is colored.
+fn render_error(error: &io::Error) -> String {
+    let text = error.to_string();
+    match text.split_once(": ") {
+        Some((kind, message)) if kind.ends_with(" Error") => {
+            format!("{}: {}", kind.bright_red(), message)
+        }
+        _ => text,
+    }
+}
+
+pub fn shell_sources(code: &str) -> SourceMap {
+    let mut sources = SourceMap::new();
+    sources.add(SHELL_CODE_SOURCE, code);
+    sources
+}
+
+fn analyze_lexically(
+    code_to_parse: String,
+    code_source: &str,
+) -> std::result::Result<Vec<Token>, Box<Diagnostic>> {
     let mut code_lexer = lexer::Lexer::new(code_to_parse);
     code_lexer
         .get_context()
-        .set_code_source("Shell".to_string());
-    Ok(code_lexer.analyze().unwrap())
+        .set_code_source(code_source.to_string());
+    code_lexer.analyze()
 }
 
-pub fn analyze_syntatically(code: String) -> Result<()> {
-    let tokens = analyze_lexically(code)?;
+fn parse_tokens(
+    tokens: Vec<Token>,
+    code_source: &str,
+) -> std::result::Result<BodyNode, Diagnostics> {
     let mut code_parser = parser::Parser::new(tokens);
     code_parser
         .get_context()
-        .set_code_source("Shell".to_string());
-    let nodes = code_parser.parse()?.nodes;
+        .set_code_source(code_source.to_string());
+    code_parser.parse()
+}
+
+pub fn analyze_syntatically(code: String) -> std::result::Result<(), Diagnostics> {
+    let tokens = analyze_lexically(code, SHELL_CODE_SOURCE)?;
+    let nodes = parse_tokens(tokens, SHELL_CODE_SOURCE)?.nodes;
     if !nodes.is_empty() {
         println!(
             "Parsing successfully completed! Nodes Count: {}",
@@ -97,6 +170,164 @@ pub fn analyze_syntatically(code: String) -> Result<()> {
     Ok(())
 }
 
+// Warnings are printed, while errors stop the code from being executed.
+// The packages the code imports are run before it.
+pub fn execute(loader: &mut PackageLoader, code: String) -> Result<Value> {
+    let index = loader.load_entry_code(SHELL_CODE_SOURCE, code)?;
+    print_warnings(loader);
+    TypeChecker::new(SHELL_CODE_SOURCE).check(loader.get_module(index).get_body())?;
+    loader.execute_module(index, &mut Executor::new())
+}
+
+fn print_warnings(loader: &mut PackageLoader) {
+    let warnings = loader.take_warnings();
+    if !warnings.is_empty() {
+        eprintln!("{}", warnings.render(loader.get_sources()));
+    }
+}
+
+// Loads the packages of the datablaze manage.blz belongs to. The problems
+// in them are rendered along with their code.
+pub fn load_packages(manager_file: &Path, manage_config: &ManageConfig) -> PackageLoader {
+    let packages_dir = manage_config.get_packages_dir(manager_file);
+    let mut loader = PackageLoader::new(packages_dir.clone().unwrap_or_default());
+    if packages_dir.is_some() {
+        if let Err(error) = loader.load_packages() {
+            exit_with_error(error, loader.get_sources());
+        }
+    }
+    loader
+}
+
+// Runs manage.blz after the packages it imports, the way the server starts a datablaze.
+pub fn run_manage_file(loader: &mut PackageLoader, manager_file: &Path) -> Result<Value> {
+    let index = loader.load_entry(manager_file)?;
+    print_warnings(loader);
+    loader.execute_module(index, &mut Executor::new())
+}
+
+// Code given to the shell can import the packages of the datablaze given by -blz_file,
+// or of the default one when it exists.
+fn load_shell_packages(args: &[String]) -> Result<PackageLoader> {
+    let config = Config::parse_arguments(args.to_vec())?;
+    if !Config::blz_exists(&config.manager_file) {
+        return Ok(PackageLoader::new(PathBuf::new()));
+    }
+    let manage_config = ManageConfig::load(&config.manager_file)?;
+    Ok(load_packages(
+        Path::new(&config.manager_file),
+        &manage_config,
+    ))
+}
+
+// blaze parser [--emit=json|sexpr|tokens] [path]
+// Without an emit format only the number of parsed statements is printed.
+fn parse_with_console(args: &[String]) -> Result<()> {
+    let mut emit = None;
+    let mut path = None;
+    for arg in args {
+        match arg.strip_prefix("--emit=") {
+            Some(format) => emit = Some(format),
+            None => path = Some(arg),
+        }
+    }
+    if emit.is_some_and(|format| !["json", "sexpr", "tokens"].contains(&format)) {
+        eprintln!("Unknown emit format, expected json, sexpr or tokens");
+        std::process::exit(1);
+    }
+    let (code, code_source) = match path {
+        Some(path) => (fs::read_to_string(path)?, path.to_string()),
+        None => (read_stdin()?, SHELL_CODE_SOURCE.to_string()),
+    };
+    let mut sources = SourceMap::new();
+    sources.add(code_source.clone(), code.clone());
+
+    let tokens = analyze_lexically(code, &code_source)
+        .unwrap_or_else(|diagnostic| exit_with_error(diagnostic.into(), &sources));
+    if emit == Some("tokens") {
+        for token in tokens {
+            println!(
+                "{}:{} {}..{} {} {}",
+                token.span.line,
+                token.span.column,
+                token.span.start,
+                token.span.stop,
+                token.token_type,
+                token.value
+            );
+        }
+        return Ok(());
+    }
+    let body = parse_tokens(tokens, &code_source)
+        .unwrap_or_else(|diagnostics| exit_with_error(diagnostics.into(), &sources));
+    match emit {
+        Some("json") => println!("{}", Tree::from_body(&body).to_json()),
+        Some("sexpr") => println!("{}", Tree::from_body(&body).to_sexpr()),
+        _ if !body.nodes.is_empty() => println!(
+            "Parsing successfully completed! Nodes Count: {}",
+            body.nodes.len()
+        ),
+        _ => {}
+    }
+    Ok(())
+}
+
+// Piped input is read to the end, while a terminal gives a single line.
+fn read_stdin() -> io::Result<String> {
+    if io::stdin().is_terminal() {
+        return input_text();
+    }
+    io::read_to_string(io::stdin())
+}
+
+// Paths can be files or directories with .blz files inside.
+fn format_files(args: &[String]) -> Result<()> {
+    let check = args.iter().any(|arg| arg == "--check");
+    let paths: Vec<&String> = args.iter().filter(|arg| *arg != "--check").collect();
+    if paths.is_empty() {
+        eprintln!("Usage: blaze fmt [--check] <paths>");
+        std::process::exit(1);
+    }
+    let mut files: Vec<PathBuf> = vec![];
+    for path in paths {
+        let path = Path::new(path);
+        match path.is_dir() {
+            true => files.extend(loader::find_blaze_files(path)?),
+            false => files.push(path.to_path_buf()),
+        }
+    }
+
+    let mut is_failed = false;
+    for file in files {
+        let code = fs::read_to_string(&file)?;
+        let code_source = file.display().to_string();
+        let formatted_code = match formatter::format_code(&code, &code_source) {
+            Ok(formatted_code) => formatted_code,
+            Err(diagnostics) => {
+                let mut sources = SourceMap::new();
+                sources.add(code_source, code);
+                eprintln!("{}", diagnostics.render(&sources));
+                is_failed = true;
+                continue;
+            }
+        };
+        if formatted_code == code {
+            continue;
+        }
+        if check {
+            println!("{} is not formatted", code_source);
+            is_failed = true;
+        } else {
+            fs::write(&file, formatted_code)?;
+            println!("Formatted {}", code_source);
+        }
+    }
+    if is_failed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 fn input_text() -> io::Result<String> {
     let mut code_to_parse = String::new();
     std::io::stdin().read_line(&mut code_to_parse)?;
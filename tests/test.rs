@@ -1,8 +1,35 @@
 use blaze::db::create_db;
+use blaze::db::manage::ManageConfig;
+use blaze::scripting::ast::binary_operator::BinaryOperatorNode;
+use blaze::scripting::ast::body::BodyNode;
+use blaze::scripting::ast::call::{CallNode, CallType};
+use blaze::scripting::ast::dump::Tree;
+use blaze::scripting::ast::enum_declaration::EnumDeclarationNode;
+use blaze::scripting::ast::expression::ExpressionNode;
+use blaze::scripting::ast::function_declaration::FunctionDeclarationNode;
+use blaze::scripting::ast::identifier::IdentifierNode;
+use blaze::scripting::ast::number::NumberNode;
+use blaze::scripting::ast::table_declaration::{
+    ColumnType, Constraint, ReferenceModifier, TableDeclarationNode,
+};
+use blaze::scripting::ast::variable_declaration::VariableDeclaration;
+use blaze::scripting::ast::visitor::{walk_expression_mut, Visitor, VisitorMut};
+use blaze::scripting::diagnostic::{Diagnostic, DiagnosticCode, Diagnostics, Severity};
+use blaze::scripting::executor::Executor;
+use blaze::scripting::formatter::format_code;
 use blaze::scripting::lexer::Lexer;
+use blaze::scripting::loader::{PackageLoader, SymbolKind};
+use blaze::scripting::number::Number;
 use blaze::scripting::parser::Parser;
-use blaze::scripting::tokens::TokenType;
+use blaze::scripting::resolver::Resolver;
+use blaze::scripting::source_map::SourceMap;
+use blaze::scripting::span::Span;
+use blaze::scripting::tokens::{TokenType, FORMULA_TOKENS};
+use blaze::scripting::type_checker::{Type, TypeChecker};
+use blaze::scripting::value::{Fields, Value};
+use blaze::server::config::Config;
 use blaze::server::headers;
+use blaze::shell::handling;
 
 #[test]
 fn test_lexer() {
@@ -19,23 +46,117 @@ fn test_lexer() {
 
     let actual_token_types: Vec<TokenType> = tokens
         .iter()
-        .map(|token| TokenType::try_from(token.token_type.clone()).unwrap())
+        .map(|token| token.token_type.clone())
         .collect();
 
     assert_eq!(actual_token_types, expected_tokens);
 }
 
+fn token_types(code: &str) -> Vec<TokenType> {
+    Lexer::new(code.to_string())
+        .analyze()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|token| token.token_type)
+        .collect()
+}
+
+#[test]
+fn test_lexer_longest_match() {
+    assert_eq!(
+        token_types("a>=b!=c&&d||!e&f<=g==h"),
+        vec![
+            TokenType::Alphanumeric,
+            TokenType::GreaterOrEqual,
+            TokenType::Alphanumeric,
+            TokenType::NotEqualSign,
+            TokenType::Alphanumeric,
+            TokenType::And,
+            TokenType::Alphanumeric,
+            TokenType::Or,
+            TokenType::Negotion,
+            TokenType::Alphanumeric,
+            TokenType::Link,
+            TokenType::Alphanumeric,
+            TokenType::LessOrEqual,
+            TokenType::Alphanumeric,
+            TokenType::EqualSign,
+            TokenType::Alphanumeric,
+        ]
+    );
+    assert_eq!(
+        token_types("iffy if_ if 1.5.x ++x-=2"),
+        vec![
+            TokenType::Alphanumeric,
+            TokenType::Alphanumeric,
+            TokenType::If,
+            TokenType::Number,
+            TokenType::Dot,
+            TokenType::Alphanumeric,
+            TokenType::Increment,
+            TokenType::Alphanumeric,
+            TokenType::SubtractAssign,
+            TokenType::Number,
+        ]
+    );
+    let tokens = Lexer::new(r#"x = "a \"quoted\" \\" + """#.to_string())
+        .analyze()
+        .unwrap();
+    assert_eq!(tokens[2].value, r#""a \"quoted\" \\""#);
+    assert_eq!(tokens[4].value, r#""""#);
+    assert!(token_types("x = \"unterminated").is_empty());
+    assert!(token_types("x = 1 $ 2").is_empty());
+}
+
+#[test]
+fn test_token_spans() {
+    let code = "fin имя = \"héllo\";\n  имя + \"→\" /* two\nlines */ ;x";
+    let spans: Vec<(String, Span)> = Lexer::new(code.to_string())
+        .analyze()
+        .unwrap()
+        .into_iter()
+        .map(|token| (token.value, token.span))
+        .collect();
+    let expected_spans = [
+        ("fin", Span::new(0, 3, 1, 1)),
+        ("имя", Span::new(4, 10, 1, 5)),
+        ("=", Span::new(11, 12, 1, 9)),
+        ("\"héllo\"", Span::new(13, 21, 1, 11)),
+        (";", Span::new(21, 22, 1, 18)),
+        ("имя", Span::new(25, 31, 2, 3)),
+        ("+", Span::new(32, 33, 2, 7)),
+        ("\"→\"", Span::new(34, 39, 2, 9)),
+        (";", Span::new(56, 57, 3, 10)),
+        ("x", Span::new(57, 58, 3, 11)),
+    ];
+    for ((value, span), (expected_value, expected_span)) in spans.iter().zip(expected_spans) {
+        assert_eq!((value.as_str(), *span), (expected_value, expected_span));
+    }
+    assert_eq!(spans.len(), expected_spans.len());
+    for (value, span) in &spans {
+        assert_eq!(&code[span.start..span.stop], value);
+    }
+
+    assert_eq!(
+        execute("fin имя = \"héllo\"; имя + \" wörld\"").unwrap(),
+        Value::String("héllo wörld".to_string())
+    );
+    assert!(token_types("fin ü = \"ü\"ü").is_empty());
+}
+
 fn parser(code: &str) -> std::io::Result<bool> {
     let mut code_lexer = Lexer::new(code.to_string());
     let code_source = String::from("Tests");
     code_lexer.get_context().code_source = code_source.clone();
-    let tokens = code_lexer.analyze()?;
+    let Ok(tokens) = code_lexer.analyze() else {
+        return Ok(false);
+    };
 
-    let mut code_parser = Parser::new(tokens.clone());
+    let mut code_parser = Parser::new(tokens);
     code_parser.get_context().code_source = code_source;
     let ast = code_parser.parse();
 
-    Ok(ast.is_ok() && !tokens.is_empty() && !ast?.nodes.is_empty())
+    Ok(ast.is_ok_and(|ast| !ast.nodes.is_empty()))
 }
 
 #[test]
@@ -53,12 +174,1929 @@ fn test_parser() {
     .unwrap());
 }
 
+fn execute(code: &str) -> std::io::Result<Value> {
+    let body = resolved(code)?;
+    Executor::new().execute(&body)
+}
+
+#[test]
+fn test_executor() {
+    assert_eq!(
+        execute("mut x = 2; x = x * 3; x").unwrap(),
+        Value::Number(Number::Int(6))
+    );
+    assert_eq!(
+        execute(r#"fin greeting = "Hello, " + "\"Blaze\""; greeting"#).unwrap(),
+        Value::String("Hello, \"Blaze\"".to_string())
+    );
+    assert_eq!(
+        execute("function add(a: float, b: float = 10) { a + b }; add(1) + add(1, b = 2)").unwrap(),
+        Value::Number(Number::Int(14))
+    );
+    assert_eq!(
+        execute("function fact(n: uint) { if n < 2 { 1 } else { n * fact(n - 1) } }; fact(5)")
+            .unwrap(),
+        Value::Number(Number::Int(120))
+    );
+    assert_eq!(
+        execute(
+            "mut i = 0; mut total = 0; while true { ++i; if i > 5 { break }; if i < 4 { if i > 2 { continue } }; total = total + i }; total"
+        )
+        .unwrap(),
+        Value::Number(Number::Int(12))
+    );
+    assert_eq!(
+        execute(r#"function sign(a: int) { if a > 0 { return "positive" }; "other" }; sign(3)"#)
+            .unwrap(),
+        Value::String("positive".to_string())
+    );
+    assert_eq!(
+        execute("mut x = 1; { mut x = 5; x = 6 }; x").unwrap(),
+        Value::Number(Number::Int(1))
+    );
+    assert!(execute("function f() { x = 2 }; fin x = 1; f()").is_err());
+    assert!(execute("break").is_err());
+    assert!(execute("function f(a: int) { a }; f()").is_err());
+    assert!(execute("function f() { f() }; f()").is_err());
+    assert!(execute("!1").is_err());
+}
+
+#[test]
+fn test_operator_precedence() {
+    assert_eq!(execute("1 + 2 * 3").unwrap(), Value::Number(Number::Int(7)));
+    assert_eq!(execute("2 * 3 + 1").unwrap(), Value::Number(Number::Int(7)));
+    assert_eq!(
+        execute("10 - 4 - 3").unwrap(),
+        Value::Number(Number::Int(3))
+    );
+    assert_eq!(
+        execute("16 / 4 / 2").unwrap(),
+        Value::Number(Number::Float(2.0))
+    );
+    assert_eq!(
+        execute("2 ^ 3 ^ 2").unwrap(),
+        Value::Number(Number::Float(512.0))
+    );
+    assert_eq!(
+        execute("2 * 3 ^ 2").unwrap(),
+        Value::Number(Number::Float(18.0))
+    );
+    assert_eq!(
+        execute("1 + 2 >= 3 && 4 != 5 || false").unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        execute("false && true || true").unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(execute("!false == true").unwrap(), Value::Boolean(true));
+    assert_eq!(
+        execute("-2 ^ 2").unwrap(),
+        Value::Number(Number::Float(-4.0))
+    );
+    assert_eq!(
+        execute("2 ^ -1 - -1").unwrap(),
+        Value::Number(Number::Float(1.5))
+    );
+    assert_eq!(
+        execute("fin x = 3; -x").unwrap(),
+        Value::Number(Number::Int(-3))
+    );
+    assert_eq!(
+        execute("fin a = 1; fin b = 2; -(a + b) * 2").unwrap(),
+        Value::Number(Number::Int(-6))
+    );
+    assert_eq!(execute("- -1").unwrap(), Value::Number(Number::Int(1)));
+    assert_eq!(
+        execute("fin x = 2; - -x ^ 2").unwrap(),
+        Value::Number(Number::Float(4.0))
+    );
+    assert_eq!(
+        execute("mut a = 1; mut b = 2; a = b = 3 * 2 - 1; a + b").unwrap(),
+        Value::Number(Number::Int(10))
+    );
+}
+
+fn number(value: i64) -> Box<dyn ExpressionNode> {
+    Box::new(NumberNode::new(Number::Int(value)))
+}
+
+fn identifier(name: &str) -> Box<dyn ExpressionNode> {
+    Box::new(IdentifierNode::new(name.to_string()))
+}
+
+#[test]
+fn test_ast_shapes() {
+    let body = parse("fin total = 1 + 2 * price; total").unwrap();
+    let mut expected_body = BodyNode::new();
+    expected_body.add_node(Box::new(VariableDeclaration::new(
+        "total".to_string(),
+        false,
+        None,
+        Some(Box::new(BinaryOperatorNode::new(
+            TokenType::Addition,
+            number(1),
+            Box::new(BinaryOperatorNode::new(
+                TokenType::Multiplication,
+                number(2),
+                identifier("price"),
+            )),
+        ))),
+    )));
+    expected_body.add_node(identifier("total"));
+    assert_eq!(body, expected_body);
+    assert_eq!(body.clone(), body);
+
+    assert_ne!(parse("1 - 2").unwrap(), parse("1 + 2").unwrap());
+    assert_ne!(parse("1").unwrap(), parse("\"1\"").unwrap());
+    assert_eq!(
+        parse("(1 + 2) * 3").unwrap().nodes[0].clone(),
+        parse("(1 + 2)*3;").unwrap().nodes[0]
+    );
+    let debugged = format!("{:?}", parse("!x").unwrap());
+    assert!(debugged.contains("UnaryOperatorNode { operator: Negotion"));
+}
+
+struct IdentifierCollector(Vec<String>);
+
+impl Visitor for IdentifierCollector {
+    fn visit_identifier(&mut self, node: &IdentifierNode) {
+        self.0.push(node.get_name().to_string());
+    }
+}
+
+struct AdditionFolder;
+
+impl VisitorMut for AdditionFolder {
+    fn visit_expression_mut(&mut self, node: &mut Box<dyn ExpressionNode>) {
+        walk_expression_mut(self, node);
+        let Some(operator) = node.downcast_ref::<BinaryOperatorNode>() else {
+            return;
+        };
+        let left = operator.get_left_operand().downcast_ref::<NumberNode>();
+        let right = operator.get_right_operand().downcast_ref::<NumberNode>();
+        if let (TokenType::Addition, Some(left), Some(right)) =
+            (operator.get_operator(), left, right)
+        {
+            let sum = left.get_value().checked_add(right.get_value()).unwrap();
+            *node = Box::new(NumberNode::new(sum));
+        }
+    }
+}
+
+#[test]
+fn test_visitors() {
+    let body = parse("fin x = a + (1 + 2) * f(b, c = 3 + 4); while x { y }").unwrap();
+    let mut collector = IdentifierCollector(vec![]);
+    collector.visit_body(&body);
+    assert_eq!(collector.0, vec!["a", "f", "b", "x", "y"]);
+
+    let mut body = parse("1 + 2 + x; f(1 + 2, c = { 3 + 4 }); table t { n: int = 1 + 1 }").unwrap();
+    AdditionFolder.visit_body_mut(&mut body);
+    assert_eq!(
+        body,
+        parse("3 + x; f(3, c = { 7 }); table t { n: int = 2 }").unwrap()
+    );
+}
+
+#[test]
+fn test_ast_dump() {
+    let tree = Tree::from_body(&parse("while x { ++x }").unwrap());
+    assert_eq!(
+        tree.to_sexpr(),
+        r#"(BodyNode
+  :statements ((WhileLoopNode
+      :span (span 0 15 1 1)
+      :condition (IdentifierNode :span (span 6 7 1 7) :name "x")
+      :body (BodyNode
+        :span (span 8 15 1 9)
+        :statements ((UnaryOperatorNode
+            :span (span 10 13 1 11)
+            :operator "++"
+            :side "left"
+            :operand (IdentifierNode :span (span 12 13 1 13) :name "x")))))))"#
+    );
+
+    let tree = Tree::from_body(&parse("f(1, c = \"a\\n\")").unwrap());
+    assert_eq!(
+        tree.to_json(),
+        r#"{
+  "type": "BodyNode",
+  "statements": [
+    {
+      "type": "CallNode",
+      "span": {"start": 0, "stop": 15, "line": 1, "column": 1},
+      "call_type": "function",
+      "callee": {
+        "type": "IdentifierNode",
+        "span": {"start": 0, "stop": 1, "line": 1, "column": 1},
+        "name": "f"
+      },
+      "arguments": [
+        {
+          "type": "Parameter",
+          "name": null,
+          "datatype": null,
+          "value": {
+            "type": "NumberNode",
+            "span": {"start": 2, "stop": 3, "line": 1, "column": 3},
+            "kind": "int",
+            "value": 1
+          }
+        },
+        {
+          "type": "Parameter",
+          "name": "c",
+          "datatype": null,
+          "value": {
+            "type": "StringNode",
+            "value": "a\n"
+          }
+        }
+      ]
+    }
+  ]
+}"#
+    );
+    assert_eq!(
+        Tree::from_body(&BodyNode::new()).to_sexpr(),
+        "(BodyNode :statements ())"
+    );
+
+    // Expressions carry their own spans, which statements replace.
+    let tree = Tree::from_body(&parse("fin y = (a + -2) * b[0]").unwrap());
+    assert_eq!(
+        tree.to_sexpr(),
+        r#"(BodyNode
+  :statements ((VariableDeclaration
+      :span (span 0 23 1 1)
+      :name "y"
+      :mutable false
+      :datatype nil
+      :value (BinaryOperatorNode
+        :span (span 8 23 1 9)
+        :operator "*"
+        :left (ParenthesizedNode
+          :span (span 8 16 1 9)
+          :expression (BinaryOperatorNode
+            :span (span 9 15 1 10)
+            :operator "+"
+            :left (IdentifierNode :span (span 9 10 1 10) :name "a")
+            :right (NumberNode :span (span 13 15 1 14) :kind "int" :value -2)))
+        :right (CallNode
+          :span (span 19 23 1 20)
+          :call_type "position"
+          :callee (IdentifierNode :span (span 19 20 1 20) :name "b")
+          :arguments ((Parameter
+              :name nil
+              :datatype nil
+              :value (NumberNode :span (span 21 22 1 22) :kind "int" :value 0))))))))"#
+    );
+    let tree = Tree::from_body(&parse("1 + 2").unwrap());
+    assert!(tree.to_json().contains(
+        r#""type": "BinaryOperatorNode",
+      "span": {"start": 0, "stop": 5, "line": 1, "column": 1},
+      "operator": "+""#
+    ));
+}
+
+#[test]
+fn test_parenthesized_formulas() {
+    assert_eq!(
+        execute("(1 + 2) * 3").unwrap(),
+        Value::Number(Number::Int(9))
+    );
+    assert_eq!(
+        execute("2 ^ (1 + 1) * ((4))").unwrap(),
+        Value::Number(Number::Float(16.0))
+    );
+    assert_eq!(
+        execute("10 - (4 - 3)").unwrap(),
+        Value::Number(Number::Int(9))
+    );
+    assert_eq!(
+        execute("function add(a: float, b: float) { a + b }; add((1 + 2) * 2, b = (3 - 1))")
+            .unwrap(),
+        Value::Number(Number::Int(8))
+    );
+    assert_eq!(
+        execute(r#"if (1 < 2) && (2 < 3) "yes" else "no""#).unwrap(),
+        Value::String("yes".to_string())
+    );
+    assert_eq!(
+        execute("mut i = 0; while (i < 3) { ++i }; i").unwrap(),
+        Value::Number(Number::Int(3))
+    );
+    assert!(!parser("(1 + 2").unwrap());
+    assert!(!parser("(1 + 2))").unwrap());
+    assert!(!parser("()").unwrap());
+
+    let nested = |depth: usize, opening: &str, closing: &str| {
+        format!("{}1{}", opening.repeat(depth), closing.repeat(depth))
+    };
+    assert!(parse(&nested(64, "(", ")")).is_ok());
+    let too_deep = diagnostic(&nested(5000, "(", ")"));
+    assert_eq!(too_deep.code, DiagnosticCode::NestingTooDeep);
+    assert_eq!(too_deep.span, Span::new(64, 65, 1, 65));
+    for (opening, closing) in [
+        ("[", "]"),
+        ("{", "}"),
+        ("f(", ")"),
+        ("!(", ")"),
+        ("2 ^ ", ""),
+    ] {
+        let code = format!("fin x = {}; 2", nested(5000, opening, closing));
+        let diagnostics = diagnostics(&code);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::NestingTooDeep);
+    }
+}
+
+fn parse(code: &str) -> std::io::Result<BodyNode> {
+    let tokens = Lexer::new(code.to_string()).analyze()?;
+    Ok(Parser::new(tokens).parse()?)
+}
+
+// Parsed with the calls of types marked, whatever the resolver reports.
+fn resolved(code: &str) -> std::io::Result<BodyNode> {
+    let mut body = parse(code)?;
+    Resolver::new("void").resolve(&mut body);
+    Ok(body)
+}
+
+fn diagnostics(code: &str) -> Diagnostics {
+    let Err(error) = parse(code) else {
+        panic!("'{}' is parsed without errors", code);
+    };
+    Diagnostics::from_io_error(&error).unwrap().clone()
+}
+
+fn diagnostic(code: &str) -> Diagnostic {
+    diagnostics(code)[0].clone()
+}
+
+#[test]
+fn test_diagnostics() {
+    let unrecognized = diagnostic("x = 1 $ 2");
+    assert_eq!(unrecognized.code, DiagnosticCode::UnrecognizedCharacter);
+    assert_eq!(unrecognized.span, Span::new(6, 7, 1, 7));
+    assert_eq!(
+        diagnostic("x =\n \"unterminated").code,
+        DiagnosticCode::UnterminatedString
+    );
+    assert_eq!(diagnostic("1a").code, DiagnosticCode::AlphanumericInNumber);
+
+    let immutable = resolved_diagnostics("fin x = 1; x = 2")[0].clone();
+    assert_eq!(immutable.severity, Severity::Error);
+    assert_eq!(immutable.code, DiagnosticCode::ImmutableAssignment);
+    assert_eq!(immutable.span, Span::new(11, 12, 1, 12));
+    assert_eq!(immutable.labels[0].span, Span::new(4, 5, 1, 5));
+    assert_eq!(
+        immutable.to_string(),
+        "error[R0005]: cannot assign twice to immutable variable 'x'\n --> void:1:12\n  = note: 'x' is declared here, at void:1:5"
+    );
+
+    let unclosed = diagnostic("(1 + 2");
+    assert_eq!(unclosed.code, DiagnosticCode::UnexpectedEnd);
+    assert_eq!(unclosed.expected_tokens, vec![TokenType::RPar]);
+    assert_eq!(unclosed.span, Span::new(6, 6, 1, 7));
+
+    // The expected tokens are listed in the same order every time.
+    let missing_operand = diagnostic("1 + ;");
+    assert_eq!(missing_operand.code, DiagnosticCode::UnexpectedToken);
+    assert_eq!(missing_operand.expected_tokens, FORMULA_TOKENS.to_vec());
+    assert_eq!(diagnostic("1 + ;"), missing_operand);
+
+    let duplicate = diagnostic("enum Side { Left, Right, Left }");
+    assert_eq!(duplicate.code, DiagnosticCode::DuplicateVariant);
+    assert_eq!(duplicate.code.as_str(), "P0003");
+    assert_eq!(duplicate.span.column, 26);
+    assert_eq!(duplicate.labels[0].span.column, 13);
+
+    let executed = execute("(1 + 2").unwrap_err();
+    assert_eq!(
+        Diagnostics::from_io_error(&executed).unwrap()[0].code,
+        DiagnosticCode::UnexpectedEnd
+    );
+    assert!(Diagnostics::from_io_error(&execute("!1").unwrap_err()).is_none());
+}
+
+#[test]
+fn test_source_snippets() {
+    let sources = |code: &str| {
+        let mut sources = SourceMap::new();
+        sources.add("void", code);
+        sources
+    };
+    let render = |code: &str| diagnostics(code).render_plain(&sources(code));
+    let immutable = "fin x = 1;\nx = 2";
+    assert_eq!(
+        resolved_diagnostics(immutable).render_plain(&sources(immutable)),
+        [
+            "error[R0005]: cannot assign twice to immutable variable 'x'",
+            " --> void:2:1",
+            "  |",
+            "1 | fin x = 1;",
+            "  |     - 'x' is declared here",
+            "2 | x = 2",
+            "  | ^ cannot assign twice",
+        ]
+        .join("\n")
+    );
+    assert_eq!(
+        render("fin x = 1 fin y = 2"),
+        [
+            "error[P0001]: expected ';', found 'fin'",
+            " --> void:1:11",
+            "  |",
+            "1 | fin x = 1 fin y = 2",
+            "  |           ^^^ expected ';' here",
+        ]
+        .join("\n")
+    );
+    let rendered = render("enum Side {\n\tLeft,\n\tRight,\n\tLeft\n}\n\n\n\n\n\n(1 + 2");
+    assert!(rendered
+        .contains("2 | \tLeft,\n  | \t---- first declared here\n...\n4 | \tLeft\n  | \t^^^^"));
+    assert!(rendered.contains("11 | (1 + 2\n   |       ^ expected ')' here"));
+}
+
+#[test]
+fn test_error_recovery() {
+    let codes = |code: &str| -> Vec<DiagnosticCode> {
+        diagnostics(code)
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect()
+    };
+    assert_eq!(
+        codes("fin x = ; mut y = 2; y = )"),
+        vec![
+            DiagnosticCode::UnexpectedToken,
+            DiagnosticCode::UnexpectedToken
+        ]
+    );
+    // Errors inside brackets don't make the brackets around them look unbalanced.
+    let recovered = diagnostics(
+        "function f() {\n  1 + ;\n  fin a = 1;\n  a = )\n};\ntable t { name str };\nfin ok = 1;\nok = ;",
+    );
+    let lines: Vec<usize> = recovered
+        .iter()
+        .map(|diagnostic| diagnostic.span.line)
+        .collect();
+    assert_eq!(lines, vec![2, 4, 6, 8]);
+    assert_eq!(
+        codes("1; }; 2 +"),
+        vec![
+            DiagnosticCode::UnexpectedToken,
+            DiagnosticCode::UnexpectedEnd
+        ]
+    );
+    assert_eq!(codes("enum E { A, A }; enum F { B, B }").len(), 2);
+    assert!(parse("").unwrap().nodes.is_empty());
+    assert!(parse("fin x = 1; { x }").is_ok());
+}
+
+#[test]
+fn test_table_declarations() {
+    let body = parse(
+        r#"
+        table countries {
+            name: str <=50,
+            alpha2: str 2,
+            geolocation: geo;
+        };
+
+        table accounts: uuid {
+            name: str <=30 = format("User{}", self.id),
+            gender: Gender = Gender.Unspecified,
+            age: int >0 <100,
+            country: &countries?,
+            created_at: datetime = "now";
+        }
+
+        table shopping_cart {
+            account: &accounts!,
+            added_products: &products[],
+            seller: &accounts,
+            price: decimal >= 0.00m <= 18446744073709551615,
+        };
+        "#,
+    )
+    .unwrap();
+    assert_eq!(body.nodes.len(), 3);
+
+    let countries = body.nodes[0]
+        .downcast_ref::<TableDeclarationNode>()
+        .unwrap();
+    assert_eq!(countries.get_name(), "countries");
+    assert_eq!(countries.get_primary_key(), None);
+    assert_eq!(countries.get_columns().len(), 3);
+    let name = countries.get_column("name").unwrap();
+    assert_eq!(
+        name.get_column_type(),
+        &ColumnType::Datatype("str".to_string())
+    );
+    assert_eq!(
+        name.get_constraints(),
+        &vec![Constraint::Bound(TokenType::LessOrEqual, Number::Int(50))]
+    );
+    assert_eq!(
+        countries.get_column("alpha2").unwrap().get_constraints(),
+        &vec![Constraint::Exact(Number::Int(2))]
+    );
+
+    let accounts = body.nodes[1]
+        .downcast_ref::<TableDeclarationNode>()
+        .unwrap();
+    assert_eq!(accounts.get_primary_key(), Some("uuid"));
+    assert!(accounts.get_column("name").unwrap().get_default().is_some());
+    assert!(accounts
+        .get_column("gender")
+        .unwrap()
+        .get_default()
+        .is_some());
+    assert!(accounts.get_column("age").unwrap().get_default().is_none());
+    assert_eq!(
+        accounts.get_column("age").unwrap().get_constraints(),
+        &vec![
+            Constraint::Bound(TokenType::Greater, Number::Int(0)),
+            Constraint::Bound(TokenType::Less, Number::Int(100))
+        ]
+    );
+    assert_eq!(
+        accounts.get_column("country").unwrap().get_column_type(),
+        &ColumnType::Reference("countries".to_string(), ReferenceModifier::Optional)
+    );
+
+    let shopping_cart = body.nodes[2]
+        .downcast_ref::<TableDeclarationNode>()
+        .unwrap();
+    let reference_modifiers: Vec<&ColumnType> = shopping_cart
+        .get_columns()
+        .iter()
+        .take(3)
+        .map(|column| column.get_column_type())
+        .collect();
+    assert_eq!(
+        reference_modifiers,
+        vec![
+            &ColumnType::Reference("accounts".to_string(), ReferenceModifier::Unique),
+            &ColumnType::Reference("products".to_string(), ReferenceModifier::Many),
+            &ColumnType::Reference("accounts".to_string(), ReferenceModifier::Single),
+        ]
+    );
+    assert_eq!(
+        shopping_cart.get_column("price").unwrap().get_constraints(),
+        &vec![
+            Constraint::Bound(TokenType::GreaterOrEqual, Number::Int(0)),
+            Constraint::Bound(TokenType::LessOrEqual, Number::Uint(u64::MAX))
+        ]
+    );
+    let price_bounds: Vec<String> = shopping_cart
+        .get_column("price")
+        .unwrap()
+        .get_constraints()
+        .iter()
+        .map(|constraint| constraint.to_string())
+        .collect();
+    assert_eq!(price_bounds, vec![">= 0.00m", "<= 18446744073709551615"]);
+
+    assert!(!parser("table countries { name: str <= }").unwrap());
+    assert!(!parser("table countries { name str }").unwrap());
+    assert!(!parser("table countries { name: &countries[ }").unwrap());
+    assert!(!parser("table countries { name: str").unwrap());
+}
+
+#[test]
+fn test_enum_declarations() {
+    let enums = r#"
+        enum Gender: str {
+            Male,
+            Female,
+            Other,
+            Unspecified
+        };
+        enum Level: uint { Low, Medium, High, };
+    "#;
+    assert_eq!(
+        execute(&format!("{} Gender.Unspecified", enums)).unwrap(),
+        Value::String("Unspecified".to_string())
+    );
+    assert!(matches!(
+        execute(&format!("{} Level.High", enums)).unwrap(),
+        Value::Number(Number::Uint(2))
+    ));
+    assert!(matches!(
+        execute(&format!(
+            "{} enum Sign: int {{ Minus, Plus }}; Sign.Plus",
+            enums
+        ))
+        .unwrap(),
+        Value::Number(Number::Int(1))
+    ));
+    assert_eq!(
+        execute(&format!("{} fin Gender = 1; Gender", enums)).unwrap(),
+        Value::Number(Number::Int(1))
+    );
+    assert!(execute(&format!("{} Gender.Robot", enums)).is_err());
+    assert!(execute("enum Point: float { X, Y }").is_err());
+    assert!(!parser("enum Gender: str { Male, Male }").unwrap());
+    assert!(!parser("enum Gender: str { Male Female }").unwrap());
+
+    let body = parse(&format!(
+        "{} table accounts {{ name: str, gender: Gender = Gender.Unspecified, level: Level = Level.Medium }}",
+        enums
+    ))
+    .unwrap();
+    let mut executor = Executor::new();
+    executor.execute(&body).unwrap();
+    let row = executor
+        .create_row(
+            "accounts",
+            vec![
+                ("name".to_string(), Value::String("Alice".to_string())),
+                ("level".to_string(), Value::Number(Number::Uint(1))),
+            ],
+        )
+        .unwrap();
+    assert_eq!(
+        row.get_field("gender"),
+        Some(&Value::String("Unspecified".to_string()))
+    );
+    let row = executor
+        .create_row(
+            "accounts",
+            vec![("name".to_string(), Value::String("Bob".to_string()))],
+        )
+        .unwrap();
+    assert!(matches!(
+        row.get_field("level"),
+        Some(Value::Number(Number::Uint(1)))
+    ));
+    let invalid_rows: Vec<Fields> = vec![
+        vec![("gender".to_string(), Value::String("Robot".to_string()))],
+        vec![("gender".to_string(), Value::Number(Number::Int(0)))],
+        vec![("level".to_string(), Value::Number(Number::Int(3)))],
+        vec![("nickname".to_string(), Value::Null)],
+    ];
+    for fields in invalid_rows {
+        assert!(executor.create_row("accounts", fields).is_err());
+    }
+}
+
+#[test]
+fn test_events() {
+    let body = parse(
+        r#"
+        table carts { products: int = 0, last_update: str = "never" };
+        mut deleted_products = 0;
+        event cart_change (&carts.products, "change") {
+            &carts.last_update = "now";
+        };
+        event cart_shrink (&carts.products, "update") {
+            if new.products < old.products { &carts.last_update = "shrunk" }
+        };
+        event cart_delete (&carts, "delete") { deleted_products = old.products };
+        "#,
+    )
+    .unwrap();
+    let mut executor = Executor::new();
+    executor.execute(&body).unwrap();
+
+    let products = |count: i64| vec![("products".to_string(), Value::Number(Number::Int(count)))];
+    let row = executor.insert_row("carts", products(3)).unwrap();
+    assert_eq!(
+        row.get_field("last_update"),
+        Some(&Value::String("now".to_string()))
+    );
+    let row = executor.update_row("carts", 0, products(2)).unwrap();
+    assert_eq!(
+        row.get_field("last_update"),
+        Some(&Value::String("shrunk".to_string()))
+    );
+    let last_update = vec![(
+        "last_update".to_string(),
+        Value::String("later".to_string()),
+    )];
+    executor.update_row("carts", 0, last_update).unwrap();
+    assert_eq!(
+        executor.get_rows("carts")[0][1],
+        (
+            "last_update".to_string(),
+            Value::String("later".to_string())
+        )
+    );
+    executor.delete_row("carts", 0).unwrap();
+    assert!(executor.get_rows("carts").is_empty());
+    assert_eq!(
+        executor.get_variable("deleted_products"),
+        Some(Value::Number(Number::Int(2)))
+    );
+    assert!(executor.delete_row("carts", 0).is_err());
+
+    // A failing handler cancels the whole write.
+    let body = parse(
+        r#"
+        table carts { products: int = 0 };
+        event cart_insert (&carts, "insert") { &carts.owner = "nobody" };
+        "#,
+    )
+    .unwrap();
+    let mut executor = Executor::new();
+    executor.execute(&body).unwrap();
+    assert!(executor.insert_row("carts", products(1)).is_err());
+    assert!(executor.get_rows("carts").is_empty());
+
+    // Rows breaking the constraints of their columns are never written.
+    let body = parse(
+        r#"
+        table carts { products: int >= 0 <= 10, code: str 3 = "abc" };
+        mut writes = 0;
+        event cart_write (&carts, "change") { writes += 1 };
+        "#,
+    )
+    .unwrap();
+    let mut executor = Executor::new();
+    executor.execute(&body).unwrap();
+    executor.insert_row("carts", products(10)).unwrap();
+    let error = executor.insert_row("carts", products(11)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Runtime Error: 11 does not satisfy '<= 10' in column 'carts.products'"
+    );
+    assert!(executor.update_row("carts", 0, products(-1)).is_err());
+    let code = vec![("code".to_string(), Value::String("abcd".to_string()))];
+    assert!(executor.update_row("carts", 0, code).is_err());
+    assert_eq!(executor.get_rows("carts").len(), 1);
+    assert_eq!(
+        executor.get_variable("writes"),
+        Some(Value::Number(Number::Int(1)))
+    );
+
+    assert!(execute("event cart_insert (&carts, \"insert\") {}").is_err());
+    assert!(
+        execute("table carts { products: int }; event e (&carts.owner, \"insert\") {}").is_err()
+    );
+    assert!(execute("table carts { products: int }; &carts.products").is_err());
+    assert!(!parser("table carts { products: int }; event e (&carts, \"create\") {}").unwrap());
+}
+
+#[test]
+fn test_iteration_and_compound_assignment() {
+    assert_eq!(
+        execute("mut x = 10; x += 5; x -= 3; x *= 2; x /= 4; x").unwrap(),
+        Value::Number(Number::Int(6))
+    );
+    assert_eq!(
+        execute(r#"mut name = "bla"; name += "ze"; name"#).unwrap(),
+        Value::String("blaze".to_string())
+    );
+    assert!(execute("mut x = 1; x /= 0").is_err());
+    assert!(execute("x of 5 {}").is_err());
+
+    let declarations = parse(
+        r#"
+        table products { name: str, price: float };
+        function get_total_cart_price(products: &products[]): float {
+            mut total: float = 0;
+            product of products {
+                total += product.price;
+            };
+            total
+        };
+        "#,
+    )
+    .unwrap();
+    let queries = parse(
+        r#"
+        fin total = get_total_cart_price(&products);
+        mut cheap = 0;
+        for product of &products {
+            if product.price > 10 { continue };
+            if product.name == "stop" { break };
+            cheap += 1;
+        };
+        "#,
+    )
+    .unwrap();
+    let mut executor = Executor::new();
+    executor.execute(&declarations).unwrap();
+    for (name, price) in [
+        ("pen", 2.0),
+        ("book", 15.5),
+        ("cup", 4.5),
+        ("stop", 1.0),
+        ("hat", 3.0),
+    ] {
+        let fields = vec![
+            ("name".to_string(), Value::String(name.to_string())),
+            ("price".to_string(), Value::Number(Number::Float(price))),
+        ];
+        executor.insert_row("products", fields).unwrap();
+    }
+    executor.execute(&queries).unwrap();
+    assert_eq!(
+        executor.get_variable("total"),
+        Some(Value::Number(Number::Int(26)))
+    );
+    assert_eq!(
+        executor.get_variable("cheap"),
+        Some(Value::Number(Number::Int(2)))
+    );
+
+    for code in [
+        "fin x = 1; x = 2;",
+        "fin x = 1; x += 1;",
+        "fin x = 1; ++x;",
+        "function f(a: int) { a = 1 }",
+        "fin items = [1]; item of items { item -= 1 }",
+    ] {
+        let diagnostics = resolved_diagnostics(code);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::ImmutableAssignment);
+    }
+    assert!(parser("fin x = 1; { mut x = 2; x = 3 }").unwrap());
+    assert!(parser("mut x = 1; x *= 2").unwrap());
+}
+
+#[test]
+fn test_comments() {
+    assert_eq!(
+        execute(
+            "// a line comment\nfin x = 1; // after code\n/* a block /* nested */ comment */ x + /**/ 2"
+        )
+        .unwrap(),
+        Value::Number(Number::Int(3))
+    );
+    assert_eq!(
+        execute("mut x = 6; x /= 2; x // a division before a comment").unwrap(),
+        Value::Number(Number::Int(3))
+    );
+    assert!(!parser("fin x = 1; /* unterminated /* nested */").unwrap());
+
+    let body = parse(
+        "/// Everyone who has ever signed up.\n/// Deleted accounts stay here as well.\ntable accounts { name: str };\n\
+        /// Ignored, as variables aren't documented.\nfin x = 1;\n\
+        ///Levels of access.\nenum Level: uint { Low, High };\n\
+        //// Just a comment.\nfunction f() { 1 };",
+    )
+    .unwrap();
+    assert_eq!(body.nodes.len(), 4);
+    let table = body.nodes[0]
+        .downcast_ref::<TableDeclarationNode>()
+        .unwrap();
+    assert_eq!(
+        table.get_doc(),
+        Some("Everyone who has ever signed up.\nDeleted accounts stay here as well.")
+    );
+    let level = body.nodes[2].downcast_ref::<EnumDeclarationNode>().unwrap();
+    assert_eq!(level.get_doc(), Some("Levels of access."));
+    let function = body.nodes[3]
+        .downcast_ref::<FunctionDeclarationNode>()
+        .unwrap();
+    assert_eq!(function.get_doc(), None);
+}
+
+#[test]
+fn test_formatter() {
+    let format = |code: &str| format_code(code, "void").unwrap();
+    assert_eq!(
+        format("mut x=1;x+=2 ;if x>2 {x=!true} elif x==1 { log(\"a\\n\") } else {  }"),
+        "mut x = 1;\nx += 2;\nif x > 2 {\n    x = !true;\n} elif x == 1 {\n    log(\"a\\n\");\n} else {}\n"
+    );
+    assert_eq!(
+        format("table users : id { id: int, name: str 2 <=30; role: &roles? = \"user\" }"),
+        "table users: id {\n    id: int,\n    name: str 2 <= 30,\n    role: &roles? = \"user\",\n}\n"
+    );
+    assert_eq!(
+        format("manage (cache=true, limit=10);"),
+        "manage (\n    cache = true,\n    limit = 10,\n);\n"
+    );
+
+    let code = "// Shop\npackage shop;\n\n\n/// People\ntable users {\n    id: int, // the key\n\n    // optional\n    email: str,\n    // no more columns\n}\nfor user of users { /* skipped */\n    continue }\n";
+    assert_eq!(
+        format(code),
+        "// Shop\npackage shop;\n\n/// People\ntable users {\n    id: int, // the key\n\n    // optional\n    email: str,\n    // no more columns\n}\nfor user of users {\n    /* skipped */\n    continue;\n}\n"
+    );
+
+    let long_function = "function notify(recipient: str, subject: str, message: str, sender: str, retries: int = 3, delay: int = 0): bool { true }";
+    let formatted_function = format(long_function);
+    assert_eq!(
+        formatted_function,
+        "function notify(\n    recipient: str,\n    subject: str,\n    message: str,\n    sender: str,\n    retries: int = 3,\n    delay: int = 0,\n): bool {\n    true;\n}\n"
+    );
+    assert_eq!(format(&formatted_function), formatted_function);
+    assert_eq!(
+        parse(&formatted_function).unwrap(),
+        parse(long_function).unwrap()
+    );
+
+    let misplaced = format_code("fin y = 1 + /* two */ 2;", "void").unwrap_err();
+    assert_eq!(misplaced[0].code, DiagnosticCode::MisplacedComment);
+    assert_eq!(misplaced[0].span, Span::new(12, 21, 1, 13));
+    assert!(format_code("fin y = ", "void").is_err());
+}
+
+#[test]
+fn test_position_and_type_calls() {
+    let string = |value: &str| Value::String(value.to_string());
+    assert_eq!(execute(r#"fin s = "blaze"; s[0]"#).unwrap(), string("b"));
+    assert_eq!(
+        execute(r#"fin last = 0 - 1; "blaze"[last]"#).unwrap(),
+        string("e")
+    );
+    assert_eq!(execute(r#"fin s = "blaze"; s[1:3]"#).unwrap(), string("la"));
+    assert_eq!(
+        execute(r#"fin s = "blaze"; s[2:] + s[:2]"#).unwrap(),
+        string("azebl")
+    );
+    assert_eq!(execute(r#""blaze"[1:100]"#).unwrap(), string("laze"));
+    assert!(execute(r#""blaze"[5]"#).is_err());
+    assert_eq!(
+        execute(r#"int("3") + 1"#).unwrap(),
+        Value::Number(Number::Int(4))
+    );
+    assert_eq!(
+        execute("uint(2.7)").unwrap(),
+        Value::Number(Number::Uint(2))
+    );
+    assert_eq!(execute("str(1) + str(true)").unwrap(), string("1true"));
+    assert!(execute("uint(0 - 1)").is_err());
+    assert!(execute(r#"int("three")"#).is_err());
+
+    let accounts = r#"table accounts { name: str, role: str = "user" }; "#;
+    let row = Value::Document(vec![
+        ("name".to_string(), string("x")),
+        ("role".to_string(), string("user")),
+    ]);
+    assert_eq!(
+        execute(&format!(r#"{}accounts(name = "x")"#, accounts)).unwrap(),
+        row
+    );
+    assert_eq!(
+        execute(&format!(r#"{}accounts("x")"#, accounts)).unwrap(),
+        row
+    );
+    assert_eq!(
+        execute(&format!(
+            r#"{}fin a = accounts("x"); a["role"][0:2]"#,
+            accounts
+        ))
+        .unwrap(),
+        string("us")
+    );
+    assert!(execute(&format!(r#"{}accounts(email = "x")"#, accounts)).is_err());
+
+    let call = parse("matrix[i][j]").unwrap();
+    let outer = call.nodes[0].downcast_ref::<CallNode>().unwrap();
+    assert_eq!(*outer.get_call_type(), CallType::Position);
+    let inner = outer.get_callee().downcast_ref::<CallNode>().unwrap();
+    assert_eq!(*inner.get_call_type(), CallType::Position);
+    let call = resolved("int(x)").unwrap();
+    let cast = call.nodes[0].downcast_ref::<CallNode>().unwrap();
+    assert_eq!(*cast.get_call_type(), CallType::Type);
+    // Tables are known wherever they are declared, even after the calls.
+    assert_eq!(
+        execute("function mk() { t(a = 1) }; table t { a: int }; mk()").unwrap(),
+        Value::Document(vec![("a".to_string(), Value::Number(Number::Int(1)))])
+    );
+
+    let format = |code: &str| format_code(code, "void").unwrap();
+    assert_eq!(
+        format("items [ 0 ] [1 :2];s[ : 3]"),
+        "items[0][1:2];\ns[:3];\n"
+    );
+
+    assert_eq!(
+        type_errors(r#"fin s = "blaze"; fin c: str = s[1:]; int(s)"#),
+        vec![]
+    );
+    assert_eq!(
+        type_errors(r#"fin s = "blaze"; s["a"]; fin n = 1; n[0]; int(1, 2)"#),
+        vec![
+            (
+                DiagnosticCode::TypeMismatch,
+                "expected 'int', found 'str'".to_string()
+            ),
+            (
+                DiagnosticCode::TypeMismatch,
+                "'int' cannot be indexed".to_string()
+            ),
+            (
+                DiagnosticCode::InvalidArguments,
+                "'int' takes 1 positional argument but 2 were given".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        type_errors(&format!(r#"{}accounts(name = 1, email = "x")"#, accounts)),
+        vec![
+            (
+                DiagnosticCode::TypeMismatch,
+                "expected 'str', found 'int'".to_string()
+            ),
+            (
+                DiagnosticCode::InvalidArguments,
+                "table 'accounts' has no column 'email'".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_array_and_document_literals() {
+    let string = |value: &str| Value::String(value.to_string());
+    assert_eq!(
+        execute("fin x = 2; [1, x, x + 1,]").unwrap(),
+        Value::Array(vec![
+            Value::Number(Number::Int(1)),
+            Value::Number(Number::Int(2)),
+            Value::Number(Number::Int(3))
+        ])
+    );
+    assert_eq!(execute("[]").unwrap(), Value::Array(vec![]));
+    assert_eq!(
+        execute("[[1, 2], [3]][0][1]").unwrap(),
+        Value::Number(Number::Int(2))
+    );
+    assert_eq!(
+        execute("mut total = 0; for item of [1, 2, 3] { total += item }; total").unwrap(),
+        Value::Number(Number::Int(6))
+    );
+
+    let document = r#"fin user = { name: "x", "first tag": null, roles: ["admin"], address: { city: "y" } }; "#;
+    assert_eq!(
+        execute(&format!("{}user", document)).unwrap(),
+        Value::Document(vec![
+            ("name".to_string(), string("x")),
+            ("first tag".to_string(), Value::Null),
+            ("roles".to_string(), Value::Array(vec![string("admin")])),
+            (
+                "address".to_string(),
+                Value::Document(vec![("city".to_string(), string("y"))])
+            ),
+        ])
+    );
+    assert_eq!(
+        execute(&format!("{}user.address.city + user.roles[0]", document)).unwrap(),
+        string("yadmin")
+    );
+    assert_eq!(execute("{:}").unwrap(), Value::Document(vec![]));
+    // Positions and members follow any operand, and items can be assigned.
+    assert_eq!(
+        execute("[1, 2][0] + { a: 1 }.a + ([3])[-1] + { a: [4] }.a[0]").unwrap(),
+        Value::Number(Number::Int(9))
+    );
+    assert_eq!(
+        execute(r#"mut a = [[1], [2]]; a[1][0] = 5; a[0][-1] += 1; a"#).unwrap(),
+        Value::Array(vec![
+            Value::Array(vec![Value::Number(Number::Int(2))]),
+            Value::Array(vec![Value::Number(Number::Int(5))]),
+        ])
+    );
+    assert_eq!(
+        execute(r#"mut d = { a: 1 }; d["b"] = 2; ++d["a"]; d"#).unwrap(),
+        Value::Document(vec![
+            ("a".to_string(), Value::Number(Number::Int(2))),
+            ("b".to_string(), Value::Number(Number::Int(2))),
+        ])
+    );
+    assert!(execute("mut a = [1]; a[1] = 2").is_err());
+    assert!(execute(r#"mut s = "ab"; s[0] = "c""#).is_err());
+    // Blocks still evaluate to their last statement.
+    assert_eq!(
+        execute("fin name = 1; { name }").unwrap(),
+        Value::Number(Number::Int(1))
+    );
+    assert_eq!(execute("if true {} else { 1 }").unwrap(), Value::Null);
+
+    let duplicate = diagnostic(r#"{ a: 1, "a": 2 }"#);
+    assert_eq!(duplicate.code, DiagnosticCode::DuplicateField);
+    assert_eq!(duplicate.message, "'a' field is already written");
+    assert!(!parser("[1 2]").unwrap());
+    assert!(!parser("{ a: 1 b: 2 }").unwrap());
+
+    let format = |code: &str| format_code(code, "void").unwrap();
+    assert_eq!(
+        format(r#"fin x=[ 1,2 , ];fin y={a:x,"b c":{:},"if":[]}"#),
+        "fin x = [1, 2];\nfin y = { a: x, \"b c\": {:}, \"if\": [] }\n"
+    );
+    assert_eq!(
+        format("{a:[1]}.a [0]+[2][0]"),
+        "{ a: [1] }.a[0] + [2][0];\n"
+    );
+
+    assert_eq!(
+        type_errors("fin x: float[] = [1, 2.5, 3]; fin y = { a: x }"),
+        vec![]
+    );
+    assert_eq!(
+        type_errors(r#"fin x: int[] = [1, "a"]; fin y: str[] = [1]"#),
+        vec![
+            (
+                DiagnosticCode::TypeMismatch,
+                "expected 'int', found 'str'".to_string()
+            ),
+            (
+                DiagnosticCode::TypeMismatch,
+                "expected 'str[]', found 'int[]'".to_string()
+            ),
+        ]
+    );
+
+    let bson = bson::Bson::from(execute(&format!("{}user", document)).unwrap());
+    assert_eq!(
+        bson,
+        bson::Bson::Document(bson::doc! {
+            "name": "x",
+            "first tag": null,
+            "roles": ["admin"],
+            "address": { "city": "y" },
+        })
+    );
+}
+
+#[test]
+fn test_numeric_literals() {
+    let number = |code: &str| match execute(code).unwrap() {
+        Value::Number(number) => (number.get_type_name(), number.to_string()),
+        value => panic!("{} is not a number", value),
+    };
+    assert_eq!(number("42"), ("int", "42".to_string()));
+    assert_eq!(number("0xFF + 0b1010"), ("int", "265".to_string()));
+    assert_eq!(number("0o17"), ("int", "15".to_string()));
+    assert_eq!(number("1_000_000"), ("int", "1000000".to_string()));
+    assert_eq!(
+        number("18446744073709551615"),
+        ("uint", "18446744073709551615".to_string())
+    );
+    assert_eq!(number("1e9"), ("float", "1000000000".to_string()));
+    assert_eq!(number("2.5E-1"), ("float", "0.25".to_string()));
+    assert_eq!(number("19.90m"), ("decimal", "19.90".to_string()));
+    assert_eq!(number("0.1m + 0.2m"), ("decimal", "0.3".to_string()));
+    assert_eq!(number("10m / 4"), ("decimal", "2.5".to_string()));
+    assert_eq!(
+        number("1m / 3"),
+        ("decimal", "0.3333333333333333333333333333".to_string())
+    );
+    assert_eq!(number("1.1m ^ 2"), ("decimal", "1.21".to_string()));
+    assert_eq!(
+        number("2m ^ 90"),
+        ("decimal", "1237940039285380274899124224".to_string())
+    );
+    assert_eq!(
+        number("(-1m) ^ 400000001 + 1m ^ 400000000"),
+        ("decimal", "0".to_string())
+    );
+    assert_eq!(number("0.1m ^ 400000000"), ("decimal", "0".to_string()));
+    assert!(execute("1.5m ^ 400000000").is_err());
+    assert_eq!(number("7 / 2"), ("float", "3.5".to_string()));
+    assert_eq!(number("fin x = 5; x-1"), ("int", "4".to_string()));
+    assert_eq!(number("fin x = -5; x - -1"), ("int", "-4".to_string()));
+    assert_eq!(
+        number(r#"decimal("4.50") * 2"#),
+        ("decimal", "9.00".to_string())
+    );
+    assert_eq!(number(r#"int("0x10")"#), ("int", "16".to_string()));
+    assert_eq!(execute("0.1 + 0.2 == 0.3").unwrap(), Value::Boolean(false));
+    assert_eq!(
+        execute("0.1m + 0.2m == 0.3m").unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        execute("1 == 1.0 && 2.50m == 2.5").unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        number("9223372036854775807 + 9223372036854775808"),
+        ("uint", "18446744073709551615".to_string())
+    );
+    assert!(execute("18446744073709551615 + 1").is_err());
+    assert!(execute("1m / 0").is_err());
+
+    assert_eq!(
+        token_types("1_000 0x1F 0b11 1.5e-3 2m"),
+        vec![TokenType::Number; 5]
+    );
+    assert_eq!(
+        token_types("a-1"),
+        vec![
+            TokenType::Alphanumeric,
+            TokenType::Subtraction,
+            TokenType::Number
+        ]
+    );
+    assert_eq!(
+        token_types("=-1"),
+        vec![TokenType::Assign, TokenType::Subtraction, TokenType::Number]
+    );
+    let out_of_range = |code: &str| {
+        let diagnostic = Lexer::new(code.to_string()).analyze().unwrap_err();
+        (diagnostic.code, diagnostic.message, diagnostic.span)
+    };
+    assert_eq!(
+        out_of_range("x = 18446744073709551616"),
+        (
+            DiagnosticCode::NumberOutOfRange,
+            "18446744073709551616 is out of range of 'uint'".to_string(),
+            Span::new(4, 24, 1, 5)
+        )
+    );
+    let negative = diagnostic("x = -9223372036854775809");
+    assert_eq!(
+        (negative.code, negative.message, negative.span),
+        (
+            DiagnosticCode::NumberOutOfRange,
+            "-9223372036854775809 is out of range of 'int'".to_string(),
+            Span::new(4, 24, 1, 5)
+        )
+    );
+    assert_eq!(
+        number("-9223372036854775808"),
+        ("int", "-9223372036854775808".to_string())
+    );
+    assert_eq!(out_of_range("1e999").1, "1e999 is out of range of 'float'");
+    assert_eq!(
+        out_of_range("0x1_0000_0000_0000_0000").1,
+        "0x1_0000_0000_0000_0000 is out of range of 'uint'"
+    );
+    assert_eq!(
+        out_of_range("99999999999999999999999999999m").1,
+        "99999999999999999999999999999m is out of range of 'decimal'"
+    );
+    assert_eq!(
+        out_of_range("0b102"),
+        (
+            DiagnosticCode::InvalidDigit,
+            "'2' is not a digit of binary numbers".to_string(),
+            Span::new(4, 5, 1, 5)
+        )
+    );
+    assert_eq!(
+        out_of_range("0b2").1,
+        "'2' is not a digit of binary numbers"
+    );
+    assert_eq!(out_of_range("0o9").1, "'9' is not a digit of octal numbers");
+    assert_eq!(
+        out_of_range("0xg").1,
+        "'g' is not a digit of hexadecimal numbers"
+    );
+    assert_eq!(out_of_range("0xG").0, DiagnosticCode::InvalidDigit);
+    assert_eq!(out_of_range("12ab").0, DiagnosticCode::AlphanumericInNumber);
+
+    let format = |code: &str| format_code(code, "void").unwrap();
+    assert_eq!(
+        format("fin a = [0x10, 1_0, 1.0, 1e20, 2.50m, -3]"),
+        "fin a = [16, 10, 1.0, 1e20, 2.50m, -3];\n"
+    );
+    assert_eq!(format("fin b = -2^2"), "fin b = -2 ^ 2;\n");
+    assert_eq!(format("fin c = - -x"), "fin c = - -x;\n");
+    assert_eq!(format("fin d = -(a+b)"), "fin d = -(a + b);\n");
+
+    let table = "table products { price: decimal = 0m, stock: uint = 0 }; ";
+    assert_eq!(
+        type_errors(&format!(
+            "{}fin price: decimal = 1 + 2.5m; products(2.5m, 3)",
+            table
+        )),
+        vec![]
+    );
+    assert_eq!(
+        type_errors(&format!(
+            "{}fin total: decimal = 1.5; fin f: int = 2 / 2",
+            table
+        )),
+        vec![
+            (
+                DiagnosticCode::TypeMismatch,
+                "expected 'decimal', found 'float'".to_string()
+            ),
+            (
+                DiagnosticCode::TypeMismatch,
+                "expected 'int', found 'float'".to_string()
+            ),
+        ]
+    );
+
+    let bson = bson::Bson::from(execute("[1, 2.5, 19.99m, 18446744073709551615]").unwrap());
+    assert_eq!(
+        bson,
+        bson::Bson::Array(vec![
+            bson::Bson::Int64(1),
+            bson::Bson::Double(2.5),
+            bson::Bson::Decimal128("19.99".parse().unwrap()),
+            bson::Bson::Decimal128("18446744073709551615".parse().unwrap()),
+        ])
+    );
+
+    assert_eq!(
+        execute("18446744073709551615 * 2").unwrap_err().to_string(),
+        "Runtime Error: the result of '*' is out of range"
+    );
+    let overflows = |code: &str| execute(code).unwrap_err().to_string();
+    assert_eq!(
+        overflows("9223372036854775807 + 1"),
+        "Runtime Error: the result of '+' is out of range"
+    );
+    assert_eq!(
+        overflows("-9223372036854775807 - 2"),
+        "Runtime Error: the result of '-' is out of range"
+    );
+    assert_eq!(
+        overflows("4611686018427387904 * 2"),
+        "Runtime Error: the result of '*' is out of range"
+    );
+}
+
+fn type_errors(code: &str) -> Vec<(DiagnosticCode, String)> {
+    match TypeChecker::new("void").check(&resolved(code).unwrap()) {
+        Ok(()) => vec![],
+        Err(diagnostics) => diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.code, diagnostic.message.clone()))
+            .collect(),
+    }
+}
+
+#[test]
+fn test_type_checker() {
+    let well_typed = r#"
+        enum Role: int { Admin, User }
+        table users { id: int, name: str, role: Role = Role.User }
+        function greet(name: str, times: int = 1): str { return name + "!"; }
+        function double(x: float) { x * 2 }
+        fin greeting = greet("Ann", times = 2);
+        mut total: float = double(2);
+        total += 1;
+        fin level: int = Role.Admin;
+        for user of &users { fin name: str = user.name; }
+        if total > 2 && greeting == "Ann!" { total = 0; }
+    "#;
+    assert_eq!(type_errors(well_typed), vec![]);
+
+    let mismatch = |message: &str| (DiagnosticCode::TypeMismatch, message.to_string());
+    let arguments = |message: &str| (DiagnosticCode::InvalidArguments, message.to_string());
+    assert_eq!(
+        type_errors("fin x: int = \"one\"; mut y = 1; y = true;"),
+        vec![
+            mismatch("expected 'int', found 'str'"),
+            mismatch("expected 'int', found 'bool'")
+        ]
+    );
+    assert_eq!(
+        type_errors("function f(a: int, b: str = \"\"): str { return a; } f(\"x\"); f(b = 1, c = 2); f(1, \"\", 3);"),
+        vec![
+            mismatch("expected 'str', found 'int'"),
+            mismatch("expected 'int', found 'str'"),
+            arguments("'f' has no argument 'c'"),
+            arguments("'f' is missing argument 'a'"),
+            mismatch("expected 'str', found 'int'"),
+            arguments("'f' takes 2 arguments but 3 were given"),
+        ]
+    );
+    assert_eq!(
+        type_errors("fin p: point = 1; while 1 {} for i of 5 {}"),
+        vec![
+            (
+                DiagnosticCode::UnknownType,
+                "unknown type 'point'".to_string()
+            ),
+            mismatch("expected 'bool', found 'int'"),
+            mismatch("'int' cannot be iterated over"),
+        ]
+    );
+
+    let diagnostic = &TypeChecker::new("void")
+        .check(&parse("mut count = 0;\ncount = \"many\";").unwrap())
+        .unwrap_err()[0];
+    assert_eq!(diagnostic.span, Span::new(15, 20, 2, 1));
+    assert_eq!(
+        diagnostic.span_label.as_deref(),
+        Some("the assigned value has to be 'int'")
+    );
+    assert_eq!(
+        Type::Array(Box::new(Type::Row("users".into()))).to_string(),
+        "&users[]"
+    );
+    assert!(
+        handling::execute(&mut PackageLoader::new(""), "fin x: bool = 1;".to_string()).is_err()
+    );
+
+    // Negative literals can't be 'uint', and other negative values are stopped at runtime.
+    assert_eq!(
+        type_errors("fin x: uint = -5; function f(n: uint) { n }; f(-1); fin y: uint = 5;"),
+        vec![
+            mismatch("expected 'uint', found -5"),
+            mismatch("expected 'uint', found -1")
+        ]
+    );
+    assert!(execute("fin a = -5; fin x: uint = a;")
+        .unwrap_err()
+        .to_string()
+        .ends_with("-5 cannot be stored in 'x', which is declared as 'uint'"));
+    assert!(execute("mut x: uint = 1; x = x - 2;").is_err());
+    assert!(matches!(
+        execute("mut x: float = 1; x").unwrap(),
+        Value::Number(Number::Float(_))
+    ));
+    assert!(execute("function f(n: str) { n }; f(1)").is_err());
+
+    // Integers divided in place stay integers.
+    assert_eq!(
+        type_errors("mut x = 1; x /= 2; mut y: uint = 9; y /= x;"),
+        vec![]
+    );
+    assert!(matches!(
+        execute("mut x = 7; x /= 2; x").unwrap(),
+        Value::Number(Number::Int(3))
+    ));
+    assert!(matches!(
+        execute("mut x: uint = 9; x /= 2; x").unwrap(),
+        Value::Number(Number::Uint(4))
+    ));
+    assert!(matches!(
+        execute("mut x = 7.0; x /= 2; x").unwrap(),
+        Value::Number(Number::Float(_))
+    ));
+    assert!(execute("mut x: uint = 3; x /= -1;").is_err());
+
+    // Columns and variables of enums take the values of the variants, which are checked at runtime.
+    let colors = "enum Color: str { Red, Blue }; table paints { color: Color }; ";
+    assert_eq!(
+        type_errors(&format!(
+            r#"{}paints(color = "Red"); fin c: Color = "Blue";"#,
+            colors
+        )),
+        vec![]
+    );
+    assert!(execute(&format!(r#"{}paints(color = "Red")"#, colors)).is_ok());
+    assert!(execute(&format!(r#"{}paints(color = "Green")"#, colors)).is_err());
+    assert!(execute(&format!(r#"{}fin c: Color = "Green";"#, colors)).is_err());
+    assert_eq!(
+        type_errors(&format!("{}paints(color = 1)", colors)),
+        vec![mismatch("expected 'Color', found 'int'")]
+    );
+}
+
+fn resolved_diagnostics(code: &str) -> Diagnostics {
+    Resolver::new("void").resolve(&mut parse(code).unwrap())
+}
+
+fn resolve(code: &str) -> Vec<(Severity, DiagnosticCode, String, Span)> {
+    resolved_diagnostics(code)
+        .iter()
+        .map(|diagnostic| {
+            let message = diagnostic.message.clone();
+            (
+                diagnostic.severity,
+                diagnostic.code,
+                message,
+                diagnostic.span,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_generic_functions() {
+    let body =
+        parse("function first<T, U>(items: T[], other: U): T { null }; first<int, str>(x, \"\")")
+            .unwrap();
+    let function = body.nodes[0]
+        .downcast_ref::<FunctionDeclarationNode>()
+        .unwrap();
+    assert_eq!(function.get_generics(), ["T", "U"]);
+    assert_eq!(function.get_arguments()[0].get_datatype(), Some("T[]"));
+    let call = body.nodes[1].downcast_ref::<CallNode>().unwrap();
+    let callee = call.get_callee().downcast_ref::<IdentifierNode>().unwrap();
+    let type_names: Vec<&str> = callee
+        .get_types()
+        .iter()
+        .map(|datatype| datatype.get_name())
+        .collect();
+    assert_eq!(type_names, ["int", "str"]);
+    // Without a '(' after the '>' it stays a comparison.
+    assert!(parse("a < b > c").unwrap().nodes[0]
+        .downcast_ref::<BinaryOperatorNode>()
+        .is_some());
+
+    let first = "table users { name: str } function first<T>(items: T[]): T { for item of items { return item; }; null }";
+    assert_eq!(
+        type_errors(&format!(
+            "{} fin user: &users = first(&users); fin name: str = user.name;",
+            first
+        )),
+        vec![]
+    );
+    assert_eq!(
+        type_errors(&format!("{} fin name: str = first<str>(&users);", first)),
+        vec![(
+            DiagnosticCode::TypeMismatch,
+            "expected 'str[]', found '&users[]'".to_string()
+        ),]
+    );
+    assert_eq!(
+        type_errors(&format!(
+            "{} fin count: int = first(&users); first<int, str>(&users);",
+            first
+        )),
+        vec![
+            (
+                DiagnosticCode::TypeMismatch,
+                "expected 'int', found '&users'".to_string()
+            ),
+            (
+                DiagnosticCode::InvalidArguments,
+                "'first' takes 1 type arguments but 2 were given".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        type_errors("function f<T>(x: T): T { fin y: T = 1; x }"),
+        vec![(
+            DiagnosticCode::TypeMismatch,
+            "expected 'T', found 'int'".to_string()
+        ),]
+    );
+    assert_eq!(
+        format_code(
+            "function first<T,U>(items: T[], other:U): T { f<int>(1) }",
+            "void"
+        )
+        .unwrap(),
+        "function first<T, U>(items: T[], other: U): T {\n    f<int>(1);\n}\n"
+    );
+}
+
+#[test]
+fn test_resolver() {
+    let resolved = r#"
+        enum Role { Admin, User }
+        function count(limit: int) { mut i = 0; while i < limit { ++i; if i > 5 { break } }; i }
+        function admin() { mut role = Role.Admin; role = fallback; role }
+        fin fallback = Role.User;
+        for user of &users { continue }
+        event greet (&users, "insert") { fin name = new.name; &users.role = Role.User; }
+        count(limit = 3)
+    "#;
+    assert_eq!(resolve(resolved), vec![]);
+
+    let error = |code, message: &str, span| (Severity::Error, code, message.to_string(), span);
+    assert_eq!(
+        resolve("x + 1; f(); Color.Red"),
+        vec![
+            error(
+                DiagnosticCode::UndefinedName,
+                "'x' is not defined",
+                Span::new(0, 1, 1, 1)
+            ),
+            error(
+                DiagnosticCode::UndefinedName,
+                "function 'f' is not defined",
+                Span::new(7, 8, 1, 8)
+            ),
+            error(
+                DiagnosticCode::UndefinedName,
+                "'Color' is not defined",
+                Span::new(12, 17, 1, 13)
+            ),
+        ]
+    );
+    assert_eq!(
+        resolve("function f() { x = 2 }; fin x = 1; fin x = 3; break;"),
+        vec![
+            error(
+                DiagnosticCode::ImmutableAssignment,
+                "cannot assign twice to immutable variable 'x'",
+                Span::new(15, 16, 1, 16)
+            ),
+            error(
+                DiagnosticCode::DuplicateDeclaration,
+                "variable 'x' is already declared in this scope",
+                Span::new(39, 40, 1, 40)
+            ),
+            error(
+                DiagnosticCode::StrayLoopControl,
+                "loop control is used outside of a loop",
+                Span::new(46, 51, 1, 47)
+            ),
+        ]
+    );
+    assert_eq!(
+        resolve("while true { function f() { continue } }"),
+        vec![error(
+            DiagnosticCode::StrayLoopControl,
+            "loop control is used outside of a loop",
+            Span::new(28, 36, 1, 29)
+        )]
+    );
+
+    assert_eq!(
+        resolve("fin a = [1]; a[0] = 2; [1][0] = 2; a.b = 2"),
+        vec![
+            error(
+                DiagnosticCode::ImmutableAssignment,
+                "cannot assign twice to immutable variable 'a'",
+                Span::new(13, 14, 1, 14)
+            ),
+            error(
+                DiagnosticCode::InvalidAssignment,
+                "only variables and their items can be assigned to",
+                Span::new(23, 33, 1, 24)
+            ),
+            error(
+                DiagnosticCode::InvalidAssignment,
+                "only variables and their items can be assigned to",
+                Span::new(35, 42, 1, 36)
+            ),
+        ]
+    );
+
+    let shadowing = resolve("mut total = 0; { fin total = 1; }");
+    assert_eq!(shadowing.len(), 1);
+    assert_eq!(shadowing[0].0, Severity::Warning);
+    assert_eq!(shadowing[0].1, DiagnosticCode::ShadowedName);
+    assert!(resolve("import shop:all; discount(price)").is_empty());
+    let mut loader = PackageLoader::new("");
+    let code = "mut total = 0; { fin total = 1; }; total".to_string();
+    assert!(handling::execute(&mut loader, code).is_ok());
+    assert!(handling::execute(&mut loader, "total".to_string()).is_err());
+}
+
 #[test]
 fn test_cteate_db() {
     let is_create = create_db::create_db_structure("./db".trim()).is_ok();
     assert!(is_create);
 }
 
+fn manage_config(code: &str) -> std::io::Result<ManageConfig> {
+    ManageConfig::from_body(&parse(code)?)
+}
+
+#[test]
+fn test_manage_config() {
+    let config = manage_config(
+        r#"manage (
+            packages = "./packages",
+            max_connections = 8,
+            port = "6980",
+            host = "127.0.0.1",
+            work_dir = "/",
+            backups_dir = "backups/",
+        );"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config,
+        ManageConfig {
+            packages: Some("./packages".to_string()),
+            max_connections: Some(8),
+            host: Some("127.0.0.1".to_string()),
+            port: Some(6980),
+            work_dir: Some("/".to_string()),
+            backups_dir: Some("backups/".to_string()),
+        }
+    );
+    let manager_file = std::path::Path::new("db/datablaze/manage.blz");
+    assert_eq!(
+        config.get_work_dir(manager_file),
+        std::path::Path::new("db/datablaze")
+    );
+    assert_eq!(
+        config.get_backups_dir(manager_file),
+        std::path::Path::new("db/datablaze/backups/")
+    );
+    let config = manage_config(r#"manage (work_dir = "/store", packages = "model")"#).unwrap();
+    assert_eq!(
+        config.get_packages_dir(manager_file),
+        Some(std::path::PathBuf::from("db/datablaze/store/model"))
+    );
+    assert_eq!(
+        config.get_backups_dir(manager_file),
+        std::path::Path::new("db/datablaze/store/backups")
+    );
+    assert_eq!(manage_config("manage (port = 80)").unwrap().port, Some(80));
+    assert_eq!(manage_config("manage ()").unwrap(), ManageConfig::default());
+
+    assert_eq!(
+        manage_config("manage (tmax_connections = 3)")
+            .unwrap()
+            .max_connections,
+        Some(3)
+    );
+    assert!(manage_config("manage (max_connections = 3, tmax_connections = 3)").is_err());
+    assert!(manage_config("manage (max_connections = \"3\")").is_err());
+    assert!(manage_config("manage (max_connections = 2.5)").is_err());
+    assert!(manage_config("manage (port = 70000)").is_err());
+    assert!(manage_config("manage (port = \"http\")").is_err());
+    assert!(manage_config("manage (host = 127)").is_err());
+    assert!(manage_config("manage (\"127.0.0.1\")").is_err());
+    assert!(manage_config("manage (port = 1, port = 2)").is_err());
+    assert!(manage_config("manage (port = 1); manage (port = 2)").is_err());
+    assert_eq!(
+        manage_config("fin port = 1").unwrap_err().to_string(),
+        "Configuration Error: no manage block is declared"
+    );
+
+    let manage_path = std::env::temp_dir().join("blaze_test_manage.blz");
+    std::fs::write(&manage_path, "manage (port = 6980, max_connections = 3);").unwrap();
+    let config = ManageConfig::load(manage_path.to_str().unwrap()).unwrap();
+    let server_config = Config::from_manage_config(&config)
+        .apply_arguments(&["run".to_string(), "-port".to_string(), "7000".to_string()])
+        .unwrap();
+    assert_eq!(server_config.port, "7000");
+    assert_eq!(server_config.host, "localhost");
+    assert_eq!(server_config.max_connections, 3);
+    let arguments = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        Config::parse_arguments(args)
+    };
+    assert_eq!(
+        arguments(&["run", "-max_connections", "many"])
+            .err()
+            .unwrap()
+            .to_string(),
+        "Argument Error: '-max_connections' must be a positive number, found 'many'"
+    );
+    assert!(arguments(&["run", "-port", "70000"]).is_err());
+    assert!(arguments(&["run", "-port"]).is_err());
+
+    // Older manage.blz files still load, and their inspections are only warned about.
+    let manage_code = r#"
+        manage (
+            packages = "./packages",
+            max_connections = 8,
+            port = "6980",
+            host = "127.0.0.1"
+        );
+
+        import users:all, animals:species;
+
+        inspect all;
+
+        attach "./data";
+    "#;
+    std::fs::write(&manage_path, manage_code).unwrap();
+    let config = ManageConfig::load(manage_path.to_str().unwrap()).unwrap();
+    assert_eq!(config.packages, Some("./packages".to_string()));
+    assert_eq!(config.max_connections, Some(8));
+    assert_eq!(config.port, Some(6980));
+    assert_eq!(config.host, Some("127.0.0.1".to_string()));
+    assert_eq!(
+        resolve("inspect all, users;"),
+        vec![(
+            Severity::Warning,
+            DiagnosticCode::DeprecatedStatement,
+            "'inspect' is deprecated and has no effect".to_string(),
+            Span::new(0, 7, 1, 1)
+        )]
+    );
+    assert!(execute("inspect all; 1").is_ok());
+    assert_eq!(
+        format_code("inspect  all,users", "void").unwrap(),
+        "inspect all, users;\n"
+    );
+}
+
+fn package_dir(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+    let directory = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&directory);
+    for (path, code) in files {
+        let path = directory.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, code).unwrap();
+    }
+    directory
+}
+
+#[test]
+fn test_package_loader() {
+    let directory = package_dir(
+        "blaze_test_packages",
+        &[
+            (
+                "packages/shop/products.blz",
+                "package shop;\nimport units:all;\nfin price = 2 * unit;",
+            ),
+            (
+                "packages/shop/orders.blz",
+                "package shop;\nfunction total(count: int) { return count * price; };",
+            ),
+            ("packages/units.blz", "fin unit = 5;\nattach \"../data\";"),
+            ("manage.blz", "import shop:total;\nfin result = total(3);"),
+        ],
+    );
+    let mut loader = PackageLoader::new(directory.join("packages"));
+    loader.load_packages().unwrap();
+    assert_eq!(loader.get_package_modules("shop").unwrap().len(), 2);
+
+    let index = loader.load_entry(&directory.join("manage.blz")).unwrap();
+    assert_eq!(
+        loader.load_module(&directory.join("manage.blz")).unwrap(),
+        index
+    );
+    let symbols = loader.get_imported_symbols(index).unwrap();
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].get_package(), "shop");
+    assert_eq!(symbols[0].get_kind(), &SymbolKind::Function);
+
+    let order = loader.get_execution_order(index).unwrap();
+    let names: Vec<String> = order
+        .iter()
+        .map(|module| {
+            module
+                .get_path()
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(names, vec!["units", "orders", "products", "manage"]);
+    assert_eq!(
+        order[0].get_attachments(),
+        vec![order[0].get_path().parent().unwrap().join("../data")]
+    );
+
+    let mut executor = Executor::new();
+    loader.execute_module(index, &mut executor).unwrap();
+    assert_eq!(
+        executor.get_variable("result"),
+        Some(Value::Number(Number::Int(30)))
+    );
+
+    // The way the server starts a datablaze: the packages are found through manage.blz,
+    // and the tables they declare can be called from manage.blz and from shell code.
+    let directory = package_dir(
+        "blaze_test_package_entry",
+        &[
+            (
+                "model/accounts.blz",
+                "package bank;\ntable accounts { name: str };\nfin bonus = 10;",
+            ),
+            (
+                "manage.blz",
+                "manage (port = 6980);\nimport bank:all;\naccounts(name = \"x\")",
+            ),
+        ],
+    );
+    let manager_file = directory.join("manage.blz");
+    let manage_config = ManageConfig::load(manager_file.to_str().unwrap()).unwrap();
+    let mut loader = handling::load_packages(&manager_file, &manage_config);
+    assert_eq!(
+        handling::run_manage_file(&mut loader, &manager_file).unwrap(),
+        Value::Document(vec![("name".to_string(), Value::String("x".to_string()))])
+    );
+    let code = "import bank:bonus;\nbonus * 2".to_string();
+    assert_eq!(
+        handling::execute(&mut loader, code).unwrap(),
+        Value::Number(Number::Int(20))
+    );
+    assert!(handling::execute(&mut loader, "bonus".to_string()).is_err());
+
+    let directory = package_dir(
+        "blaze_test_package_cycle",
+        &[
+            ("a.blz", "import b:all;\nfin x = 1;"),
+            ("b.blz", "import a:x;\nfin y = 2;"),
+        ],
+    );
+    let error = PackageLoader::new(&directory).load_packages().unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("import cycle detected: a -> b -> a"));
+
+    let directory = package_dir(
+        "blaze_test_package_symbols",
+        &[("a.blz", "fin x = 1;"), ("b.blz", "import a:y;")],
+    );
+    assert!(PackageLoader::new(&directory).load_packages().is_err());
+
+    let directory = package_dir(
+        "blaze_test_package_duplicates",
+        &[
+            ("one.blz", "package a;\nfin x = 1;"),
+            ("two.blz", "package a;\nfin x = 2;"),
+        ],
+    );
+    assert!(PackageLoader::new(&directory).load_packages().is_err());
+
+    let directory = package_dir(
+        "blaze_test_package_snippets",
+        &[
+            ("fine.blz", "fin x = 1;"),
+            ("broken.blz", "package shop;\nfin price = 2 *;"),
+        ],
+    );
+    let mut loader = PackageLoader::new(&directory);
+    let error = loader.load_packages().unwrap_err();
+    let rendered = Diagnostics::from_io_error(&error)
+        .unwrap()
+        .render_plain(loader.get_sources());
+    assert!(rendered.contains("broken.blz:2:16"));
+    assert!(rendered.contains("2 | fin price = 2 *;\n  |                ^ expected"));
+}
+
 #[test]
 fn test_header_parser() {
     let response = "POST / HTTP/1.1\nHost: localhost:3300\nUser-Agent: curl/8.7.1\nAccept: */*\nPassword: 1221\n"